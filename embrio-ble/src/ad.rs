@@ -0,0 +1,278 @@
+//! Advertising data, a sequence of length prefixed AD structures.
+
+pub const FLAGS: u8 = 0x01;
+pub const INCOMPLETE_SERVICE_UUIDS_16: u8 = 0x02;
+pub const COMPLETE_SERVICE_UUIDS_16: u8 = 0x03;
+pub const SHORTENED_LOCAL_NAME: u8 = 0x08;
+pub const COMPLETE_LOCAL_NAME: u8 = 0x09;
+pub const TX_POWER_LEVEL: u8 = 0x0A;
+pub const SERVICE_DATA_16: u8 = 0x16;
+pub const MANUFACTURER_SPECIFIC_DATA: u8 = 0xFF;
+
+pub const LE_LIMITED_DISCOVERABLE: u8 = 0x01;
+pub const LE_GENERAL_DISCOVERABLE: u8 = 0x02;
+pub const BR_EDR_NOT_SUPPORTED: u8 = 0x04;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The data ends part way through a structure.
+    Truncated,
+    /// A structure is too short for its type.
+    Invalid,
+    /// The structures don't fit in the buffer.
+    TooLong,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AdStructure<'a> {
+    Flags(u8),
+    /// Little endian 16-bit service UUIDs.
+    ServiceUuids16 {
+        complete: bool,
+        uuids: &'a [u8],
+    },
+    ShortenedLocalName(&'a str),
+    CompleteLocalName(&'a str),
+    /// In dBm.
+    TxPowerLevel(i8),
+    ServiceData16 {
+        uuid: u16,
+        data: &'a [u8],
+    },
+    ManufacturerSpecificData {
+        company: u16,
+        data: &'a [u8],
+    },
+    /// Any other type, or a name that isn't valid UTF-8.
+    Other {
+        ty: u8,
+        data: &'a [u8],
+    },
+}
+
+/// An iterator over the structures in some advertising data, see [`parse`].
+#[derive(Debug, Clone)]
+pub struct AdStructures<'a> {
+    data: &'a [u8],
+}
+
+/// Parses `data`, any error ends the iteration.
+pub fn parse(data: &[u8]) -> AdStructures<'_> {
+    AdStructures { data }
+}
+
+/// Encodes `structures` one after another into `buf`, returning the number
+/// of bytes written.
+pub fn encode(
+    structures: &[AdStructure<'_>],
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let mut len = 0;
+    for structure in structures {
+        len += structure.encode(&mut buf[len..])?;
+    }
+    Ok(len)
+}
+
+fn u16_le(data: &[u8]) -> u16 {
+    u16::from(data[0]) | u16::from(data[1]) << 8
+}
+
+impl<'a> AdStructure<'a> {
+    fn decode(ty: u8, data: &'a [u8]) -> Result<Self, Error> {
+        let name = |data| core::str::from_utf8(data).ok();
+        Ok(match ty {
+            FLAGS => AdStructure::Flags(*data.first().ok_or(Error::Invalid)?),
+            INCOMPLETE_SERVICE_UUIDS_16 | COMPLETE_SERVICE_UUIDS_16 => {
+                if data.len() % 2 != 0 {
+                    return Err(Error::Invalid);
+                }
+                AdStructure::ServiceUuids16 {
+                    complete: ty == COMPLETE_SERVICE_UUIDS_16,
+                    uuids: data,
+                }
+            }
+            SHORTENED_LOCAL_NAME if name(data).is_some() => {
+                AdStructure::ShortenedLocalName(name(data).unwrap())
+            }
+            COMPLETE_LOCAL_NAME if name(data).is_some() => {
+                AdStructure::CompleteLocalName(name(data).unwrap())
+            }
+            TX_POWER_LEVEL => AdStructure::TxPowerLevel(
+                *data.first().ok_or(Error::Invalid)? as i8,
+            ),
+            SERVICE_DATA_16 | MANUFACTURER_SPECIFIC_DATA => {
+                if data.len() < 2 {
+                    return Err(Error::Invalid);
+                }
+                let (id, data) = (u16_le(data), &data[2..]);
+                if ty == SERVICE_DATA_16 {
+                    AdStructure::ServiceData16 { uuid: id, data }
+                } else {
+                    AdStructure::ManufacturerSpecificData { company: id, data }
+                }
+            }
+            ty => AdStructure::Other { ty, data },
+        })
+    }
+
+    /// The 16-bit UUIDs of a `ServiceUuids16` structure.
+    pub fn uuids(&self) -> impl Iterator<Item = u16> + 'a {
+        let uuids = match *self {
+            AdStructure::ServiceUuids16 { uuids, .. } => uuids,
+            _ => &[],
+        };
+        uuids.chunks(2).map(u16_le)
+    }
+
+    /// Returns the number of bytes written to `buf`.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let single;
+        let (ty, id, data): (u8, Option<u16>, &[u8]) = match *self {
+            AdStructure::Flags(flags) => {
+                single = [flags];
+                (FLAGS, None, &single)
+            }
+            AdStructure::ServiceUuids16 { complete, uuids } => {
+                let ty = if complete {
+                    COMPLETE_SERVICE_UUIDS_16
+                } else {
+                    INCOMPLETE_SERVICE_UUIDS_16
+                };
+                (ty, None, uuids)
+            }
+            AdStructure::ShortenedLocalName(name) => {
+                (SHORTENED_LOCAL_NAME, None, name.as_bytes())
+            }
+            AdStructure::CompleteLocalName(name) => {
+                (COMPLETE_LOCAL_NAME, None, name.as_bytes())
+            }
+            AdStructure::TxPowerLevel(level) => {
+                single = [level as u8];
+                (TX_POWER_LEVEL, None, &single)
+            }
+            AdStructure::ServiceData16 { uuid, data } => {
+                (SERVICE_DATA_16, Some(uuid), data)
+            }
+            AdStructure::ManufacturerSpecificData { company, data } => {
+                (MANUFACTURER_SPECIFIC_DATA, Some(company), data)
+            }
+            AdStructure::Other { ty, data } => (ty, None, data),
+        };
+
+        let id_len = if id.is_some() { 2 } else { 0 };
+        let len = 2 + id_len + data.len();
+        if len - 1 > usize::from(u8::max_value()) || buf.len() < len {
+            return Err(Error::TooLong);
+        }
+        buf[0] = (len - 1) as u8;
+        buf[1] = ty;
+        if let Some(id) = id {
+            buf[2] = id as u8;
+            buf[3] = (id >> 8) as u8;
+        }
+        buf[2 + id_len..len].copy_from_slice(data);
+        Ok(len)
+    }
+}
+
+impl<'a> Iterator for AdStructures<'a> {
+    type Item = Result<AdStructure<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = usize::from(*self.data.first()?);
+        // A zero length structure marks the end of the significant part,
+        // anything after it is padding
+        if len == 0 {
+            self.data = &[];
+            return None;
+        }
+        let result = match self.data.get(1..=len) {
+            Some(structure) => {
+                self.data = &self.data[len + 1..];
+                AdStructure::decode(structure[0], &structure[1..])
+            }
+            None => Err(Error::Truncated),
+        };
+        if result.is_err() {
+            self.data = &[];
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let structures = [
+            AdStructure::Flags(LE_GENERAL_DISCOVERABLE | BR_EDR_NOT_SUPPORTED),
+            AdStructure::CompleteLocalName("embrio"),
+            AdStructure::ServiceUuids16 {
+                complete: true,
+                uuids: &[0x0F, 0x18, 0x0A, 0x18],
+            },
+            AdStructure::TxPowerLevel(-4),
+            AdStructure::ManufacturerSpecificData {
+                company: 0x0059,
+                data: &[1, 2],
+            },
+            AdStructure::ServiceData16 {
+                uuid: 0x180F,
+                data: &[100],
+            },
+            AdStructure::Other {
+                ty: 0x19,
+                data: &[0x40, 0x00],
+            },
+        ];
+        let mut buf = [0; 64];
+        let len = encode(&structures, &mut buf).unwrap();
+        assert_eq!(&buf[..3], &[0x02, FLAGS, 0x06]);
+        assert_eq!(len, 3 + 8 + 6 + 3 + 6 + 5 + 4);
+
+        let mut parsed = parse(&buf[..len]);
+        for structure in &structures {
+            assert_eq!(parsed.next(), Some(Ok(*structure)));
+        }
+        assert_eq!(parsed.next(), None);
+        let mut uuids = structures[2].uuids();
+        assert_eq!(uuids.next(), Some(0x180F));
+        assert_eq!(uuids.next(), Some(0x180A));
+        assert_eq!(uuids.next(), None);
+    }
+
+    #[test]
+    fn stops_at_padding() {
+        let data = [0x02, FLAGS, 0x06, 0x00, 0xFF, 0xFF];
+        let mut parsed = parse(&data);
+        assert_eq!(parsed.next(), Some(Ok(AdStructure::Flags(0x06))));
+        assert_eq!(parsed.next(), None);
+    }
+
+    #[test]
+    fn errors() {
+        let mut parsed = parse(&[0x05, COMPLETE_LOCAL_NAME, b'a']);
+        assert_eq!(parsed.next(), Some(Err(Error::Truncated)));
+        assert_eq!(parsed.next(), None);
+
+        let mut parsed = parse(&[0x02, SERVICE_DATA_16, 0x0F, 0x02, 0x01, 6]);
+        assert_eq!(parsed.next(), Some(Err(Error::Invalid)));
+        assert_eq!(parsed.next(), None);
+
+        assert_eq!(
+            parse(&[0x03, COMPLETE_LOCAL_NAME, 0xFF, 0xFE]).next(),
+            Some(Ok(AdStructure::Other {
+                ty: COMPLETE_LOCAL_NAME,
+                data: &[0xFF, 0xFE],
+            }))
+        );
+
+        assert_eq!(
+            AdStructure::CompleteLocalName("embrio").encode(&mut [0; 7]),
+            Err(Error::TooLong)
+        );
+    }
+}
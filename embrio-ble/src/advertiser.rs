@@ -0,0 +1,130 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::timer::Timer;
+use futures_core::stream::Stream;
+use futures_util::ready;
+
+use crate::{
+    link::Channel,
+    pdu::{self, Pdu, MAX_LEN},
+    radio::Radio,
+    Error,
+};
+
+/// A [`Stream`] of advertising events, sending a PDU once on each advertising
+/// channel on each tick of an interval.
+///
+/// The random `advDelay` added to each interval by the specification is not
+/// applied, so two advertisers with the same period may keep colliding.
+pub struct Advertiser<R: Radio, T: Timer> {
+    radio: R,
+    interval: T::Interval,
+    pdu: [u8; MAX_LEN],
+    len: usize,
+    /// Index into `Channel::ADVERTISING` during an event.
+    channel: Option<usize>,
+    sending: bool,
+}
+
+impl<R: Radio, T: Timer> Advertiser<R, T> {
+    pub fn new(
+        radio: R,
+        timer: T,
+        period: Duration,
+        pdu: &[u8],
+    ) -> Result<Self, pdu::Error> {
+        let mut advertiser = Advertiser {
+            radio,
+            interval: timer.interval(period),
+            pdu: [0; MAX_LEN],
+            len: 0,
+            channel: None,
+            sending: false,
+        };
+        advertiser.copy_pdu(pdu)?;
+        Ok(advertiser)
+    }
+
+    fn copy_pdu(&mut self, pdu: &[u8]) -> Result<(), pdu::Error> {
+        let len = pdu.len();
+        if Pdu::parse(pdu)?.payload.len() + pdu::HEADER_LEN != len {
+            return Err(pdu::Error::TooLong);
+        }
+        self.pdu[..len].copy_from_slice(pdu);
+        self.len = len;
+        Ok(())
+    }
+
+    /// Replace the PDU being advertised, a send in progress is aborted and
+    /// restarted with the new PDU, or the old one if this fails.
+    pub fn set_pdu(self: Pin<&mut Self>, pdu: &[u8]) -> Result<(), pdu::Error> {
+        // Safety: `radio` is only accessed through new pinned references
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+        if this.sending {
+            unsafe { Pin::new_unchecked(&mut this.radio) }.abort();
+            this.sending = false;
+        }
+        this.copy_pdu(pdu)
+    }
+}
+
+impl<R: Radio, T: Timer> Stream for Advertiser<R, T> {
+    type Item = Result<(), Error<R::Error, T::Error>>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // Safety: `radio` and `interval` are only accessed through new pinned
+        // references
+        let Advertiser {
+            radio,
+            interval,
+            pdu,
+            len,
+            channel,
+            sending,
+        } = unsafe { Pin::get_unchecked_mut(self) };
+        let mut radio = unsafe { Pin::new_unchecked(radio) };
+        let interval = unsafe { Pin::new_unchecked(interval) };
+
+        if channel.is_none() {
+            match ready!(interval.poll_next(cx)) {
+                Some(Ok(())) => *channel = Some(0),
+                Some(Err(err)) => {
+                    return Poll::Ready(Some(Err(Error::Timer(err))))
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+
+        while let Some(index) = *channel {
+            if !*sending {
+                radio.as_mut().set_channel(Channel::ADVERTISING[index]);
+                *sending = true;
+            }
+            let result = ready!(radio.as_mut().poll_send(cx, &pdu[..*len]));
+            *sending = false;
+            if let Err(err) = result {
+                *channel = None;
+                return Poll::Ready(Some(Err(Error::Radio(err))));
+            }
+            *channel = Some(index + 1).filter(|&next| next < 3);
+        }
+
+        Poll::Ready(Some(Ok(())))
+    }
+}
+
+impl<R: Radio, T: Timer> Drop for Advertiser<R, T> {
+    fn drop(&mut self) {
+        if self.sending {
+            // Safety: this is the last use of `radio` before it is dropped
+            unsafe { Pin::new_unchecked(&mut self.radio) }.abort();
+        }
+    }
+}
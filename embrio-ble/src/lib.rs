@@ -0,0 +1,21 @@
+//! Bluetooth Low Energy advertising, enough to act as a broadcaster or an
+//! observer.
+//!
+//! Everything above the [`Radio`](radio::Radio) trait is hardware independent,
+//! the link layer helpers in [`link`] are for radios that don't calculate the
+//! CRC or whiten packets themselves.
+
+#![no_std]
+
+pub mod ad;
+pub mod advertiser;
+pub mod link;
+pub mod pdu;
+pub mod radio;
+pub mod scanner;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error<R, T> {
+    Radio(R),
+    Timer(T),
+}
@@ -0,0 +1,125 @@
+/// The access address of every advertising channel packet.
+pub const ADVERTISING_ACCESS_ADDRESS: u32 = 0x8E89_BED6;
+
+/// The CRC initial value of every advertising channel packet.
+pub const ADVERTISING_CRC_INIT: u32 = 0x55_5555;
+
+// x^24 + x^10 + x^9 + x^6 + x^4 + x^3 + x + 1, bit reversed without the x^0
+// term which is shifted in separately
+const CRC_POLY_REVERSED: u32 = 0x5A_6000;
+
+/// One of the 40 RF channels, identified by its channel index.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Channel(u8);
+
+impl Channel {
+    pub const ADVERTISING: [Channel; 3] =
+        [Channel(37), Channel(38), Channel(39)];
+
+    pub fn new(index: u8) -> Option<Self> {
+        if index < 40 {
+            Some(Channel(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn index(self) -> u8 {
+        self.0
+    }
+
+    pub fn is_advertising(self) -> bool {
+        self.0 >= 37
+    }
+
+    /// The centre frequency in MHz above 2400MHz.
+    pub fn frequency(self) -> u8 {
+        match self.0 {
+            37 => 2,
+            38 => 26,
+            39 => 80,
+            index @ 0..=10 => 4 + 2 * index,
+            index => 6 + 2 * index,
+        }
+    }
+
+    /// The initial whitening register, position 0 in bit 6 followed by the
+    /// channel index, the layout most radios take it in.
+    pub fn whitening_iv(self) -> u8 {
+        0x40 | self.0
+    }
+}
+
+/// The CRC of a PDU, as the 3 bytes sent after it.
+pub fn crc(init: u32, pdu: &[u8]) -> [u8; 3] {
+    // Held bit reversed so that bytes can be shifted in least significant bit
+    // first, as they are sent
+    let mut state = init.reverse_bits() >> 8;
+    for &byte in pdu {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let feedback = (state ^ u32::from(byte)) & 1;
+            byte >>= 1;
+            state >>= 1;
+            if feedback == 1 {
+                state |= 1 << 23;
+                state ^= CRC_POLY_REVERSED;
+            }
+        }
+    }
+    [state as u8, (state >> 8) as u8, (state >> 16) as u8]
+}
+
+/// Whitens the PDU and CRC sent on `channel`, whitening is its own inverse so
+/// this also dewhitens received packets.
+pub fn whiten(channel: Channel, data: &mut [u8]) {
+    // The 7-bit register is held bit reversed in the top bits so its output
+    // is bit 7, the feedback into position 0 and the x^4 tap are both applied
+    // before shifting
+    let mut state = channel.whitening_iv().reverse_bits();
+    for byte in data {
+        for bit in 0..8 {
+            if state & 0x80 != 0 {
+                state ^= 0x11;
+                *byte ^= 1 << bit;
+            }
+            state <<= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequencies() {
+        let frequency = |index| Channel::new(index).unwrap().frequency();
+        assert_eq!(frequency(37), 2);
+        assert_eq!(frequency(0), 4);
+        assert_eq!(frequency(10), 24);
+        assert_eq!(frequency(38), 26);
+        assert_eq!(frequency(11), 28);
+        assert_eq!(frequency(36), 78);
+        assert_eq!(frequency(39), 80);
+        assert_eq!(Channel::new(40), None);
+    }
+
+    #[test]
+    fn crc_of_nothing_is_the_reversed_init() {
+        assert_eq!(crc(ADVERTISING_CRC_INIT, &[]), [0xAA, 0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn whitening_sequence() {
+        let mut data = [0; 8];
+        whiten(Channel(0), &mut data);
+        assert_eq!(data, [0x40, 0xB2, 0xBC, 0xC3, 0x1F, 0x37, 0x4A, 0x5F]);
+
+        let mut data = [0; 8];
+        whiten(Channel(37), &mut data);
+        assert_eq!(data, [0x8D, 0xD2, 0x57, 0xA1, 0x3D, 0xA7, 0x66, 0xB0]);
+        whiten(Channel(37), &mut data);
+        assert_eq!(data, [0; 8]);
+    }
+}
@@ -0,0 +1,306 @@
+use core::fmt;
+
+pub const HEADER_LEN: usize = 2;
+
+/// The longest advertising channel PDU payload.
+pub const MAX_PAYLOAD_LEN: usize = 37;
+
+/// The longest advertising channel PDU, header included.
+pub const MAX_LEN: usize = HEADER_LEN + MAX_PAYLOAD_LEN;
+
+/// The longest `AdvData`, the payload remaining after the advertiser address.
+pub const MAX_DATA_LEN: usize = MAX_PAYLOAD_LEN - ADDRESS_LEN;
+
+const ADDRESS_LEN: usize = 6;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The buffer is shorter than the PDU.
+    Truncated,
+    /// The payload is longer than an advertising PDU allows.
+    TooLong,
+    /// The payload is too short for the PDU type.
+    Invalid,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PduType {
+    AdvInd,
+    AdvDirectInd,
+    AdvNonconnInd,
+    ScanReq,
+    ScanRsp,
+    ConnectReq,
+    AdvScanInd,
+    Reserved(u8),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AddressKind {
+    Public,
+    Random,
+}
+
+/// A device address, the bytes are in the order they are sent, least
+/// significant first.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Address {
+    pub kind: AddressKind,
+    pub bytes: [u8; ADDRESS_LEN],
+}
+
+/// An advertising channel PDU.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Pdu<'a> {
+    pub ty: PduType,
+    /// Whether the first address in the payload is random.
+    pub tx_add: bool,
+    /// Whether the second address in the payload is random.
+    pub rx_add: bool,
+    pub payload: &'a [u8],
+}
+
+impl PduType {
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0x0F {
+            0 => PduType::AdvInd,
+            1 => PduType::AdvDirectInd,
+            2 => PduType::AdvNonconnInd,
+            3 => PduType::ScanReq,
+            4 => PduType::ScanRsp,
+            5 => PduType::ConnectReq,
+            6 => PduType::AdvScanInd,
+            bits => PduType::Reserved(bits),
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            PduType::AdvInd => 0,
+            PduType::AdvDirectInd => 1,
+            PduType::AdvNonconnInd => 2,
+            PduType::ScanReq => 3,
+            PduType::ScanRsp => 4,
+            PduType::ConnectReq => 5,
+            PduType::AdvScanInd => 6,
+            PduType::Reserved(bits) => bits & 0x0F,
+        }
+    }
+
+    /// Whether the payload is the advertiser address followed by `AdvData`.
+    pub fn has_data(self) -> bool {
+        match self {
+            PduType::AdvInd
+            | PduType::AdvNonconnInd
+            | PduType::ScanRsp
+            | PduType::AdvScanInd => true,
+            _ => false,
+        }
+    }
+}
+
+impl Address {
+    pub fn public(bytes: [u8; ADDRESS_LEN]) -> Self {
+        Address {
+            kind: AddressKind::Public,
+            bytes,
+        }
+    }
+
+    pub fn random(bytes: [u8; ADDRESS_LEN]) -> Self {
+        Address {
+            kind: AddressKind::Random,
+            bytes,
+        }
+    }
+
+    fn from_slice(kind: AddressKind, slice: &[u8]) -> Self {
+        let mut bytes = [0; ADDRESS_LEN];
+        bytes.copy_from_slice(slice);
+        Address { kind, bytes }
+    }
+}
+
+/// Formatted most significant byte first, the way addresses are usually
+/// written.
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = &self.bytes;
+        write!(
+            f,
+            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+            b[5], b[4], b[3], b[2], b[1], b[0]
+        )
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Address({:?}, {})", self.kind, self)
+    }
+}
+
+fn kind(random: bool) -> AddressKind {
+    if random {
+        AddressKind::Random
+    } else {
+        AddressKind::Public
+    }
+}
+
+impl<'a> Pdu<'a> {
+    /// An advertising PDU carrying the advertiser address and `data`.
+    pub fn advertising(
+        ty: PduType,
+        address: &Address,
+        data: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        assert!(ty.has_data());
+        if data.len() > MAX_DATA_LEN {
+            return Err(Error::TooLong);
+        }
+        let len = HEADER_LEN + ADDRESS_LEN + data.len();
+        if buf.len() < len {
+            return Err(Error::Truncated);
+        }
+        let random = address.kind == AddressKind::Random;
+        buf[0] = ty.bits() | (random as u8) << 6;
+        buf[1] = (ADDRESS_LEN + data.len()) as u8;
+        buf[HEADER_LEN..HEADER_LEN + ADDRESS_LEN]
+            .copy_from_slice(&address.bytes);
+        buf[HEADER_LEN + ADDRESS_LEN..len].copy_from_slice(data);
+        Ok(len)
+    }
+
+    /// Parses the PDU at the start of `buf`, ignoring anything after it.
+    pub fn parse(buf: &'a [u8]) -> Result<Self, Error> {
+        if buf.len() < HEADER_LEN {
+            return Err(Error::Truncated);
+        }
+        let len = usize::from(buf[1]);
+        if len > MAX_PAYLOAD_LEN {
+            return Err(Error::TooLong);
+        }
+        let payload = buf
+            .get(HEADER_LEN..HEADER_LEN + len)
+            .ok_or(Error::Truncated)?;
+        let pdu = Pdu {
+            ty: PduType::from_bits(buf[0]),
+            tx_add: buf[0] & (1 << 6) != 0,
+            rx_add: buf[0] & (1 << 7) != 0,
+            payload,
+        };
+        let min_len = match pdu.ty {
+            PduType::AdvDirectInd | PduType::ScanReq => 2 * ADDRESS_LEN,
+            PduType::ConnectReq => 34,
+            PduType::Reserved(_) => 0,
+            _ => ADDRESS_LEN,
+        };
+        if payload.len() < min_len {
+            return Err(Error::Invalid);
+        }
+        Ok(pdu)
+    }
+
+    /// The address of the advertiser, which is the scanner or initiator
+    /// address for PDUs sent to an advertiser.
+    pub fn address(&self) -> Option<Address> {
+        match self.ty {
+            PduType::Reserved(_) => None,
+            _ => Some(Address::from_slice(
+                kind(self.tx_add),
+                &self.payload[..ADDRESS_LEN],
+            )),
+        }
+    }
+
+    /// The second address, of the advertiser for scan and connect requests, or
+    /// of the initiator for directed advertising.
+    pub fn target_address(&self) -> Option<Address> {
+        match self.ty {
+            PduType::AdvDirectInd | PduType::ScanReq | PduType::ConnectReq => {
+                Some(Address::from_slice(
+                    kind(self.rx_add),
+                    &self.payload[ADDRESS_LEN..2 * ADDRESS_LEN],
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// The `AdvData` of PDUs that carry it, see [`ad`](crate::ad) for
+    /// parsing it.
+    pub fn data(&self) -> Option<&'a [u8]> {
+        if self.ty.has_data() {
+            Some(&self.payload[ADDRESS_LEN..])
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: Address = Address {
+        kind: AddressKind::Random,
+        bytes: [0x56, 0x34, 0x12, 0xEE, 0xFF, 0xC0],
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut buf = [0; MAX_LEN];
+        let len = Pdu::advertising(
+            PduType::AdvNonconnInd,
+            &ADDRESS,
+            &[0x02, 0x01, 0x06],
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(len, 11);
+        assert_eq!(&buf[..2], &[0x42, 9]);
+
+        let pdu = Pdu::parse(&buf).unwrap();
+        assert_eq!(pdu.ty, PduType::AdvNonconnInd);
+        assert_eq!(pdu.address(), Some(ADDRESS));
+        assert_eq!(pdu.target_address(), None);
+        assert_eq!(pdu.data(), Some(&[0x02, 0x01, 0x06][..]));
+    }
+
+    #[test]
+    fn scan_request() {
+        let buf = [0x83, 12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let pdu = Pdu::parse(&buf).unwrap();
+        assert_eq!(pdu.ty, PduType::ScanReq);
+        assert_eq!(pdu.address(), Some(Address::public([1, 2, 3, 4, 5, 6])));
+        assert_eq!(
+            pdu.target_address(),
+            Some(Address::random([7, 8, 9, 10, 11, 12]))
+        );
+        assert_eq!(pdu.data(), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Pdu::parse(&[0x40]), Err(Error::Truncated));
+        assert_eq!(Pdu::parse(&[0x40, 7, 1, 2]), Err(Error::Truncated));
+        assert_eq!(Pdu::parse(&[0x40, 38]), Err(Error::TooLong));
+        assert_eq!(
+            Pdu::parse(&[0x43, 6, 1, 2, 3, 4, 5, 6]),
+            Err(Error::Invalid)
+        );
+        assert_eq!(
+            Pdu::advertising(PduType::AdvInd, &ADDRESS, &[0; 32], &mut [0; 64]),
+            Err(Error::TooLong)
+        );
+    }
+
+    #[test]
+    fn display_address() {
+        extern crate std;
+        use std::string::ToString;
+        assert_eq!(ADDRESS.to_string(), "C0:FF:EE:12:34:56");
+    }
+}
@@ -0,0 +1,156 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use crate::link::Channel;
+
+/// A radio able to send and receive advertising channel packets.
+///
+/// PDUs are passed as the 2 byte header followed by the payload, the radio is
+/// responsible for the preamble, access address, CRC and whitening.
+pub trait Radio {
+    type Error: Debug;
+
+    /// Tune to `channel` for the following sends and receives, must not be
+    /// called while an operation is in progress.
+    fn set_channel(self: Pin<&mut Self>, channel: Channel);
+
+    /// Send `pdu`.
+    ///
+    /// Once this has returned `Pending` it must be polled again with the same
+    /// arguments until it completes, or [`abort`](Radio::abort)ed.
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        pdu: &[u8],
+    ) -> Poll<Result<(), Self::Error>>;
+
+    /// Receive the next PDU with a valid CRC into `buf`, returning its length.
+    ///
+    /// Once this has returned `Pending` it must be polled again with the same
+    /// arguments until it completes, or [`abort`](Radio::abort)ed.
+    fn poll_recv(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>>;
+
+    /// Stop any in progress send or receive.
+    fn abort(self: Pin<&mut Self>);
+
+    fn send<'a>(self: Pin<&'a mut Self>, pdu: &'a [u8]) -> Send<'a, Self> {
+        Send {
+            radio: self,
+            pdu,
+            started: false,
+            done: false,
+        }
+    }
+
+    fn recv<'a>(self: Pin<&'a mut Self>, buf: &'a mut [u8]) -> Recv<'a, Self> {
+        Recv {
+            radio: self,
+            buf,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+impl<R> Radio for Pin<&mut R>
+where
+    R: Radio,
+{
+    type Error = <R as Radio>::Error;
+
+    fn set_channel(self: Pin<&mut Self>, channel: Channel) {
+        <R as Radio>::set_channel(Pin::get_mut(self).as_mut(), channel)
+    }
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        pdu: &[u8],
+    ) -> Poll<Result<(), Self::Error>> {
+        <R as Radio>::poll_send(Pin::get_mut(self).as_mut(), cx, pdu)
+    }
+
+    fn poll_recv(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        <R as Radio>::poll_recv(Pin::get_mut(self).as_mut(), cx, buf)
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        <R as Radio>::abort(Pin::get_mut(self).as_mut())
+    }
+}
+
+#[derive(Debug)]
+pub struct Send<'a, R: Radio + ?Sized> {
+    radio: Pin<&'a mut R>,
+    pdu: &'a [u8],
+    started: bool,
+    done: bool,
+}
+
+#[derive(Debug)]
+pub struct Recv<'a, R: Radio + ?Sized> {
+    radio: Pin<&'a mut R>,
+    buf: &'a mut [u8],
+    started: bool,
+    done: bool,
+}
+
+impl<'a, R: Radio + ?Sized> Future for Send<'a, R> {
+    type Output = Result<(), R::Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.done, "Send polled after completion");
+        this.started = true;
+        let result = this.radio.as_mut().poll_send(cx, this.pdu);
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, R: Radio + ?Sized> Future for Recv<'a, R> {
+    type Output = Result<usize, R::Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.done, "Recv polled after completion");
+        this.started = true;
+        let result = this.radio.as_mut().poll_recv(cx, this.buf);
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, R: Radio + ?Sized> Drop for Send<'a, R> {
+    fn drop(&mut self) {
+        if self.started && !self.done {
+            self.radio.as_mut().abort();
+        }
+    }
+}
+
+impl<'a, R: Radio + ?Sized> Drop for Recv<'a, R> {
+    fn drop(&mut self) {
+        if self.started && !self.done {
+            self.radio.as_mut().abort();
+        }
+    }
+}
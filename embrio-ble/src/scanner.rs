@@ -0,0 +1,158 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::timer::Timer;
+use futures_core::stream::Stream;
+use futures_util::ready;
+
+use crate::{
+    ad::{self, AdStructures},
+    link::Channel,
+    pdu::{Address, Pdu, PduType, MAX_DATA_LEN, MAX_LEN},
+    radio::Radio,
+    Error,
+};
+
+/// An advertising PDU received while scanning.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Report {
+    pub ty: PduType,
+    pub address: Address,
+    pub channel: Channel,
+    data: [u8; MAX_DATA_LEN],
+    len: u8,
+}
+
+impl Report {
+    /// Advertising PDUs become reports, anything else received on the
+    /// advertising channels is ignored.
+    fn new(channel: Channel, pdu: &Pdu<'_>) -> Option<Self> {
+        match pdu.ty {
+            PduType::AdvInd
+            | PduType::AdvDirectInd
+            | PduType::AdvNonconnInd
+            | PduType::AdvScanInd
+            | PduType::ScanRsp => {}
+            _ => return None,
+        }
+        let data = pdu.data().unwrap_or(&[]);
+        let mut report = Report {
+            ty: pdu.ty,
+            address: pdu.address()?,
+            channel,
+            data: [0; MAX_DATA_LEN],
+            len: data.len() as u8,
+        };
+        report.data[..data.len()].copy_from_slice(data);
+        Some(report)
+    }
+
+    /// The `AdvData`, empty for directed advertising.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..usize::from(self.len)]
+    }
+
+    pub fn ad_structures(&self) -> AdStructures<'_> {
+        ad::parse(self.data())
+    }
+}
+
+/// A [`Stream`] of advertising reports, listening on each advertising channel
+/// in turn and moving to the next on each tick of an interval.
+///
+/// PDUs that fail to parse are skipped.
+pub struct Scanner<R: Radio, T: Timer> {
+    radio: R,
+    interval: T::Interval,
+    buf: [u8; MAX_LEN],
+    /// Index into `Channel::ADVERTISING`.
+    channel: usize,
+    receiving: bool,
+}
+
+impl<R: Radio, T: Timer> Scanner<R, T> {
+    pub fn new(radio: R, timer: T, window: Duration) -> Self {
+        Scanner {
+            radio,
+            interval: timer.interval(window),
+            buf: [0; MAX_LEN],
+            channel: 0,
+            receiving: false,
+        }
+    }
+}
+
+impl<R: Radio, T: Timer> Stream for Scanner<R, T> {
+    type Item = Result<Report, Error<R::Error, T::Error>>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // Safety: `radio` and `interval` are only accessed through new pinned
+        // references
+        let Scanner {
+            radio,
+            interval,
+            buf,
+            channel,
+            receiving,
+        } = unsafe { Pin::get_unchecked_mut(self) };
+        let mut radio = unsafe { Pin::new_unchecked(radio) };
+        let mut interval = unsafe { Pin::new_unchecked(interval) };
+
+        loop {
+            match interval.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(()))) => {
+                    if *receiving {
+                        radio.as_mut().abort();
+                        *receiving = false;
+                    }
+                    *channel = (*channel + 1) % Channel::ADVERTISING.len();
+                    continue;
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Some(Err(Error::Timer(err))))
+                }
+                Poll::Ready(None) => {
+                    if *receiving {
+                        radio.as_mut().abort();
+                        *receiving = false;
+                    }
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => {}
+            }
+
+            let channel = Channel::ADVERTISING[*channel];
+            if !*receiving {
+                radio.as_mut().set_channel(channel);
+                *receiving = true;
+            }
+            let result = ready!(radio.as_mut().poll_recv(cx, buf));
+            *receiving = false;
+            let len = match result {
+                Ok(len) => len,
+                Err(err) => return Poll::Ready(Some(Err(Error::Radio(err)))),
+            };
+            let report = Pdu::parse(&buf[..len])
+                .ok()
+                .and_then(|pdu| Report::new(channel, &pdu));
+            if let Some(report) = report {
+                return Poll::Ready(Some(Ok(report)));
+            }
+        }
+    }
+}
+
+impl<R: Radio, T: Timer> Drop for Scanner<R, T> {
+    fn drop(&mut self) {
+        if self.receiving {
+            // Safety: this is the last use of `radio` before it is dropped
+            unsafe { Pin::new_unchecked(&mut self.radio) }.abort();
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    cell::RefCell,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use embrio_ble::{
+    advertiser::Advertiser, link::Channel, pdu::Error as PduError,
+    radio::Radio, Error,
+};
+use embrio_native::timer::StreamTimer;
+use futures::{
+    executor::block_on,
+    stream::{self, Iter, Stream, StreamExt},
+    task::noop_waker,
+};
+
+type Ticks = Iter<std::vec::IntoIter<Result<(), &'static str>>>;
+
+#[derive(Default)]
+struct Log {
+    sent: Vec<(u8, Vec<u8>)>,
+    aborts: usize,
+}
+
+/// Takes a pending poll to send each PDU, logging it once sent.
+struct MockRadio {
+    log: Rc<RefCell<Log>>,
+    channel: Option<Channel>,
+    sending: bool,
+}
+
+impl Radio for MockRadio {
+    type Error = ();
+
+    fn set_channel(self: Pin<&mut Self>, channel: Channel) {
+        assert!(!self.sending);
+        Pin::get_mut(self).channel = Some(channel);
+    }
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pdu: &[u8],
+    ) -> Poll<Result<(), ()>> {
+        let this = Pin::get_mut(self);
+        if this.sending {
+            this.sending = false;
+            let channel = this.channel.unwrap().index();
+            this.log.borrow_mut().sent.push((channel, pdu.to_vec()));
+            Poll::Ready(Ok(()))
+        } else {
+            this.sending = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn poll_recv(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut [u8],
+    ) -> Poll<Result<usize, ()>> {
+        unimplemented!()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let this = Pin::get_mut(self);
+        this.sending = false;
+        this.log.borrow_mut().aborts += 1;
+    }
+}
+
+/// Every interval ticks immediately, failing after the given number of ticks.
+fn timer(ticks: usize) -> StreamTimer<Ticks> {
+    let mut ticks = vec![Ok(()); ticks];
+    ticks.push(Err("stopped"));
+    StreamTimer::new(stream::iter(ticks))
+}
+
+const PDU: [u8; 11] = [0x42, 9, 1, 2, 3, 4, 5, 6, 0x02, 0x01, 0x06];
+
+fn radio() -> (MockRadio, Rc<RefCell<Log>>) {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let radio = MockRadio {
+        log: log.clone(),
+        channel: None,
+        sending: false,
+    };
+    (radio, log)
+}
+
+#[test]
+fn each_channel_per_tick() {
+    let (radio, log) = radio();
+    let advertiser =
+        Advertiser::new(radio, timer(2), Duration::from_millis(100), &PDU)
+            .unwrap();
+
+    assert_eq!(
+        block_on(advertiser.collect::<Vec<_>>()),
+        vec![Ok(()), Ok(()), Err(Error::Timer("stopped"))]
+    );
+    let log = log.borrow();
+    let channels: Vec<_> = log.sent.iter().map(|(c, _)| *c).collect();
+    assert_eq!(channels, vec![37, 38, 39, 37, 38, 39]);
+    assert!(log.sent.iter().all(|(_, pdu)| pdu == &PDU));
+    assert_eq!(log.aborts, 0);
+}
+
+#[test]
+fn invalid_pdu() {
+    let new = |pdu: &[u8]| {
+        Advertiser::new(radio().0, timer(0), Duration::from_millis(100), pdu)
+            .err()
+    };
+    assert_eq!(new(&PDU[..10]), Some(PduError::Truncated));
+    assert_eq!(
+        new(&[0x42, 6, 1, 2, 3, 4, 5, 6, 0]),
+        Some(PduError::TooLong)
+    );
+}
+
+#[test]
+fn set_pdu_restarts_send() {
+    let (radio, log) = radio();
+    let mut advertiser = Box::pin(
+        Advertiser::new(radio, timer(1), Duration::from_millis(100), &PDU)
+            .unwrap(),
+    );
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(advertiser.as_mut().poll_next(&mut cx).is_pending());
+    let mut pdu = PDU;
+    pdu[10] = 0x04;
+    advertiser.as_mut().set_pdu(&pdu).unwrap();
+    assert_eq!(log.borrow().aborts, 1);
+
+    assert!(advertiser.as_mut().poll_next(&mut cx).is_pending());
+    assert!(advertiser.as_mut().poll_next(&mut cx).is_pending());
+    assert_eq!(log.borrow().sent, vec![(37, pdu.to_vec())]);
+
+    drop(advertiser);
+    assert_eq!(log.borrow().aborts, 2);
+}
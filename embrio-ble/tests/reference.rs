@@ -0,0 +1,122 @@
+//! Packets as they are sent over the air, after the access address.
+//!
+//! The expected CRCs and whitened bytes were generated by independent
+//! implementations, one following the shift register diagrams in the core
+//! specification (Vol 6, Part B, 3.1) and one following the well known
+//! software implementation by Dmitry Grinberg, which agreed on every input.
+
+use embrio_ble::{
+    ad::{self, AdStructure},
+    link::{self, Channel, ADVERTISING_CRC_INIT},
+    pdu::{Address, Pdu, PduType},
+};
+
+/// ADV_NONCONN_IND from random address C0:FF:EE:12:34:56, general
+/// discoverable without BR/EDR, named "embrio".
+const ADV_NONCONN_IND: [u8; 19] = [
+    0x42, 0x11, 0x56, 0x34, 0x12, 0xEE, 0xFF, 0xC0, 0x02, 0x01, 0x06, 0x07,
+    0x09, b'e', b'm', b'b', b'r', b'i', b'o',
+];
+
+const ADV_NONCONN_IND_CRC: [u8; 3] = [0xBD, 0x28, 0x6A];
+
+const ADV_NONCONN_IND_WHITENED: [(u8, [u8; 22]); 3] = [
+    (
+        37,
+        [
+            0xCF, 0xC3, 0x01, 0x95, 0x2F, 0x49, 0x99, 0x70, 0x77, 0x30, 0x17,
+            0x4F, 0x9F, 0x12, 0x95, 0x81, 0x34, 0x80, 0xC4, 0x6D, 0xB6, 0x39,
+        ],
+    ),
+    (
+        38,
+        [
+            0x94, 0xD4, 0x12, 0x14, 0x4B, 0x30, 0x1E, 0x4F, 0x19, 0xA4, 0xA9,
+            0x45, 0x72, 0x2B, 0xA0, 0x02, 0x99, 0x0B, 0x4D, 0x2D, 0x04, 0x85,
+        ],
+    ),
+    (
+        39,
+        [
+            0x5D, 0x26, 0x1C, 0x6B, 0x97, 0x18, 0x63, 0x5A, 0xC3, 0xD7, 0xC3,
+            0x43, 0x29, 0x3C, 0xB3, 0x83, 0xFD, 0x72, 0xCA, 0x12, 0x6A, 0x11,
+        ],
+    ),
+];
+
+/// SCAN_RSP from public address 06:05:04:03:02:01 carrying manufacturer
+/// specific data for company 0x0059, received on channel 39.
+const SCAN_RSP_WHITENED: [u8; 17] = [
+    0x1B, 0x3B, 0x4B, 0x5D, 0x86, 0xF2, 0x99, 0x9C, 0xC4, 0x29, 0x9C, 0x44,
+    0x21, 0x5B, 0x25, 0x7C, 0xD6,
+];
+
+#[test]
+fn crc() {
+    assert_eq!(
+        link::crc(ADVERTISING_CRC_INIT, &ADV_NONCONN_IND),
+        ADV_NONCONN_IND_CRC
+    );
+}
+
+#[test]
+fn whitening() {
+    for (index, whitened) in &ADV_NONCONN_IND_WHITENED {
+        let channel = Channel::new(*index).unwrap();
+        let mut packet = [0; 22];
+        packet[..19].copy_from_slice(&ADV_NONCONN_IND);
+        packet[19..].copy_from_slice(&ADV_NONCONN_IND_CRC);
+        link::whiten(channel, &mut packet);
+        assert_eq!(&packet, whitened, "channel {}", index);
+    }
+}
+
+#[test]
+fn build() {
+    let address = Address::random([0x56, 0x34, 0x12, 0xEE, 0xFF, 0xC0]);
+    let mut data = [0; 31];
+    let len = ad::encode(
+        &[
+            AdStructure::Flags(
+                ad::LE_GENERAL_DISCOVERABLE | ad::BR_EDR_NOT_SUPPORTED,
+            ),
+            AdStructure::CompleteLocalName("embrio"),
+        ],
+        &mut data,
+    )
+    .unwrap();
+
+    let mut buf = [0; 39];
+    let len = Pdu::advertising(
+        PduType::AdvNonconnInd,
+        &address,
+        &data[..len],
+        &mut buf,
+    )
+    .unwrap();
+    assert_eq!(&buf[..len], &ADV_NONCONN_IND[..]);
+}
+
+#[test]
+fn receive() {
+    let mut packet = SCAN_RSP_WHITENED;
+    link::whiten(Channel::new(39).unwrap(), &mut packet);
+    let (pdu, crc) = packet.split_at(packet.len() - 3);
+    assert_eq!(link::crc(ADVERTISING_CRC_INIT, pdu), crc);
+
+    let pdu = Pdu::parse(pdu).unwrap();
+    assert_eq!(pdu.ty, PduType::ScanRsp);
+    assert_eq!(
+        pdu.address(),
+        Some(Address::public([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]))
+    );
+    let mut structures = ad::parse(pdu.data().unwrap());
+    assert_eq!(
+        structures.next(),
+        Some(Ok(AdStructure::ManufacturerSpecificData {
+            company: 0x0059,
+            data: &[0x01, 0x02],
+        }))
+    );
+    assert_eq!(structures.next(), None);
+}
@@ -0,0 +1,164 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use embrio_ble::{
+    ad::AdStructure,
+    link::Channel,
+    pdu::{Address, PduType},
+    radio::Radio,
+    scanner::{Report, Scanner},
+};
+use embrio_native::timer::StreamTimer;
+use futures::{
+    executor::block_on,
+    stream::{Stream, StreamExt},
+};
+
+#[derive(Default)]
+struct Air {
+    /// Packets waiting to be received on each channel index.
+    packets: HashMap<u8, VecDeque<Vec<u8>>>,
+    /// Whether the radio is waiting on a quiet channel.
+    quiet: bool,
+    tuned: Vec<u8>,
+    aborts: usize,
+}
+
+/// Receives the packets queued for its channel, then waits for more.
+struct MockRadio {
+    air: Rc<RefCell<Air>>,
+    channel: Option<Channel>,
+}
+
+/// Ticks whenever the radio is waiting on a quiet channel, ending after the
+/// given number of ticks.
+struct MockInterval {
+    air: Rc<RefCell<Air>>,
+    ticks: usize,
+}
+
+impl Radio for MockRadio {
+    type Error = ();
+
+    fn set_channel(self: Pin<&mut Self>, channel: Channel) {
+        let this = Pin::get_mut(self);
+        this.air.borrow_mut().tuned.push(channel.index());
+        this.channel = Some(channel);
+    }
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _pdu: &[u8],
+    ) -> Poll<Result<(), ()>> {
+        unimplemented!()
+    }
+
+    fn poll_recv(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, ()>> {
+        let mut air = self.air.borrow_mut();
+        let index = self.channel.unwrap().index();
+        match air.packets.get_mut(&index).and_then(VecDeque::pop_front) {
+            Some(packet) => {
+                buf[..packet.len()].copy_from_slice(&packet);
+                Poll::Ready(Ok(packet.len()))
+            }
+            None => {
+                air.quiet = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let mut air = self.air.borrow_mut();
+        air.quiet = false;
+        air.aborts += 1;
+    }
+}
+
+impl Stream for MockInterval {
+    type Item = Result<(), ()>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = Pin::get_mut(self);
+        if !this.air.borrow().quiet {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else if this.ticks == 0 {
+            Poll::Ready(None)
+        } else {
+            this.ticks -= 1;
+            Poll::Ready(Some(Ok(())))
+        }
+    }
+}
+
+fn adv(ty: u8, address: u8, data: &[u8]) -> Vec<u8> {
+    let mut pdu = vec![ty, 6 + data.len() as u8, address, 0, 0, 0, 0, 0];
+    pdu.extend_from_slice(data);
+    pdu
+}
+
+#[test]
+fn reports_per_channel() {
+    let air = Rc::new(RefCell::new(Air::default()));
+    {
+        let packets = &mut air.borrow_mut().packets;
+        packets.insert(
+            37,
+            vec![
+                adv(0x42, 1, &[0x02, 0x01, 0x06]),
+                // truncated
+                vec![0x42, 9, 1, 2],
+                // SCAN_REQ
+                vec![0x03, 12, 2, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0],
+            ]
+            .into(),
+        );
+        packets.insert(38, vec![adv(0x04, 3, &[])].into());
+    }
+    let radio = MockRadio {
+        air: air.clone(),
+        channel: None,
+    };
+    let timer = StreamTimer::new(MockInterval {
+        air: air.clone(),
+        ticks: 3,
+    });
+    let scanner = Scanner::new(radio, timer, Duration::from_millis(10));
+
+    let reports: Vec<Report> = block_on(scanner.collect::<Vec<_>>())
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].ty, PduType::AdvNonconnInd);
+    assert_eq!(reports[0].address, Address::random([1, 0, 0, 0, 0, 0]));
+    assert_eq!(reports[0].channel.index(), 37);
+    assert_eq!(
+        reports[0].ad_structures().collect::<Vec<_>>(),
+        vec![Ok(AdStructure::Flags(0x06))]
+    );
+    assert_eq!(reports[1].ty, PduType::ScanRsp);
+    assert_eq!(reports[1].address, Address::public([3, 0, 0, 0, 0, 0]));
+    assert_eq!(reports[1].channel.index(), 38);
+    assert_eq!(reports[1].data(), &[]);
+
+    let air = air.borrow();
+    assert_eq!(air.tuned, vec![37, 37, 37, 37, 38, 38, 39, 37]);
+    assert_eq!(air.aborts, 4);
+}
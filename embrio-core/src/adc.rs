@@ -0,0 +1,89 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+pub trait Adc {
+    type Channel: Copy;
+
+    type Error: Debug;
+
+    /// Start a conversion of `channel`, or collect the result of the
+    /// previously started conversion once it's done.
+    ///
+    /// Once this has returned `Pending` it must be polled again with the same
+    /// channel until it completes, or [`abort`](Adc::abort)ed.
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        channel: Self::Channel,
+    ) -> Poll<Result<u16, Self::Error>>;
+
+    /// Stop any in progress conversion, discarding its result.
+    fn abort(self: Pin<&mut Self>);
+
+    fn read(self: Pin<&mut Self>, channel: Self::Channel) -> Read<'_, Self> {
+        Read {
+            adc: self,
+            channel,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+impl<A> Adc for Pin<&mut A>
+where
+    A: Adc,
+{
+    type Channel = <A as Adc>::Channel;
+
+    type Error = <A as Adc>::Error;
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        channel: Self::Channel,
+    ) -> Poll<Result<u16, Self::Error>> {
+        <A as Adc>::poll_read(Pin::get_mut(self).as_mut(), cx, channel)
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        <A as Adc>::abort(Pin::get_mut(self).as_mut())
+    }
+}
+
+#[derive(Debug)]
+pub struct Read<'a, A: Adc + ?Sized> {
+    adc: Pin<&'a mut A>,
+    channel: A::Channel,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, A: Adc + ?Sized> Future for Read<'a, A> {
+    type Output = Result<u16, A::Error>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        // Safety: nothing is structurally pinned
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+        assert!(!this.done, "Read polled after completion");
+        this.started = true;
+        let result = this.adc.as_mut().poll_read(cx, this.channel);
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, A: Adc + ?Sized> Drop for Read<'a, A> {
+    fn drop(&mut self) {
+        if self.started && !self.done {
+            self.adc.as_mut().abort();
+        }
+    }
+}
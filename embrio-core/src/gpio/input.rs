@@ -0,0 +1,177 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+pub trait Input {
+    fn state(&self) -> bool;
+
+    /// Returns `Ready` once the pin is at `level`, otherwise arranges for the
+    /// current task to be woken when the pin may have changed.
+    fn poll_level(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        level: bool,
+    ) -> Poll<()>;
+
+    /// Returns `Ready` once `edge` has happened since the wait started, on
+    /// the first call after the previous wait completed or was cancelled,
+    /// otherwise arranges for the current task to be woken when it may have.
+    ///
+    /// The edge is latched, so pulses shorter than the time between polls
+    /// are still seen.
+    fn poll_edge(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        edge: Edge,
+    ) -> Poll<()>;
+
+    /// Stop a wait that returned `Pending` and won't be polled again, so the
+    /// pin no longer watches for it.
+    fn cancel_wait(self: Pin<&mut Self>) {}
+
+    fn is_high(&self) -> bool {
+        self.state()
+    }
+
+    fn is_low(&self) -> bool {
+        !self.state()
+    }
+
+    fn wait_for_high(self: Pin<&mut Self>) -> WaitForLevel<'_, Self> {
+        WaitForLevel::new(self, true)
+    }
+
+    fn wait_for_low(self: Pin<&mut Self>) -> WaitForLevel<'_, Self> {
+        WaitForLevel::new(self, false)
+    }
+
+    fn wait_for_rising_edge(self: Pin<&mut Self>) -> WaitForEdge<'_, Self> {
+        WaitForEdge::new(self, Edge::Rising)
+    }
+
+    fn wait_for_falling_edge(self: Pin<&mut Self>) -> WaitForEdge<'_, Self> {
+        WaitForEdge::new(self, Edge::Falling)
+    }
+
+    fn wait_for_any_edge(self: Pin<&mut Self>) -> WaitForEdge<'_, Self> {
+        WaitForEdge::new(self, Edge::Any)
+    }
+}
+
+impl<P> Input for Pin<&mut P>
+where
+    P: Input,
+{
+    fn state(&self) -> bool {
+        (**self).state()
+    }
+
+    fn poll_level(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        level: bool,
+    ) -> Poll<()> {
+        <P as Input>::poll_level(Pin::get_mut(self).as_mut(), cx, level)
+    }
+
+    fn poll_edge(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        edge: Edge,
+    ) -> Poll<()> {
+        <P as Input>::poll_edge(Pin::get_mut(self).as_mut(), cx, edge)
+    }
+
+    fn cancel_wait(self: Pin<&mut Self>) {
+        <P as Input>::cancel_wait(Pin::get_mut(self).as_mut())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Any,
+}
+
+/// Cancels the wait on the pin if dropped before completing.
+#[derive(Debug)]
+pub struct WaitForLevel<'a, P: Input + ?Sized> {
+    pin: Pin<&'a mut P>,
+    level: bool,
+    pending: bool,
+}
+
+/// Cancels the wait on the pin if dropped before completing.
+#[derive(Debug)]
+pub struct WaitForEdge<'a, P: Input + ?Sized> {
+    pin: Pin<&'a mut P>,
+    edge: Edge,
+    pending: bool,
+}
+
+impl<'a, P: Input + ?Sized> WaitForLevel<'a, P> {
+    fn new(pin: Pin<&'a mut P>, level: bool) -> Self {
+        WaitForLevel {
+            pin,
+            level,
+            pending: false,
+        }
+    }
+}
+
+impl<'a, P: Input + ?Sized> Future for WaitForLevel<'a, P> {
+    type Output = ();
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let level = self.level;
+        let poll = self.pin.as_mut().poll_level(cx, level);
+        self.pending = poll.is_pending();
+        poll
+    }
+}
+
+impl<'a, P: Input + ?Sized> Drop for WaitForLevel<'a, P> {
+    fn drop(&mut self) {
+        if self.pending {
+            self.pin.as_mut().cancel_wait();
+        }
+    }
+}
+
+impl<'a, P: Input + ?Sized> WaitForEdge<'a, P> {
+    fn new(pin: Pin<&'a mut P>, edge: Edge) -> Self {
+        WaitForEdge {
+            pin,
+            edge,
+            pending: false,
+        }
+    }
+}
+
+impl<'a, P: Input + ?Sized> Future for WaitForEdge<'a, P> {
+    type Output = ();
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let edge = self.edge;
+        let poll = self.pin.as_mut().poll_edge(cx, edge);
+        self.pending = poll.is_pending();
+        poll
+    }
+}
+
+impl<'a, P: Input + ?Sized> Drop for WaitForEdge<'a, P> {
+    fn drop(&mut self) {
+        if self.pending {
+            self.pin.as_mut().cancel_wait();
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod input;
+mod output;
+mod port;
+
+pub use self::{
+    input::{Edge, Input, WaitForEdge, WaitForLevel},
+    output::Output,
+    port::{InputPort, Port},
+};
@@ -0,0 +1,34 @@
+/// A group of output pins driven together, bit `n` of each mask and state is
+/// the `n`th pin of the group.
+pub trait Port {
+    /// The level each pin is being driven to.
+    fn state(&self) -> u32;
+
+    /// Drives the pins selected by `mask` to their bit of `state`, the other
+    /// pins are left unchanged.
+    ///
+    /// This is not atomic across the group, implementations may change the
+    /// pins going high and those going low with separate writes, so a pin
+    /// can briefly be seen in its new state while another is still in its
+    /// old one.
+    fn set_state(&self, mask: u32, state: u32);
+
+    fn set_high(&self, mask: u32) {
+        self.set_state(mask, !0);
+    }
+
+    fn set_low(&self, mask: u32) {
+        self.set_state(mask, 0);
+    }
+
+    fn toggle(&self, mask: u32) {
+        self.set_state(mask, !self.state());
+    }
+}
+
+/// A group of input pins read together, bit `n` of the state is the `n`th pin
+/// of the group.
+pub trait InputPort {
+    /// The level of each pin, all sampled at once.
+    fn state(&self) -> u32;
+}
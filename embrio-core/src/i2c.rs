@@ -0,0 +1,158 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    slice,
+    task::{self, Poll},
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error<E> {
+    /// No device acknowledged the address
+    AddressNack,
+    /// The device did not acknowledge a written byte
+    DataNack,
+    /// Another master took control of the bus
+    ArbitrationLost,
+    Other(E),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(err: E) -> Self {
+        Error::Other(err)
+    }
+}
+
+#[derive(Debug)]
+pub enum Operation<'a> {
+    Read(&'a mut [u8]),
+    Write(&'a [u8]),
+}
+
+/// A bus master using 7-bit addressing.
+pub trait I2c {
+    type Error: Debug;
+
+    /// Run `operations` as a single transaction with `address`, adjacent
+    /// operations in the same direction are merged and a repeated start is
+    /// generated whenever the direction changes.
+    ///
+    /// Once this has returned `Pending` it must be polled again with the same
+    /// arguments until it completes, or [`abort`](I2c::abort)ed.
+    fn poll_transaction(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Poll<Result<(), Error<Self::Error>>>;
+
+    /// Stop any in progress transaction, releasing the bus.
+    fn abort(self: Pin<&mut Self>);
+
+    fn transaction<'a, 'b>(
+        self: Pin<&'a mut Self>,
+        address: u8,
+        operations: &'a mut [Operation<'b>],
+    ) -> Transaction<'a, 'b, Self> {
+        Transaction::new(self, address, Operations::Many(operations))
+    }
+
+    fn write<'a>(
+        self: Pin<&'a mut Self>,
+        address: u8,
+        buf: &'a [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        let operations = Operations::One(Operation::Write(buf));
+        Transaction::new(self, address, operations)
+    }
+
+    fn read<'a>(
+        self: Pin<&'a mut Self>,
+        address: u8,
+        buf: &'a mut [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        let operations = Operations::One(Operation::Read(buf));
+        Transaction::new(self, address, operations)
+    }
+
+    fn write_read<'a>(
+        self: Pin<&'a mut Self>,
+        address: u8,
+        write: &'a [u8],
+        read: &'a mut [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        let operations =
+            Operations::Two([Operation::Write(write), Operation::Read(read)]);
+        Transaction::new(self, address, operations)
+    }
+}
+
+#[derive(Debug)]
+enum Operations<'a, 'b> {
+    One(Operation<'b>),
+    Two([Operation<'b>; 2]),
+    Many(&'a mut [Operation<'b>]),
+}
+
+impl<'a, 'b> Operations<'a, 'b> {
+    fn as_mut_slice(&mut self) -> &mut [Operation<'b>] {
+        match self {
+            Operations::One(operation) => slice::from_mut(operation),
+            Operations::Two(operations) => operations,
+            Operations::Many(operations) => operations,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Transaction<'a, 'b, I: I2c + ?Sized> {
+    i2c: Pin<&'a mut I>,
+    address: u8,
+    operations: Operations<'a, 'b>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, 'b, I: I2c + ?Sized> Transaction<'a, 'b, I> {
+    fn new(
+        i2c: Pin<&'a mut I>,
+        address: u8,
+        operations: Operations<'a, 'b>,
+    ) -> Self {
+        Transaction {
+            i2c,
+            address,
+            operations,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+impl<'a, 'b, I: I2c + ?Sized> Future for Transaction<'a, 'b, I> {
+    type Output = Result<(), Error<I::Error>>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.done, "Transaction polled after completion");
+        this.started = true;
+        let result = this.i2c.as_mut().poll_transaction(
+            cx,
+            this.address,
+            this.operations.as_mut_slice(),
+        );
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, 'b, I: I2c + ?Sized> Drop for Transaction<'a, 'b, I> {
+    fn drop(&mut self) {
+        if self.started && !self.done {
+            self.i2c.as_mut().abort();
+        }
+    }
+}
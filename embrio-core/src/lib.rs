@@ -1,6 +1,12 @@
 #![no_std]
 #![feature(arbitrary_self_types, const_fn, never_type)]
 
+pub mod adc;
 pub mod gpio;
+pub mod i2c;
 pub mod io;
+pub mod pwm;
+pub mod spi;
+pub mod storage;
 pub mod timer;
+pub mod watchdog;
@@ -0,0 +1,30 @@
+use core::{fmt::Debug, time::Duration};
+
+/// A set of output channels sharing a single period.
+///
+/// Duty cycles are given in ticks of the underlying counter, from `0` (always
+/// inactive) up to [`max_duty`](Pwm::max_duty) (always active).
+pub trait Pwm {
+    type Channel: Copy;
+
+    type Error: Debug;
+
+    fn period(&self) -> Duration;
+
+    /// Change the period, the duty cycle of each channel is rescaled to keep
+    /// the same ratio.
+    fn set_period(&mut self, period: Duration) -> Result<(), Self::Error>;
+
+    /// The duty cycle corresponding to always active, this changes along with
+    /// the period.
+    fn max_duty(&self) -> u32;
+
+    fn duty(&self, channel: Self::Channel) -> u32;
+
+    /// Values above [`max_duty`](Pwm::max_duty) are clamped to it.
+    fn set_duty(&mut self, channel: Self::Channel, duty: u32);
+
+    fn enable(&mut self, channel: Self::Channel);
+
+    fn disable(&mut self, channel: Self::Channel);
+}
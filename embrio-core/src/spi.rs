@@ -0,0 +1,270 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    slice,
+    task::{self, Poll},
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Polarity {
+    IdleLow,
+    IdleHigh,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Phase {
+    CaptureOnFirstTransition,
+    CaptureOnSecondTransition,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Mode {
+    pub polarity: Polarity,
+    pub phase: Phase,
+}
+
+pub const MODE_0: Mode = Mode {
+    polarity: Polarity::IdleLow,
+    phase: Phase::CaptureOnFirstTransition,
+};
+
+pub const MODE_1: Mode = Mode {
+    polarity: Polarity::IdleLow,
+    phase: Phase::CaptureOnSecondTransition,
+};
+
+pub const MODE_2: Mode = Mode {
+    polarity: Polarity::IdleHigh,
+    phase: Phase::CaptureOnFirstTransition,
+};
+
+pub const MODE_3: Mode = Mode {
+    polarity: Polarity::IdleHigh,
+    phase: Phase::CaptureOnSecondTransition,
+};
+
+/// A bus master without any chip select handling.
+///
+/// Once a `poll_*` method has returned `Pending` it must be polled again with
+/// the same buffer until it completes or is aborted, the bytes may already be
+/// in flight.
+pub trait Bus {
+    type Error: Debug;
+
+    /// Write each byte of `buf` and replace it with the byte read at the same
+    /// time.
+    fn poll_transfer(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>>;
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>>;
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>>;
+
+    /// Wait for all written bytes to have been clocked out.
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>>;
+
+    /// Give up on an operation that returned `Pending`, so the next one
+    /// starts afresh rather than picking up its result.
+    fn abort(self: Pin<&mut Self>) {}
+}
+
+#[derive(Debug)]
+pub enum Operation<'a> {
+    Read(&'a mut [u8]),
+    Write(&'a [u8]),
+    Transfer(&'a mut [u8]),
+}
+
+/// A single device on a bus, selecting it acquires the bus and asserts its
+/// chip select.
+pub trait Device {
+    type Bus: Bus;
+
+    fn poll_select(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), <Self::Bus as Bus>::Error>>;
+
+    /// Access the bus, must only be used while the device is selected.
+    fn bus(self: Pin<&mut Self>) -> Pin<&mut Self::Bus>;
+
+    fn deselect(self: Pin<&mut Self>);
+
+    /// Run all `operations` with the device selected throughout.
+    fn transaction<'a, 'b>(
+        self: Pin<&'a mut Self>,
+        operations: &'a mut [Operation<'b>],
+    ) -> Transaction<'a, 'b, Self> {
+        Transaction::new(self, Operations::Many(operations))
+    }
+
+    fn transfer<'a>(
+        self: Pin<&'a mut Self>,
+        buf: &'a mut [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        Transaction::new(self, Operations::One(Operation::Transfer(buf)))
+    }
+
+    fn write<'a>(
+        self: Pin<&'a mut Self>,
+        buf: &'a [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        Transaction::new(self, Operations::One(Operation::Write(buf)))
+    }
+
+    fn read<'a>(
+        self: Pin<&'a mut Self>,
+        buf: &'a mut [u8],
+    ) -> Transaction<'a, 'a, Self> {
+        Transaction::new(self, Operations::One(Operation::Read(buf)))
+    }
+}
+
+#[derive(Debug)]
+enum Operations<'a, 'b> {
+    One(Operation<'b>),
+    Many(&'a mut [Operation<'b>]),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum State {
+    Selecting,
+    Running { index: usize, position: usize },
+    Flushing,
+    Done,
+}
+
+#[derive(Debug)]
+pub struct Transaction<'a, 'b, D: Device + ?Sized> {
+    device: Pin<&'a mut D>,
+    operations: Operations<'a, 'b>,
+    state: State,
+}
+
+impl<'a, 'b> Operations<'a, 'b> {
+    fn as_mut_slice(&mut self) -> &mut [Operation<'b>] {
+        match self {
+            Operations::One(operation) => slice::from_mut(operation),
+            Operations::Many(operations) => operations,
+        }
+    }
+}
+
+impl<'a, 'b, D: Device + ?Sized> Transaction<'a, 'b, D> {
+    fn new(device: Pin<&'a mut D>, operations: Operations<'a, 'b>) -> Self {
+        Transaction {
+            device,
+            operations,
+            state: State::Selecting,
+        }
+    }
+
+    fn finish<T>(&mut self, result: T) -> Poll<T> {
+        self.state = State::Done;
+        self.device.as_mut().deselect();
+        Poll::Ready(result)
+    }
+}
+
+impl<'a, 'b, D: Device + ?Sized> Future for Transaction<'a, 'b, D> {
+    type Output = Result<(), <D::Bus as Bus>::Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            match this.state {
+                State::Selecting => {
+                    match this.device.as_mut().poll_select(cx) {
+                        Poll::Ready(Ok(())) => {}
+                        Poll::Ready(Err(err)) => {
+                            this.state = State::Done;
+                            return Poll::Ready(Err(err));
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                    this.state = State::Running {
+                        index: 0,
+                        position: 0,
+                    };
+                }
+                State::Running { index, position } => {
+                    let operations = this.operations.as_mut_slice();
+                    let operation = match operations.get_mut(index) {
+                        Some(operation) => operation,
+                        None => {
+                            this.state = State::Flushing;
+                            continue;
+                        }
+                    };
+                    let bus = this.device.as_mut().bus();
+                    let result = match operation {
+                        Operation::Read(buf) if position < buf.len() => {
+                            bus.poll_read(cx, &mut buf[position..])
+                        }
+                        Operation::Write(buf) if position < buf.len() => {
+                            bus.poll_write(cx, &buf[position..])
+                        }
+                        Operation::Transfer(buf) if position < buf.len() => {
+                            bus.poll_transfer(cx, &mut buf[position..])
+                        }
+                        _ => {
+                            this.state = State::Running {
+                                index: index + 1,
+                                position: 0,
+                            };
+                            continue;
+                        }
+                    };
+                    match result {
+                        Poll::Ready(Ok(amount)) => {
+                            this.state = State::Running {
+                                index,
+                                position: position + amount,
+                            };
+                        }
+                        Poll::Ready(Err(err)) => return this.finish(Err(err)),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                State::Flushing => {
+                    match this.device.as_mut().bus().poll_flush(cx) {
+                        Poll::Ready(result) => return this.finish(result),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                State::Done => panic!("Transaction polled after completion"),
+            }
+        }
+    }
+}
+
+impl<'a, 'b, D: Device + ?Sized> Drop for Transaction<'a, 'b, D> {
+    fn drop(&mut self) {
+        // Don't leave the device selected if cancelled part way through
+        match self.state {
+            State::Running { .. } | State::Flushing => {
+                self.device.as_mut().bus().abort();
+                self.device.as_mut().deselect()
+            }
+            State::Selecting | State::Done => {}
+        }
+    }
+}
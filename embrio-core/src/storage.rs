@@ -0,0 +1,232 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error<E> {
+    /// The range extends past the end of the storage
+    OutOfBounds,
+    /// The offset or length is not a multiple of the required alignment
+    Unaligned,
+    Other(E),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(err: E) -> Self {
+        Error::Other(err)
+    }
+}
+
+/// Non-volatile storage with flash-like semantics.
+///
+/// Erasing sets every byte of an erase unit to `0xFF`, writing can only clear
+/// bits and each write unit must be written at most once between erases.
+///
+/// Once any of the `poll_*` methods has returned `Pending` it must be polled
+/// again with the same arguments until it completes. An operation that is
+/// dropped part way may have been applied to only part of its range.
+pub trait Storage {
+    type Error: Debug;
+
+    /// Total size in bytes.
+    fn capacity(&self) -> usize;
+
+    /// Offsets and lengths of reads must be multiples of this.
+    fn read_size(&self) -> usize;
+
+    /// Offsets and lengths of writes must be multiples of this.
+    fn write_size(&self) -> usize;
+
+    /// Offsets and lengths of erases must be multiples of this.
+    fn erase_size(&self) -> usize;
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Poll<Result<(), Error<Self::Error>>>;
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        data: &[u8],
+    ) -> Poll<Result<(), Error<Self::Error>>>;
+
+    /// Erase `len` bytes starting from `offset`.
+    fn poll_erase(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        len: usize,
+    ) -> Poll<Result<(), Error<Self::Error>>>;
+
+    fn read<'a>(
+        self: Pin<&'a mut Self>,
+        offset: usize,
+        buf: &'a mut [u8],
+    ) -> Read<'a, Self> {
+        Read {
+            storage: self,
+            offset,
+            buf,
+        }
+    }
+
+    fn write<'a>(
+        self: Pin<&'a mut Self>,
+        offset: usize,
+        data: &'a [u8],
+    ) -> Write<'a, Self> {
+        Write {
+            storage: self,
+            offset,
+            data,
+        }
+    }
+
+    fn erase(
+        self: Pin<&mut Self>,
+        offset: usize,
+        len: usize,
+    ) -> Erase<'_, Self> {
+        Erase {
+            storage: self,
+            offset,
+            len,
+        }
+    }
+}
+
+/// Checks an operation of `len` bytes at `offset` against the capacity and
+/// an alignment of `align`, for implementations to share.
+pub fn check<E>(
+    capacity: usize,
+    align: usize,
+    offset: usize,
+    len: usize,
+) -> Result<(), Error<E>> {
+    if offset % align != 0 || len % align != 0 {
+        return Err(Error::Unaligned);
+    }
+    match offset.checked_add(len) {
+        Some(end) if end <= capacity => Ok(()),
+        _ => Err(Error::OutOfBounds),
+    }
+}
+
+impl<S> Storage for Pin<&mut S>
+where
+    S: Storage,
+{
+    type Error = <S as Storage>::Error;
+
+    fn capacity(&self) -> usize {
+        <S as Storage>::capacity(&**self)
+    }
+
+    fn read_size(&self) -> usize {
+        <S as Storage>::read_size(&**self)
+    }
+
+    fn write_size(&self) -> usize {
+        <S as Storage>::write_size(&**self)
+    }
+
+    fn erase_size(&self) -> usize {
+        <S as Storage>::erase_size(&**self)
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Poll<Result<(), Error<Self::Error>>> {
+        <S as Storage>::poll_read(Pin::get_mut(self).as_mut(), cx, offset, buf)
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        data: &[u8],
+    ) -> Poll<Result<(), Error<Self::Error>>> {
+        <S as Storage>::poll_write(
+            Pin::get_mut(self).as_mut(),
+            cx,
+            offset,
+            data,
+        )
+    }
+
+    fn poll_erase(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        len: usize,
+    ) -> Poll<Result<(), Error<Self::Error>>> {
+        <S as Storage>::poll_erase(Pin::get_mut(self).as_mut(), cx, offset, len)
+    }
+}
+
+#[derive(Debug)]
+pub struct Read<'a, S: Storage + ?Sized> {
+    storage: Pin<&'a mut S>,
+    offset: usize,
+    buf: &'a mut [u8],
+}
+
+#[derive(Debug)]
+pub struct Write<'a, S: Storage + ?Sized> {
+    storage: Pin<&'a mut S>,
+    offset: usize,
+    data: &'a [u8],
+}
+
+#[derive(Debug)]
+pub struct Erase<'a, S: Storage + ?Sized> {
+    storage: Pin<&'a mut S>,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a, S: Storage + ?Sized> Future for Read<'a, S> {
+    type Output = Result<(), Error<S::Error>>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.storage.as_mut().poll_read(cx, this.offset, this.buf)
+    }
+}
+
+impl<'a, S: Storage + ?Sized> Future for Write<'a, S> {
+    type Output = Result<(), Error<S::Error>>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.storage.as_mut().poll_write(cx, this.offset, this.data)
+    }
+}
+
+impl<'a, S: Storage + ?Sized> Future for Erase<'a, S> {
+    type Output = Result<(), Error<S::Error>>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.storage.as_mut().poll_erase(cx, this.offset, this.len)
+    }
+}
@@ -1,4 +1,4 @@
-use core::{future::Future, time::Duration};
+use core::{future::Future, pin::Pin, time::Duration};
 
 use futures_core::stream::Stream;
 
@@ -12,6 +12,10 @@ pub trait Timer: Sized {
     fn timeout(self, duration: Duration) -> Self::Timeout;
 
     fn interval(self, duration: Duration) -> Self::Interval;
+
+    /// Stop a pending timeout early, getting the timer back unless the
+    /// timeout had already completed.
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self>;
 }
 
 /* TODO: Use this
@@ -0,0 +1,4 @@
+/// A watchdog that resets the device unless it is fed before it times out.
+pub trait Watchdog {
+    fn feed(&mut self);
+}
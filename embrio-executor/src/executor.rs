@@ -13,6 +13,7 @@ use crate::waker::EmbrioWaker;
 /// See the [crate docs](crate) for more details.
 pub struct Executor {
     waker: EmbrioWaker,
+    sleep_hook: Option<fn()>,
 }
 
 impl Executor {
@@ -22,6 +23,19 @@ impl Executor {
     pub const fn new() -> Executor {
         Executor {
             waker: EmbrioWaker::new(),
+            sleep_hook: None,
+        }
+    }
+
+    /// Create a new instance of [`Executor`] which calls `hook` each time
+    /// before it sleeps waiting to be woken, e.g. to stop clocks that are no
+    /// longer in use.
+    ///
+    /// See the [crate docs](crate) for more details.
+    pub const fn with_sleep_hook(hook: fn()) -> Executor {
+        Executor {
+            waker: EmbrioWaker::new(),
+            sleep_hook: Some(hook),
         }
     }
 
@@ -40,6 +54,9 @@ impl Executor {
                 return val;
             } else {
                 while !self.waker.test_and_clear() {
+                    if let Some(hook) = self.sleep_hook {
+                        hook();
+                    }
                     EmbrioWaker::sleep()
                 }
             }
@@ -14,6 +14,11 @@
 //! This is less efficient as it uses a busy-loop while idle, but is useful for
 //! testing code on a "native" target (i.e. your machine).
 //!
+//! The executor keeps no time of its own, a future waiting on a timer is woken
+//! by that timer's interrupt like any other. It is up to the chip crates'
+//! timers how much stays powered while sleeping, e.g. the nRF RTCs let the
+//! HFCLK stop where the TIMERs keep it running.
+//!
 //! # Safety
 //!
 //! You'll note that the signature for [`Executor::block_on`] takes `&'static
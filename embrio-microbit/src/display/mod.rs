@@ -0,0 +1,184 @@
+use core::{
+    future::Future,
+    iter,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::{gpio::Port as _, timer::Timer};
+use embrio_nrf51::gpio::{
+    mode::{Output, PushPull},
+    AnyPin, Port,
+};
+use embrio_util::display::{Scroll, MICROBIT};
+use futures_core::stream::Stream;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+
+pub use embrio_util::display::{glyph, Frame, HEIGHT, WIDTH};
+
+/// How long each electrical row is lit for while scanning.
+pub const ROW_PERIOD: Duration = Duration::from_millis(2);
+
+/// Number of rows in the electrical matrix, only one is lit at a time.
+const MATRIX_ROWS: usize = 3;
+
+/// Number of columns in the electrical matrix.
+const MATRIX_COLS: usize = 9;
+
+const ROWS_MASK: u32 = (1 << MATRIX_ROWS) - 1;
+const COLS_MASK: u32 = (1 << MATRIX_COLS) - 1;
+
+/// The 5x5 LED matrix, wired as 3 rows driven high and 9 columns driven low.
+///
+/// Only one row can be lit at a time, so frames are only visible while a
+/// [`Play`] is scanning them.
+#[derive(Debug)]
+pub struct Display<'b> {
+    rows: Port<'b, Output<PushPull>, MATRIX_ROWS>,
+    cols: Port<'b, Output<PushPull>, MATRIX_COLS>,
+}
+
+pin_project! {
+    /// Scans frames onto the [`Display`], showing each for a fixed duration.
+    ///
+    /// The display is blanked once all frames have been shown or this is
+    /// dropped.
+    pub struct Play<'a, 'b, T: Timer, I> {
+        display: Blank<'a, 'b>,
+        #[pin]
+        interval: T::Interval,
+        frames: I,
+        matrix: [u16; MATRIX_ROWS],
+        row: usize,
+        ticks_per_frame: u32,
+        ticks_left: u32,
+    }
+}
+
+/// Blanks the display when dropped.
+struct Blank<'a, 'b>(&'a mut Display<'b>);
+
+impl<'b> Display<'b> {
+    pub fn new(
+        rows: [AnyPin<'b, Output<PushPull>>; MATRIX_ROWS],
+        cols: [AnyPin<'b, Output<PushPull>>; MATRIX_COLS],
+    ) -> Self {
+        let display = Display {
+            rows: Port::new(rows),
+            cols: Port::new(cols),
+        };
+        display.clear();
+        display
+    }
+
+    pub fn free(
+        self,
+    ) -> (
+        [AnyPin<'b, Output<PushPull>>; MATRIX_ROWS],
+        [AnyPin<'b, Output<PushPull>>; MATRIX_COLS],
+    ) {
+        (self.rows.free(), self.cols.free())
+    }
+
+    /// Shows each frame for `duration` in turn.
+    pub fn play<T: Timer, I: Iterator<Item = Frame>>(
+        &mut self,
+        timer: T,
+        frames: I,
+        duration: Duration,
+    ) -> Play<'_, 'b, T, I> {
+        let ticks = duration.as_micros() / ROW_PERIOD.as_micros();
+        Play {
+            display: Blank(self),
+            interval: timer.interval(ROW_PERIOD),
+            frames,
+            matrix: [0; MATRIX_ROWS],
+            row: 0,
+            ticks_per_frame: ticks.max(1) as u32,
+            ticks_left: 0,
+        }
+    }
+
+    pub fn show<T: Timer>(
+        &mut self,
+        timer: T,
+        frame: Frame,
+        duration: Duration,
+    ) -> Play<'_, 'b, T, iter::Once<Frame>> {
+        self.play(timer, iter::once(frame), duration)
+    }
+
+    /// Scrolls `text` across the display, moving one column every `step`.
+    pub fn scroll<'c, T: Timer>(
+        &mut self,
+        timer: T,
+        text: &'c str,
+        step: Duration,
+    ) -> Play<'_, 'b, T, Scroll<'c>> {
+        self.play(timer, Scroll::new(text), step)
+    }
+
+    fn clear(&self) {
+        self.rows.set_low(ROWS_MASK);
+        self.cols.set_high(COLS_MASK);
+    }
+
+    fn light(&self, row: usize, cols: u16) {
+        // Turn the row off while changing columns to avoid ghosting
+        self.rows.set_low(ROWS_MASK);
+        self.cols.set_state(COLS_MASK, !u32::from(cols));
+        self.rows.set_high(1 << row);
+    }
+}
+
+impl<'a, 'b, T: Timer, I: Iterator<Item = Frame>> Future
+    for Play<'a, 'b, T, I>
+{
+    type Output = Result<(), T::Error>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let display = &this.display.0;
+
+        loop {
+            match ready!(this.interval.as_mut().poll_next(cx)) {
+                Some(Ok(())) => {
+                    if *this.ticks_left == 0 {
+                        match this.frames.next() {
+                            Some(frame) => {
+                                frame.matrix(&MICROBIT, &mut this.matrix[..])
+                            }
+                            None => {
+                                display.clear();
+                                return Poll::Ready(Ok(()));
+                            }
+                        }
+                        *this.ticks_left = *this.ticks_per_frame;
+                    }
+                    *this.ticks_left -= 1;
+                    display.light(*this.row, this.matrix[*this.row]);
+                    *this.row = (*this.row + 1) % MATRIX_ROWS;
+                }
+                Some(Err(err)) => {
+                    display.clear();
+                    return Poll::Ready(Err(err));
+                }
+                None => {
+                    display.clear();
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'b> Drop for Blank<'a, 'b> {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
+}
@@ -0,0 +1,108 @@
+//! Board support for the BBC micro:bit, built from the pins of an
+//! [`EmbrioNrf51`](embrio_nrf51::EmbrioNrf51).
+
+#![no_std]
+
+pub mod display;
+
+use embrio_nrf51::gpio::{
+    mode::{Floating, Input, Output, PushPull, Unconfigured},
+    Pin, Pins,
+};
+
+use self::display::Display;
+
+/// The board's named pins, taken from [`Pins`].
+///
+/// Edge connector pins 3-7 and 9-11 are shared with the display and buttons
+/// so are only available through those.
+#[derive(Debug)]
+pub struct Board<'b> {
+    pub display: Display<'b>,
+    pub buttons: Buttons<'b>,
+    pub edge: Edge<'b>,
+    pub uart: Uart<'b>,
+}
+
+/// The buttons read low while pressed, they have external pull-ups.
+#[derive(Debug)]
+pub struct Buttons<'b> {
+    pub a: Pin<'b, 17, Input<Floating>>,
+    pub b: Pin<'b, 26, Input<Floating>>,
+}
+
+/// The edge connector pins not used on the board itself.
+#[derive(Debug)]
+pub struct Edge<'b> {
+    pub p0: Pin<'b, 3, Unconfigured>,
+    pub p1: Pin<'b, 2, Unconfigured>,
+    pub p2: Pin<'b, 1, Unconfigured>,
+    pub p8: Pin<'b, 18, Unconfigured>,
+    pub p12: Pin<'b, 20, Unconfigured>,
+    /// SPI SCK by convention.
+    pub p13: Pin<'b, 23, Unconfigured>,
+    /// SPI MISO by convention.
+    pub p14: Pin<'b, 22, Unconfigured>,
+    /// SPI MOSI by convention.
+    pub p15: Pin<'b, 21, Unconfigured>,
+    pub p16: Pin<'b, 16, Unconfigured>,
+    /// I2C SCL, shared with the accelerometer and magnetometer.
+    pub p19: Pin<'b, 0, Unconfigured>,
+    /// I2C SDA, shared with the accelerometer and magnetometer.
+    pub p20: Pin<'b, 30, Unconfigured>,
+}
+
+/// Connected to the interface chip, which bridges them to USB serial.
+#[derive(Debug)]
+pub struct Uart<'b> {
+    pub tx: Pin<'b, 24, Output<PushPull>>,
+    pub rx: Pin<'b, 25, Input<Floating>>,
+}
+
+impl<'b> Board<'b> {
+    pub fn new(pins: Pins<'b>) -> Self {
+        let display = Display::new(
+            [
+                pins.p0_13.output().push_pull().degrade(),
+                pins.p0_14.output().push_pull().degrade(),
+                pins.p0_15.output().push_pull().degrade(),
+            ],
+            [
+                pins.p0_04.output().push_pull().degrade(),
+                pins.p0_05.output().push_pull().degrade(),
+                pins.p0_06.output().push_pull().degrade(),
+                pins.p0_07.output().push_pull().degrade(),
+                pins.p0_08.output().push_pull().degrade(),
+                pins.p0_09.output().push_pull().degrade(),
+                pins.p0_10.output().push_pull().degrade(),
+                pins.p0_11.output().push_pull().degrade(),
+                pins.p0_12.output().push_pull().degrade(),
+            ],
+        );
+
+        Board {
+            display,
+            buttons: Buttons {
+                a: pins.p0_17.input().floating(),
+                b: pins.p0_26.input().floating(),
+            },
+            edge: Edge {
+                p0: pins.p0_03,
+                p1: pins.p0_02,
+                p2: pins.p0_01,
+                p8: pins.p0_18,
+                p12: pins.p0_20,
+                p13: pins.p0_23,
+                p14: pins.p0_22,
+                p15: pins.p0_21,
+                p16: pins.p0_16,
+                p19: pins.p0_00,
+                p20: pins.p0_30,
+            },
+            uart: Uart {
+                tx: pins.p0_24.output().push_pull(),
+                rx: pins.p0_25.input().floating(),
+            },
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{self, Poll, Waker},
+};
+
+use embrio_core::gpio::{Edge, Input};
+
+#[derive(Debug)]
+struct State {
+    level: bool,
+    /// Counts of each edge so far, so waits see edges between polls.
+    rises: usize,
+    falls: usize,
+    wakers: Vec<Waker>,
+}
+
+/// An input pin whose level is driven from the host, cloned handles all
+/// share the same level.
+#[derive(Clone, Debug)]
+pub struct SimulatedPin {
+    state: Arc<Mutex<State>>,
+    /// The edge counts when the current edge wait started.
+    edge_wait: Option<(usize, usize)>,
+}
+
+impl SimulatedPin {
+    pub fn new(level: bool) -> Self {
+        SimulatedPin {
+            state: Arc::new(Mutex::new(State {
+                level,
+                rises: 0,
+                falls: 0,
+                wakers: Vec::new(),
+            })),
+            edge_wait: None,
+        }
+    }
+
+    pub fn set_level(&self, level: bool) {
+        let mut state = self.state.lock().unwrap();
+        if state.level != level {
+            state.level = level;
+            if level {
+                state.rises += 1;
+            } else {
+                state.falls += 1;
+            }
+            for waker in state.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    pub fn set_high(&self) {
+        self.set_level(true);
+    }
+
+    pub fn set_low(&self) {
+        self.set_level(false);
+    }
+}
+
+impl Input for SimulatedPin {
+    fn state(&self) -> bool {
+        self.state.lock().unwrap().level
+    }
+
+    fn poll_level(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        level: bool,
+    ) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.level == level {
+            Poll::Ready(())
+        } else {
+            state.register(cx.waker());
+            Poll::Pending
+        }
+    }
+
+    fn poll_edge(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        edge: Edge,
+    ) -> Poll<()> {
+        let this = Pin::get_mut(self);
+        let mut state = this.state.lock().unwrap();
+        let (rises, falls) =
+            *this.edge_wait.get_or_insert((state.rises, state.falls));
+        let rose = state.rises > rises;
+        let fell = state.falls > falls;
+        let seen = match edge {
+            Edge::Rising => rose,
+            Edge::Falling => fell,
+            Edge::Any => rose || fell,
+        };
+        if seen {
+            this.edge_wait = None;
+            Poll::Ready(())
+        } else {
+            state.register(cx.waker());
+            Poll::Pending
+        }
+    }
+
+    fn cancel_wait(self: Pin<&mut Self>) {
+        Pin::get_mut(self).edge_wait = None;
+    }
+}
+
+impl State {
+    fn register(&mut self, waker: &Waker) {
+        if !self.wakers.iter().any(|w| w.will_wake(waker)) {
+            self.wakers.push(waker.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::future::Future;
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    fn poll(future: &mut (impl Future<Output = ()> + Unpin)) -> Poll<()> {
+        Pin::new(future).poll(&mut noop_context())
+    }
+
+    #[test]
+    fn level() {
+        let mut pin = SimulatedPin::new(false);
+        let driver = pin.clone();
+
+        assert!(poll(&mut Pin::new(&mut pin).wait_for_low()).is_ready());
+
+        let mut high = Pin::new(&mut pin).wait_for_high();
+        assert!(poll(&mut high).is_pending());
+        driver.set_high();
+        assert!(poll(&mut high).is_ready());
+    }
+
+    #[test]
+    fn edges() {
+        let mut pin = SimulatedPin::new(true);
+        let driver = pin.clone();
+
+        {
+            let mut rising = Pin::new(&mut pin).wait_for_rising_edge();
+            assert!(poll(&mut rising).is_pending());
+            driver.set_low();
+            assert!(poll(&mut rising).is_pending());
+            driver.set_high();
+            assert!(poll(&mut rising).is_ready());
+        }
+
+        {
+            let mut falling = Pin::new(&mut pin).wait_for_falling_edge();
+            assert!(poll(&mut falling).is_pending());
+            driver.set_low();
+            assert!(poll(&mut falling).is_ready());
+        }
+
+        let mut any = Pin::new(&mut pin).wait_for_any_edge();
+        assert!(poll(&mut any).is_pending());
+        driver.set_high();
+        assert!(poll(&mut any).is_ready());
+    }
+
+    #[test]
+    fn pulses_between_polls() {
+        let mut pin = SimulatedPin::new(false);
+        let driver = pin.clone();
+
+        let mut rising = Pin::new(&mut pin).wait_for_rising_edge();
+        assert!(poll(&mut rising).is_pending());
+        driver.set_high();
+        driver.set_low();
+        assert!(poll(&mut rising).is_ready());
+    }
+
+    #[test]
+    fn cancelled_edge() {
+        let mut pin = SimulatedPin::new(false);
+        let driver = pin.clone();
+
+        {
+            let mut rising = Pin::new(&mut pin).wait_for_rising_edge();
+            assert!(poll(&mut rising).is_pending());
+        }
+        driver.set_high();
+        driver.set_low();
+
+        // A new wait doesn't see the edge from before it started
+        let mut rising = Pin::new(&mut pin).wait_for_rising_edge();
+        assert!(poll(&mut rising).is_pending());
+    }
+
+    #[test]
+    fn wakes() {
+        let mut pin = SimulatedPin::new(false);
+        let driver = pin.clone();
+        let (waker, count) = new_count_waker();
+        let mut cx = task::Context::from_waker(&waker);
+
+        let mut high = Pin::new(&mut pin).wait_for_high();
+        assert!(Pin::new(&mut high).poll(&mut cx).is_pending());
+        assert_eq!(count.get(), 0);
+        driver.set_high();
+        assert_eq!(count.get(), 1);
+        assert!(Pin::new(&mut high).poll(&mut cx).is_ready());
+    }
+}
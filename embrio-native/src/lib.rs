@@ -2,7 +2,9 @@
 
 use embrio_core::io::{Read, Write};
 
+pub mod gpio;
 mod io;
+pub mod timer;
 
 pub struct EmbrioNative(());
 
@@ -0,0 +1,223 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+
+use embrio_core::timer::Timer;
+use futures_core::stream::Stream;
+
+#[derive(Debug, Default)]
+struct State {
+    now: Duration,
+    deadlines: Vec<Duration>,
+    wakers: Vec<Waker>,
+}
+
+/// Virtual time driven from the host, it only moves forward when advanced so
+/// timing can be tested without waiting on the wall clock. Cloned handles all
+/// share the same time.
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    state: Arc<Mutex<State>>,
+}
+
+/// A [`Timer`] running on a [`Clock`].
+#[derive(Debug)]
+pub struct VirtualTimer {
+    clock: Clock,
+}
+
+#[derive(Debug)]
+pub struct VirtualTimeout {
+    timer: Option<VirtualTimer>,
+    deadline: Duration,
+}
+
+#[derive(Debug)]
+pub struct VirtualInterval {
+    clock: Clock,
+    period: Duration,
+    deadline: Duration,
+}
+
+/// A [`Timer`] whose timeouts complete straight away and whose interval is
+/// the given stream, for drivers that only need to be told when to tick.
+#[derive(Debug)]
+pub struct StreamTimer<S> {
+    ticks: S,
+}
+
+#[derive(Debug)]
+pub struct Immediate<T>(Option<T>);
+
+impl Clock {
+    pub fn new() -> Self {
+        Clock::default()
+    }
+
+    pub fn now(&self) -> Duration {
+        self.state.lock().unwrap().now
+    }
+
+    pub fn timer(&self) -> VirtualTimer {
+        VirtualTimer {
+            clock: self.clone(),
+        }
+    }
+
+    /// The earliest deadline a timer is waiting on.
+    pub fn next_deadline(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        state
+            .deadlines
+            .iter()
+            .cloned()
+            .filter(|&at| at > state.now)
+            .min()
+    }
+
+    /// Move time forward to `at`, waking the timers waiting on it.
+    pub fn advance_to(&self, at: Duration) {
+        let mut state = self.state.lock().unwrap();
+        assert!(at >= state.now, "virtual time only moves forward");
+        state.now = at;
+        state.deadlines.retain(|&deadline| deadline > at);
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Returns whether `deadline` has passed, otherwise registering it and the
+    /// waker until time is next advanced.
+    fn poll_deadline(
+        &self,
+        cx: &mut task::Context<'_>,
+        deadline: Duration,
+    ) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.now >= deadline {
+            return true;
+        }
+        if !state.deadlines.contains(&deadline) {
+            state.deadlines.push(deadline);
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        false
+    }
+}
+
+impl Timer for VirtualTimer {
+    type Error = Infallible;
+
+    type Timeout = VirtualTimeout;
+
+    type Interval = VirtualInterval;
+
+    fn timeout(self, duration: Duration) -> Self::Timeout {
+        let deadline = self.clock.now() + duration;
+        VirtualTimeout {
+            timer: Some(self),
+            deadline,
+        }
+    }
+
+    fn interval(self, period: Duration) -> Self::Interval {
+        let deadline = self.clock.now() + period;
+        VirtualInterval {
+            clock: self.clock,
+            period,
+            deadline,
+        }
+    }
+
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self> {
+        Pin::get_mut(timeout).timer.take()
+    }
+}
+
+impl Future for VirtualTimeout {
+    type Output = Result<VirtualTimer, Infallible>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let timer = this
+            .timer
+            .as_ref()
+            .expect("VirtualTimeout polled after completion");
+        if timer.clock.poll_deadline(cx, this.deadline) {
+            Poll::Ready(Ok(this.timer.take().unwrap()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Stream for VirtualInterval {
+    type Item = Result<(), Infallible>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.clock.poll_deadline(cx, this.deadline) {
+            // Measured from the previous deadline so the ticks don't drift
+            this.deadline += this.period;
+            Poll::Ready(Some(Ok(())))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<S> StreamTimer<S> {
+    pub fn new(ticks: S) -> Self {
+        StreamTimer { ticks }
+    }
+}
+
+impl<S, E> Timer for StreamTimer<S>
+where
+    S: Stream<Item = Result<(), E>>,
+{
+    type Error = E;
+
+    type Timeout = Immediate<Result<Self, E>>;
+
+    type Interval = S;
+
+    fn timeout(self, _duration: Duration) -> Self::Timeout {
+        Immediate(Some(Ok(self)))
+    }
+
+    fn interval(self, _duration: Duration) -> Self::Interval {
+        self.ticks
+    }
+
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self> {
+        Pin::get_mut(timeout).0.take().and_then(Result::ok)
+    }
+}
+
+// The value is never pinned, only moved out
+impl<T> Unpin for Immediate<T> {}
+
+impl<T> Future for Immediate<T> {
+    type Output = T;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        Poll::Ready(self.0.take().expect("Immediate polled after completion"))
+    }
+}
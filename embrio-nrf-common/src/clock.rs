@@ -0,0 +1,168 @@
+//! The CLOCK peripheral, starting the LFCLK for the RTCs and refcounting the
+//! HFCLK crystal oscillator.
+
+use core::{cell::Cell, marker::PhantomData};
+
+use cortex_m::interrupt::{free, Mutex};
+
+use crate::Chip;
+
+/// Where the LFCLK, used by the RTCs and watchdog, is generated from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LfclkSource {
+    /// The internal RC oscillator, ±250ppm after calibration.
+    Rc,
+    /// An external 32.768kHz crystal, if the board has one.
+    Xtal,
+    /// Divided down from the HFCLK, keeping the crystal oscillator running.
+    Synth,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Task {
+    HfclkStart,
+    HfclkStop,
+    LfclkStart,
+    LfclkStop,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Event {
+    HfclkStarted,
+    LfclkStarted,
+}
+
+/// Access to the CLOCK peripheral, implemented by each chip crate's marker
+/// type over its PAC.
+pub trait Registers: Chip {
+    fn trigger(task: Task);
+    fn event(event: Event) -> bool;
+    fn clear(event: Event);
+    /// Whether the HFCLK is running from the crystal oscillator.
+    fn hfclk_xtal() -> bool;
+    fn lfclk_running() -> bool;
+    fn lfclk_source() -> LfclkSource;
+    fn set_lfclk_source(source: LfclkSource);
+}
+
+#[derive(Debug)]
+pub struct Clock<'b, C: Registers> {
+    /// Kept while the LFCLK is synthesized from it.
+    synth: Option<Hfclk<C>>,
+    _marker: PhantomData<&'b mut C>,
+}
+
+/// A reference keeping the HFCLK running from the crystal oscillator, which
+/// peripherals needing accurate timing take while in use.
+///
+/// Without any the HFCLK falls back to the internal RC oscillator, which is
+/// stopped automatically while nothing needs it.
+#[derive(Debug)]
+pub struct Hfclk<C: Registers> {
+    _marker: PhantomData<C>,
+}
+
+static HFCLK_USERS: Mutex<Cell<usize>> = Mutex::new(Cell::new(0));
+
+fn start_lfclk<C: Registers>() {
+    if C::lfclk_running() {
+        return;
+    }
+    C::clear(Event::LfclkStarted);
+    C::trigger(Task::LfclkStart);
+    while !C::event(Event::LfclkStarted) {}
+    C::clear(Event::LfclkStarted);
+}
+
+/// Stop the crystal oscillator if no [`Hfclk`] references are left.
+///
+/// Restarting it takes time, so rather than stopping it as soon as the last
+/// reference is dropped this is run before the executor sleeps, see
+/// `Executor::with_sleep_hook`.
+pub fn release_unused_hfclk<C: Registers>() {
+    free(|c| {
+        if HFCLK_USERS.borrow(c).get() == 0 && C::hfclk_xtal() {
+            C::trigger(Task::HfclkStop);
+        }
+    });
+}
+
+impl<'b, C: Registers> Clock<'b, C> {
+    /// Starts the LFCLK from the RC oscillator, so the RTCs can be used
+    /// straight away.
+    ///
+    /// # Safety
+    ///
+    /// The CLOCK peripheral must be exclusively borrowed for `'b`.
+    #[doc(hidden)]
+    pub unsafe fn new() -> Self {
+        start_lfclk::<C>();
+        Clock {
+            synth: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn lfclk_source(&self) -> LfclkSource {
+        C::lfclk_source()
+    }
+
+    /// Restart the LFCLK from `source`, blocking until it has started.
+    ///
+    /// The RTCs and watchdog pause while it's stopped, so they lose up to
+    /// the start up time of the new source.
+    pub fn set_lfclk_source(&mut self, source: LfclkSource) {
+        let synth = match source {
+            LfclkSource::Synth => Some(Hfclk::request()),
+            _ => None,
+        };
+
+        C::trigger(Task::LfclkStop);
+        while C::lfclk_running() {}
+        C::set_lfclk_source(source);
+        start_lfclk::<C>();
+
+        self.synth = synth;
+    }
+}
+
+impl<C: Registers> Hfclk<C> {
+    /// Start the crystal oscillator if it isn't already running, blocking
+    /// until it is stable.
+    ///
+    /// Only the start is triggered from a critical section, interrupts are
+    /// still handled while waiting for the crystal.
+    pub fn request() -> Self {
+        free(|c| {
+            let users = HFCLK_USERS.borrow(c);
+            users.set(users.get() + 1);
+
+            if !C::hfclk_xtal() {
+                C::clear(Event::HfclkStarted);
+                C::trigger(Task::HfclkStart);
+            }
+        });
+        // Counted as a user, so it won't be stopped while waiting
+        while !C::hfclk_xtal() {}
+
+        Hfclk {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: Registers> Clone for Hfclk<C> {
+    fn clone(&self) -> Self {
+        Hfclk::request()
+    }
+}
+
+/// The crystal keeps running until [`release_unused_hfclk`] is called.
+impl<C: Registers> Drop for Hfclk<C> {
+    fn drop(&mut self) {
+        free(|c| {
+            let users = HFCLK_USERS.borrow(c);
+            users.set(users.get() - 1);
+        });
+    }
+}
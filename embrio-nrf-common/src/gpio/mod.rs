@@ -0,0 +1,69 @@
+//! GPIO pins and ports, the PIN_CNF, OUT and IN registers and the SENSE
+//! mechanism are laid out the same on every chip family.
+
+use crate::Chip;
+
+pub use self::{
+    pin::{AnyPin, Pin},
+    port::Port,
+};
+
+pub mod mode;
+mod pin;
+mod port;
+mod sense;
+
+#[doc(hidden)]
+pub use self::sense::{arm as arm_sense, interrupt};
+
+/// Access to the GPIO port and the PORT event of GPIOTE, implemented by each
+/// chip crate's marker type over its PAC.
+pub trait Registers: Chip {
+    /// The GPIOTE interrupt, which the PORT event is delivered through.
+    const GPIOTE: Self::Interrupt;
+
+    fn pin_cnf(pin: usize) -> u32;
+    fn set_pin_cnf(pin: usize, bits: u32);
+    fn out() -> u32;
+    /// Sets the given bits of OUT, through OUTSET.
+    fn set_out(bits: u32);
+    /// Clears the given bits of OUT, through OUTCLR.
+    fn clear_out(bits: u32);
+    fn input() -> u32;
+    fn port_event() -> bool;
+    fn clear_port_event();
+    fn enable_port_interrupt();
+    /// The pins whose SENSE level has been reached since their bit was last
+    /// cleared, or `None` on chips without a LATCH register.
+    fn latch() -> Option<u32>;
+    fn clear_latch(bits: u32);
+}
+
+/// Maps bits of a group mask to the bits of the pins `ids` in the port
+/// registers, bit `n` of the mask is pin `ids[n]`.
+fn spread(ids: impl Iterator<Item = usize>, mask: u32) -> u32 {
+    ids.enumerate()
+        .filter(|&(n, _)| mask & (1 << n) != 0)
+        .fold(0, |bits, (_, id)| bits | (1 << id))
+}
+
+/// Maps bits of the port registers back to a group mask, the inverse of
+/// [`spread`].
+fn gather(ids: impl Iterator<Item = usize>, bits: u32) -> u32 {
+    ids.enumerate()
+        .filter(|&(_, id)| bits & (1 << id) != 0)
+        .fold(0, |mask, (n, _)| mask | (1 << n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks() {
+        let ids = [13, 4, 31];
+        assert_eq!(spread(ids.iter().cloned(), 0b101), 1 << 13 | 1 << 31);
+        assert_eq!(spread(ids.iter().cloned(), 0b1000), 0);
+        assert_eq!(gather(ids.iter().cloned(), 1 << 4 | 1 << 31 | 1), 0b110);
+    }
+}
@@ -0,0 +1,211 @@
+//! Pin modes as zero sized types, each described by the value of the PIN_CNF
+//! register fields it configures, which are laid out the same on every chip
+//! family.
+
+const DIR_OUTPUT: u32 = 1;
+const INPUT_DISCONNECT: u32 = 1 << 1;
+const PULL_SHIFT: u32 = 2;
+const DRIVE_SHIFT: u32 = 8;
+
+pub trait InputMode: Sized {
+    /// Value of the PULL field.
+    const PULL: u32;
+
+    #[doc(hidden)]
+    fn new() -> Self;
+}
+
+pub trait OutputMode: Sized {
+    /// Value of the DRIVE field.
+    const DRIVE: u32;
+
+    #[doc(hidden)]
+    fn new() -> Self;
+}
+
+pub trait PinMode: Sized {
+    /// Value of the whole PIN_CNF register, with SENSE disabled.
+    const PIN_CNF: u32;
+
+    #[doc(hidden)]
+    fn new() -> Self;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Floating {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PullUp {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PullDown {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PushPull {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenDrain {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Unconfigured {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Disabled {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Input<Mode> {
+    mode: Mode,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Output<Mode> {
+    mode: Mode,
+}
+
+impl InputMode for Floating {
+    const PULL: u32 = 0;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Floating { _reserved: () }
+    }
+}
+
+impl InputMode for PullUp {
+    const PULL: u32 = 3;
+
+    #[inline(always)]
+    fn new() -> Self {
+        PullUp { _reserved: () }
+    }
+}
+
+impl InputMode for PullDown {
+    const PULL: u32 = 1;
+
+    #[inline(always)]
+    fn new() -> Self {
+        PullDown { _reserved: () }
+    }
+}
+
+impl OutputMode for PushPull {
+    /// Standard 0, standard 1.
+    const DRIVE: u32 = 0;
+
+    #[inline(always)]
+    fn new() -> Self {
+        PushPull { _reserved: () }
+    }
+}
+
+impl OutputMode for OpenDrain {
+    /// Standard 0, disconnect 1.
+    const DRIVE: u32 = 6;
+
+    #[inline(always)]
+    fn new() -> Self {
+        OpenDrain { _reserved: () }
+    }
+}
+
+impl PinMode for Disabled {
+    const PIN_CNF: u32 = INPUT_DISCONNECT;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Disabled { _reserved: () }
+    }
+}
+
+impl<Mode: InputMode> PinMode for Input<Mode> {
+    const PIN_CNF: u32 = Mode::PULL << PULL_SHIFT;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Input { mode: Mode::new() }
+    }
+}
+
+impl<Mode: OutputMode> PinMode for Output<Mode> {
+    const PIN_CNF: u32 =
+        DIR_OUTPUT | INPUT_DISCONNECT | Mode::DRIVE << DRIVE_SHIFT;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Output { mode: Mode::new() }
+    }
+}
+
+impl Unconfigured {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Unconfigured { _reserved: () }
+    }
+}
+
+impl Input<Unconfigured> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Input {
+            mode: Unconfigured::new(),
+        }
+    }
+}
+
+impl Output<Unconfigured> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Output {
+            mode: Unconfigured::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::mem;
+
+    // TODO: Static assert with const size_of fn?
+    #[test]
+    fn zst() {
+        assert!(mem::size_of::<Input<Unconfigured>>() == 0);
+        assert!(mem::size_of::<Input<Floating>>() == 0);
+        assert!(mem::size_of::<Input<PullUp>>() == 0);
+        assert!(mem::size_of::<Input<PullDown>>() == 0);
+        assert!(mem::size_of::<Output<Unconfigured>>() == 0);
+        assert!(mem::size_of::<Output<PushPull>>() == 0);
+        assert!(mem::size_of::<Output<OpenDrain>>() == 0);
+        assert!(mem::size_of::<Disabled>() == 0);
+        assert!(mem::size_of::<Unconfigured>() == 0);
+    }
+
+    #[test]
+    fn pin_cnf() {
+        assert_eq!(Disabled::PIN_CNF, 0b10);
+        assert_eq!(Input::<Floating>::PIN_CNF, 0b0000);
+        assert_eq!(Input::<PullDown>::PIN_CNF, 0b0100);
+        assert_eq!(Input::<PullUp>::PIN_CNF, 0b1100);
+        assert_eq!(Output::<PushPull>::PIN_CNF, 0x003);
+        assert_eq!(Output::<OpenDrain>::PIN_CNF, 0x603);
+    }
+}
@@ -0,0 +1,318 @@
+use core::{
+    marker::PhantomData,
+    pin,
+    task::{self, Poll},
+};
+
+use embrio_core::gpio::Edge;
+
+use super::{
+    mode::{
+        Disabled, Floating, Input, InputMode, OpenDrain, Output, OutputMode,
+        PinMode, PullDown, PullUp, PushPull, Unconfigured,
+    },
+    sense::{self, Request},
+    Registers,
+};
+
+/// Pin `N` of the GPIO port, the pin number is only part of the type so this
+/// is zero sized.
+#[derive(Debug)]
+pub struct Pin<'a, C, const N: u8, Mode> {
+    mode: Mode,
+    _marker: PhantomData<&'a C>,
+}
+
+/// A [`Pin`] that has had its number moved to runtime with
+/// [`degrade`](Pin::degrade), so pins in different positions can be stored
+/// together, e.g. in an array.
+#[derive(Debug)]
+pub struct AnyPin<'a, C, Mode> {
+    pin: u8,
+    mode: Mode,
+    _marker: PhantomData<&'a C>,
+}
+
+/// Writes the whole configuration of `pin` for `Mode`.
+#[inline]
+fn configure<C: Registers, Mode: PinMode>(pin: u8) -> Mode {
+    C::set_pin_cnf(usize::from(pin), Mode::PIN_CNF);
+    Mode::new()
+}
+
+impl<'a, C, const N: u8> Pin<'a, C, N, Unconfigured> {
+    /// # Safety
+    ///
+    /// There must only be one instance of each pin, and the GPIO peripheral
+    /// must be exclusively borrowed for `'a`.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn new() -> Self {
+        Pin {
+            mode: Unconfigured::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, C, const N: u8, Mode> Pin<'a, C, N, Mode> {
+    #[inline]
+    pub fn degrade(self) -> AnyPin<'a, C, Mode> {
+        AnyPin {
+            pin: N,
+            mode: self.mode,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn into_mode<NewMode>(self, mode: NewMode) -> Pin<'a, C, N, NewMode> {
+        Pin {
+            mode,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Only changes the type, the pin is configured once its output mode is
+    /// chosen.
+    #[inline]
+    pub fn output(self) -> Pin<'a, C, N, Output<Unconfigured>> {
+        self.into_mode(Output::new())
+    }
+
+    /// Only changes the type, the pin is configured once its input mode is
+    /// chosen.
+    #[inline]
+    pub fn input(self) -> Pin<'a, C, N, Input<Unconfigured>> {
+        self.into_mode(Input::new())
+    }
+}
+
+impl<'a, C: Registers, const N: u8, Mode> Pin<'a, C, N, Mode> {
+    #[inline]
+    pub fn disable(self) -> Pin<'a, C, N, Disabled> {
+        self.into_mode(configure::<C, _>(N))
+    }
+}
+
+impl<'a, C: Registers, const N: u8, Mode> Pin<'a, C, N, Input<Mode>> {
+    #[inline]
+    pub fn floating(self) -> Pin<'a, C, N, Input<Floating>> {
+        self.into_mode(configure::<C, _>(N))
+    }
+
+    #[inline]
+    pub fn pull_up(self) -> Pin<'a, C, N, Input<PullUp>> {
+        self.into_mode(configure::<C, _>(N))
+    }
+
+    #[inline]
+    pub fn pull_down(self) -> Pin<'a, C, N, Input<PullDown>> {
+        self.into_mode(configure::<C, _>(N))
+    }
+}
+
+impl<'a, C: Registers, const N: u8, Mode> Pin<'a, C, N, Output<Mode>> {
+    #[inline]
+    pub fn open_drain(self) -> Pin<'a, C, N, Output<OpenDrain>> {
+        self.into_mode(configure::<C, _>(N))
+    }
+
+    #[inline]
+    pub fn push_pull(self) -> Pin<'a, C, N, Output<PushPull>> {
+        self.into_mode(configure::<C, _>(N))
+    }
+}
+
+impl<'a, C, const N: u8, Mode> From<Pin<'a, C, N, Mode>>
+    for AnyPin<'a, C, Mode>
+{
+    #[inline]
+    fn from(pin: Pin<'a, C, N, Mode>) -> Self {
+        pin.degrade()
+    }
+}
+
+impl<'a, C, Mode> AnyPin<'a, C, Mode> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn get_id(&self) -> usize {
+        usize::from(self.pin)
+    }
+
+    #[inline]
+    fn into_mode<NewMode>(self, mode: NewMode) -> AnyPin<'a, C, NewMode> {
+        AnyPin {
+            pin: self.pin,
+            mode,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Only changes the type, see [`Pin::output`].
+    #[inline]
+    pub fn output(self) -> AnyPin<'a, C, Output<Unconfigured>> {
+        self.into_mode(Output::new())
+    }
+
+    /// Only changes the type, see [`Pin::input`].
+    #[inline]
+    pub fn input(self) -> AnyPin<'a, C, Input<Unconfigured>> {
+        self.into_mode(Input::new())
+    }
+}
+
+impl<'a, C: Registers, Mode> AnyPin<'a, C, Mode> {
+    #[inline]
+    pub fn disable(self) -> AnyPin<'a, C, Disabled> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+}
+
+impl<'a, C: Registers, Mode> AnyPin<'a, C, Input<Mode>> {
+    #[inline]
+    pub fn floating(self) -> AnyPin<'a, C, Input<Floating>> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+
+    #[inline]
+    pub fn pull_up(self) -> AnyPin<'a, C, Input<PullUp>> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+
+    #[inline]
+    pub fn pull_down(self) -> AnyPin<'a, C, Input<PullDown>> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+}
+
+impl<'a, C: Registers, Mode> AnyPin<'a, C, Output<Mode>> {
+    #[inline]
+    pub fn open_drain(self) -> AnyPin<'a, C, Output<OpenDrain>> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+
+    #[inline]
+    pub fn push_pull(self) -> AnyPin<'a, C, Output<PushPull>> {
+        let mode = configure::<C, _>(self.pin);
+        self.into_mode(mode)
+    }
+}
+
+fn output_state<C: Registers>(pin: u8) -> bool {
+    (C::out() & (1 << pin)) == (1 << pin)
+}
+
+fn set_output_state<C: Registers>(pin: u8, state: bool) {
+    if state {
+        C::set_out(1 << pin);
+    } else {
+        C::clear_out(1 << pin);
+    }
+}
+
+fn input_state<C: Registers>(pin: u8) -> bool {
+    (C::input() & (1 << pin)) == (1 << pin)
+}
+
+impl<'a, C: Registers, const N: u8, Mode: OutputMode> embrio_core::gpio::Output
+    for Pin<'a, C, N, Output<Mode>>
+{
+    fn state(&self) -> bool {
+        output_state::<C>(N)
+    }
+
+    fn set_state(&self, state: bool) {
+        set_output_state::<C>(N, state)
+    }
+}
+
+impl<'a, C: Registers, Mode: OutputMode> embrio_core::gpio::Output
+    for AnyPin<'a, C, Output<Mode>>
+{
+    fn state(&self) -> bool {
+        output_state::<C>(self.pin)
+    }
+
+    fn set_state(&self, state: bool) {
+        set_output_state::<C>(self.pin, state)
+    }
+}
+
+impl<'a, C: Registers, const N: u8, Mode: InputMode> embrio_core::gpio::Input
+    for Pin<'a, C, N, Input<Mode>>
+{
+    fn state(&self) -> bool {
+        input_state::<C>(N)
+    }
+
+    fn poll_level(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        level: bool,
+    ) -> Poll<()> {
+        sense::poll::<C>(usize::from(N), Request::Level(level), cx.waker())
+    }
+
+    fn poll_edge(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        edge: Edge,
+    ) -> Poll<()> {
+        sense::poll::<C>(usize::from(N), Request::Edge(edge), cx.waker())
+    }
+
+    fn cancel_wait(self: pin::Pin<&mut Self>) {
+        sense::cancel::<C>(usize::from(N))
+    }
+}
+
+impl<'a, C: Registers, Mode: InputMode> embrio_core::gpio::Input
+    for AnyPin<'a, C, Input<Mode>>
+{
+    fn state(&self) -> bool {
+        input_state::<C>(self.pin)
+    }
+
+    fn poll_level(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        level: bool,
+    ) -> Poll<()> {
+        sense::poll::<C>(
+            usize::from(self.pin),
+            Request::Level(level),
+            cx.waker(),
+        )
+    }
+
+    fn poll_edge(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        edge: Edge,
+    ) -> Poll<()> {
+        sense::poll::<C>(usize::from(self.pin), Request::Edge(edge), cx.waker())
+    }
+
+    fn cancel_wait(self: pin::Pin<&mut Self>) {
+        sense::cancel::<C>(usize::from(self.pin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::mem;
+
+    #[test]
+    fn zst() {
+        assert!(mem::size_of::<Pin<'_, (), 0, Input<Floating>>>() == 0);
+        assert!(mem::size_of::<AnyPin<'_, (), Input<Floating>>>() == 1);
+    }
+}
@@ -0,0 +1,58 @@
+use super::{
+    gather,
+    mode::{Input, InputMode, Output, OutputMode},
+    spread, AnyPin, Registers,
+};
+
+/// Pins driven together as an [`embrio_core::gpio::Port`], or read together as
+/// an [`embrio_core::gpio::InputPort`], pin `n` of the group is `pins[n]`.
+#[derive(Debug)]
+pub struct Port<'a, C, Mode, const W: usize> {
+    pins: [AnyPin<'a, C, Mode>; W],
+}
+
+impl<'a, C, Mode, const W: usize> Port<'a, C, Mode, W> {
+    /// Groups of more than 32 pins can't be represented in the `u32` masks.
+    pub fn new(pins: [AnyPin<'a, C, Mode>; W]) -> Self {
+        assert!(W <= 32);
+        Port { pins }
+    }
+
+    pub fn free(self) -> [AnyPin<'a, C, Mode>; W] {
+        self.pins
+    }
+
+    fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.pins.iter().map(AnyPin::get_id)
+    }
+}
+
+/// The pins going high change with one write to OUTSET, then the pins going
+/// low with one write to OUTCLR, a cycle later.
+impl<'a, C: Registers, Mode: OutputMode, const W: usize> embrio_core::gpio::Port
+    for Port<'a, C, Output<Mode>, W>
+{
+    fn state(&self) -> u32 {
+        gather(self.ids(), C::out())
+    }
+
+    fn set_state(&self, mask: u32, state: u32) {
+        let set = spread(self.ids(), mask & state);
+        let clear = spread(self.ids(), mask & !state);
+        if set != 0 {
+            C::set_out(set);
+        }
+        if clear != 0 {
+            C::clear_out(clear);
+        }
+    }
+}
+
+/// Every pin is read from one read of IN.
+impl<'a, C: Registers, Mode: InputMode, const W: usize>
+    embrio_core::gpio::InputPort for Port<'a, C, Input<Mode>, W>
+{
+    fn state(&self) -> u32 {
+        gather(self.ids(), C::input())
+    }
+}
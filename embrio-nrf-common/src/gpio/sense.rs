@@ -0,0 +1,220 @@
+use core::{
+    cell::RefCell,
+    mem,
+    task::{Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use embrio_core::gpio::Edge;
+
+use self::Wait::Idle;
+use super::Registers;
+
+const SENSE_SHIFT: u32 = 16;
+const SENSE_MASK: u32 = 0b11 << SENSE_SHIFT;
+const SENSE_HIGH: u32 = 2 << SENSE_SHIFT;
+const SENSE_LOW: u32 = 3 << SENSE_SHIFT;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Request {
+    Level(bool),
+    Edge(Edge),
+}
+
+enum Wait {
+    Idle,
+    /// SENSE is armed for `level`, once reached it is re-armed for `then`
+    /// if there is one, otherwise the wait is done.
+    Armed {
+        request: Request,
+        level: bool,
+        then: Option<bool>,
+        waker: Waker,
+    },
+    /// Completed, until the waiter next polls.
+    Done(Request),
+}
+
+// One slot per pin, an armed wait means that pin has its SENSE armed
+static WAITS: Mutex<RefCell<[Wait; 32]>> = Mutex::new(RefCell::new([
+    Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle,
+    Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle,
+    Idle, Idle, Idle, Idle, Idle, Idle, Idle, Idle,
+]));
+
+/// Only called from critical sections, as PIN_CNF is read then written.
+fn set_sense<C: Registers>(pin: usize, level: Option<bool>) {
+    let sense = match level {
+        Some(true) => SENSE_HIGH,
+        Some(false) => SENSE_LOW,
+        None => 0,
+    };
+    C::set_pin_cnf(pin, (C::pin_cnf(pin) & !SENSE_MASK) | sense);
+    C::clear_latch(1 << pin);
+}
+
+fn input_state<C: Registers>(pin: usize) -> bool {
+    C::input() & (1 << pin) != 0
+}
+
+/// Arm the pin's SENSE for `level` without waiting on it, for waking from
+/// System OFF.
+pub fn arm<C: Registers>(pin: usize, level: bool) {
+    free(|_| set_sense::<C>(pin, Some(level)));
+}
+
+/// Move an armed wait on to its next level, re-arming SENSE for it or
+/// disarming it and waking the waiter once done.
+fn advance<C: Registers>(pin: usize, wait: &mut Wait) {
+    if let Wait::Armed {
+        request,
+        level,
+        then,
+        ..
+    } = wait
+    {
+        match then.take() {
+            Some(next) => {
+                *level = next;
+                set_sense::<C>(pin, Some(next));
+            }
+            None => {
+                let request = *request;
+                set_sense::<C>(pin, None);
+                if let Wait::Armed { waker, .. } =
+                    mem::replace(wait, Wait::Done(request))
+                {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+/// Advances the pin's wait for as long as the pin is at its armed level.
+fn advance_while_reached<C: Registers>(pin: usize, wait: &mut Wait) {
+    while let Wait::Armed { level, .. } = *wait {
+        if input_state::<C>(pin) != level {
+            break;
+        }
+        advance::<C>(pin, wait);
+    }
+}
+
+/// Waits for `request` on the pin using its SENSE, a level is watched for
+/// directly and an edge as the level before it then the level after it.
+pub(crate) fn poll<C: Registers>(
+    pin: usize,
+    request: Request,
+    waker: &Waker,
+) -> Poll<()> {
+    free(|c| {
+        let mut waits = WAITS.borrow(c).borrow_mut();
+        let wait = &mut waits[pin];
+
+        match wait {
+            Wait::Done(done) if *done == request => {
+                *wait = Wait::Idle;
+                return Poll::Ready(());
+            }
+            Wait::Armed {
+                request: armed,
+                waker: registered,
+                ..
+            } if *armed == request => {
+                if !registered.will_wake(waker) {
+                    *registered = waker.clone();
+                }
+                return Poll::Pending;
+            }
+            _ => {}
+        }
+
+        // A different request replaces the previous one
+        if let Wait::Armed { .. } = wait {
+            set_sense::<C>(pin, None);
+        }
+        *wait = Wait::Idle;
+
+        let current = input_state::<C>(pin);
+        let (level, then) = match request {
+            Request::Level(level) => {
+                if current == level {
+                    return Poll::Ready(());
+                }
+                (level, None)
+            }
+            Request::Edge(Edge::Rising) => (false, Some(true)),
+            Request::Edge(Edge::Falling) => (true, Some(false)),
+            Request::Edge(Edge::Any) => (!current, None),
+        };
+
+        *wait = Wait::Armed {
+            request,
+            level,
+            then,
+            waker: waker.clone(),
+        };
+        set_sense::<C>(pin, Some(level));
+        C::enable_port_interrupt();
+        unsafe { NVIC::unmask(C::GPIOTE) };
+
+        // The pin may have reached the level before SENSE was armed, in which
+        // case no PORT event will be generated for it
+        advance_while_reached::<C>(pin, wait);
+        if let Wait::Done(_) = wait {
+            *wait = Wait::Idle;
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    })
+}
+
+/// Disarm the pin's SENSE, an armed pin that has reached its level holds the
+/// DETECT signal high and would stop any other pin generating PORT events.
+pub(crate) fn cancel<C: Registers>(pin: usize) {
+    free(|c| {
+        let mut waits = WAITS.borrow(c).borrow_mut();
+        if let Wait::Armed { .. } = waits[pin] {
+            set_sense::<C>(pin, None);
+        }
+        waits[pin] = Wait::Idle;
+    });
+}
+
+pub fn interrupt<C: Registers>() {
+    free(|c| {
+        NVIC::unpend(C::GPIOTE);
+        if !C::port_event() {
+            return;
+        }
+        C::clear_port_event();
+
+        let mut waits = WAITS.borrow(c).borrow_mut();
+        let latched = C::latch();
+        let armed = waits
+            .iter()
+            .filter(|wait| matches!(wait, Wait::Armed { .. }))
+            .count();
+        for (pin, wait) in waits.iter_mut().enumerate() {
+            let level = match wait {
+                Wait::Armed { level, .. } => *level,
+                _ => continue,
+            };
+            let latched = match latched {
+                Some(latched) => latched & (1 << pin) != 0,
+                // Without LATCH a pulse that has already ended can only be
+                // attributed when a single pin is armed
+                None => armed == 1,
+            };
+            if latched || input_state::<C>(pin) == level {
+                advance::<C>(pin, wait);
+                // Leaves DETECT low, so the next PORT event isn't missed
+                advance_while_reached::<C>(pin, wait);
+            }
+        }
+    });
+}
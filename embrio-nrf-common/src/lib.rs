@@ -0,0 +1,19 @@
+//! The nRF peripheral drivers which are shared between chip families with the
+//! same peripheral designs, generic over a small register access trait per
+//! peripheral that each chip crate implements for its PAC.
+
+#![no_std]
+#![feature(const_generics, never_type)]
+
+pub mod clock;
+pub mod gpio;
+pub mod radio;
+pub mod rtc;
+pub mod timer;
+pub mod uart;
+
+/// A chip family, implemented by a marker type in each chip crate so it can
+/// implement the register access traits for its PAC types.
+pub trait Chip: Sized + 'static {
+    type Interrupt: cortex_m::interrupt::Nr;
+}
@@ -0,0 +1,229 @@
+//! The RADIO packet layout in RAM, which is the same on every chip family.
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// A packet was received with an invalid CRC.
+    Crc,
+    /// The payload is longer than the format allows.
+    TooLong,
+    /// The buffer is too short for the packet.
+    Truncated,
+}
+
+/// How packets are laid out in RAM, the radio adds the preamble, address and
+/// CRC itself.
+///
+/// A packet is the optional `S0` byte, the length byte, the optional `S1`
+/// byte then up to `max_len` bytes of payload.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Format {
+    /// Whether the `S0` byte is present.
+    pub s0: bool,
+    /// Bits of the length byte sent over the air, up to 8.
+    pub length_bits: u8,
+    /// Bits of the `S1` byte sent over the air, up to 8, it is only present
+    /// in RAM when this is non-zero.
+    pub s1_bits: u8,
+    pub max_len: u8,
+}
+
+/// A decoded packet, `s0` and `s1` are zero when not part of the format.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Packet<'a> {
+    pub s0: u8,
+    pub s1: u8,
+    pub payload: &'a [u8],
+}
+
+impl Format {
+    /// A single length byte followed by the payload.
+    pub const NRF: Format = Format {
+        s0: false,
+        length_bits: 8,
+        s1_bits: 0,
+        max_len: 254,
+    };
+
+    /// The BLE header byte, the length byte then the PDU payload.
+    pub const BLE: Format = Format {
+        s0: true,
+        length_bits: 8,
+        s1_bits: 0,
+        max_len: 37,
+    };
+
+    fn s0_len(&self) -> usize {
+        self.s0 as usize
+    }
+
+    fn s1_len(&self) -> usize {
+        (self.s1_bits > 0) as usize
+    }
+
+    /// Bytes before the payload.
+    pub fn header_len(&self) -> usize {
+        self.s0_len() + 1 + self.s1_len()
+    }
+
+    /// The space needed to receive any packet in this format.
+    pub fn buffer_len(&self) -> usize {
+        self.header_len() + usize::from(self.max_len)
+    }
+
+    fn length_mask(&self) -> u8 {
+        (0xFF_u16 >> (8 - u16::from(self.length_bits))) as u8
+    }
+
+    /// Returns the number of bytes written to `buf`.
+    pub fn encode(
+        &self,
+        packet: &Packet<'_>,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let len = packet.payload.len();
+        if len > usize::from(self.max_len)
+            || len > usize::from(self.length_mask())
+        {
+            return Err(Error::TooLong);
+        }
+        let total = self.header_len() + len;
+        if buf.len() < total {
+            return Err(Error::Truncated);
+        }
+
+        let mut i = 0;
+        if self.s0 {
+            buf[i] = packet.s0;
+            i += 1;
+        }
+        buf[i] = len as u8;
+        i += 1;
+        if self.s1_bits > 0 {
+            buf[i] = packet.s1;
+            i += 1;
+        }
+        buf[i..total].copy_from_slice(packet.payload);
+        Ok(total)
+    }
+
+    pub fn decode<'a>(&self, buf: &'a [u8]) -> Result<Packet<'a>, Error> {
+        if buf.len() < self.header_len() {
+            return Err(Error::Truncated);
+        }
+
+        let mut i = 0;
+        let mut s0 = 0;
+        if self.s0 {
+            s0 = buf[i];
+            i += 1;
+        }
+        let len = usize::from(buf[i] & self.length_mask());
+        i += 1;
+        let mut s1 = 0;
+        if self.s1_bits > 0 {
+            s1 = buf[i];
+            i += 1;
+        }
+        if len > usize::from(self.max_len) {
+            return Err(Error::TooLong);
+        }
+        let payload = buf.get(i..i + len).ok_or(Error::Truncated)?;
+        Ok(Packet { s0, s1, payload })
+    }
+
+    #[doc(hidden)]
+    pub fn pcnf0(&self) -> u32 {
+        u32::from(self.length_bits)
+            | (self.s0 as u32) << 8
+            | u32::from(self.s1_bits) << 16
+    }
+
+    /// Little endian, without a static length.
+    #[doc(hidden)]
+    pub fn pcnf1(&self, base_len: u8, whitening: bool) -> u32 {
+        u32::from(self.max_len)
+            | u32::from(base_len) << 16
+            | (whitening as u32) << 25
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nrf_round_trip() {
+        let mut buf = [0; 8];
+        let packet = Packet {
+            s0: 0,
+            s1: 0,
+            payload: b"hi",
+        };
+        assert_eq!(Format::NRF.encode(&packet, &mut buf), Ok(3));
+        assert_eq!(&buf[..3], b"\x02hi");
+        assert_eq!(Format::NRF.decode(&buf), Ok(packet));
+    }
+
+    #[test]
+    fn ble_round_trip() {
+        let mut buf = [0; Format::BLE.max_len as usize + 2];
+        assert_eq!(Format::BLE.buffer_len(), buf.len());
+        let packet = Packet {
+            s0: 0x42,
+            s1: 0,
+            payload: &[1, 2, 3, 4, 5, 6],
+        };
+        assert_eq!(Format::BLE.encode(&packet, &mut buf), Ok(8));
+        assert_eq!(&buf[..2], &[0x42, 6]);
+        assert_eq!(Format::BLE.decode(&buf), Ok(packet));
+    }
+
+    #[test]
+    fn length_and_s1_fields() {
+        let format = Format {
+            s0: false,
+            length_bits: 6,
+            s1_bits: 2,
+            max_len: 63,
+        };
+        assert_eq!(format.header_len(), 2);
+        let decoded = format.decode(&[0xC3, 0x01, 7, 8, 9]).unwrap();
+        assert_eq!(decoded.s1, 0x01);
+        assert_eq!(decoded.payload, &[7, 8, 9]);
+        assert_eq!(format.pcnf0(), 6 | 2 << 16);
+    }
+
+    #[test]
+    fn errors() {
+        let mut buf = [0; 4];
+        let packet = Packet {
+            s0: 0,
+            s1: 0,
+            payload: b"long",
+        };
+        assert_eq!(
+            Format::NRF.encode(&packet, &mut buf),
+            Err(Error::Truncated)
+        );
+        assert_eq!(
+            Format::BLE.encode(
+                &Packet {
+                    payload: &[0; 38],
+                    ..packet
+                },
+                &mut [0; 64]
+            ),
+            Err(Error::TooLong)
+        );
+        assert_eq!(Format::NRF.decode(&[5, 1, 2]), Err(Error::Truncated));
+        assert_eq!(Format::BLE.decode(&[0, 38]), Err(Error::TooLong));
+        assert_eq!(Format::NRF.decode(&[]), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn registers() {
+        assert_eq!(Format::BLE.pcnf0(), 8 | 1 << 8);
+        assert_eq!(Format::BLE.pcnf1(3, true), 37 | 3 << 16 | 1 << 25);
+        assert_eq!(Format::NRF.pcnf1(4, false), 254 | 4 << 16);
+    }
+}
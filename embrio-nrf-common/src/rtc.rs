@@ -0,0 +1,429 @@
+//! The RTC, a 24-bit counter running from the LFCLK which is extended to 64
+//! bits by counting overflows.
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+
+use cortex_m::{
+    interrupt::{free, CriticalSection, Mutex},
+    peripheral::NVIC,
+};
+use futures_core::stream::Stream;
+
+use crate::Chip;
+
+/// Ticks per second of the RTC counter, running from the LFCLK without
+/// prescaling.
+pub const FREQUENCY: u64 = 32_768;
+
+/// The counter register is only 24 bits wide.
+pub const COUNTER_BITS: u32 = 24;
+
+const COUNTER_MASK: u64 = (1 << COUNTER_BITS) - 1;
+
+// A compare register must be set at least this far ahead of the counter to be
+// guaranteed to trigger
+const MIN_AHEAD: u64 = 2;
+
+/// Why an [`Rtc`] timeout or interval failed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The deadline is further away than the 64-bit tick count can reach
+    TooLong,
+}
+
+/// A low power [`Timer`](embrio_core::timer::Timer) which keeps running while
+/// the HFCLK is stopped, at the cost of ~30µs resolution.
+///
+/// The 24-bit counter is extended to 64 bits by counting overflows, so
+/// [`ticks`](Rtc::ticks) will not wrap.
+#[derive(Debug)]
+pub struct Rtc<'b, C: Chip, T: Instance<C>> {
+    _marker: PhantomData<(&'b mut T, &'b mut NVIC, C)>,
+}
+
+#[derive(Debug)]
+pub struct Timeout<'a, 'b: 'a, C: Chip, T: Instance<C>> {
+    rtc: Option<&'a mut Rtc<'b, C, T>>,
+    deadline: u64,
+    error: Option<Error>,
+}
+
+#[derive(Debug)]
+pub struct Interval<'a, 'b: 'a, C: Chip, T: Instance<C>> {
+    _rtc: &'a mut Rtc<'b, C, T>,
+    period: u64,
+    deadline: u64,
+    error: Option<Error>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Task {
+    Start,
+    Stop,
+    Clear,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Event {
+    Overflow,
+    /// Only compare register 0 is used.
+    Compare,
+}
+
+/// Access to an RTC's registers, implemented by each chip crate for its PAC
+/// register block.
+pub trait Registers<C> {
+    fn trigger(&self, task: Task);
+    fn set_prescaler(&self, prescaler: u16);
+    fn counter(&self) -> u32;
+    fn event(&self, event: Event) -> bool;
+    fn clear(&self, event: Event);
+    fn enable_interrupt(&self, event: Event);
+    fn disable_interrupt(&self, event: Event);
+    fn set_compare(&self, value: u32);
+}
+
+/// An RTC peripheral, implemented by each chip crate for its instances.
+pub trait Instance<C: Chip> {
+    type Registers: Registers<C> + 'static;
+
+    /// Index of the instance's context, less than [`INSTANCES`].
+    #[doc(hidden)]
+    const INDEX: usize;
+    #[doc(hidden)]
+    const INTERRUPT: C::Interrupt;
+
+    #[doc(hidden)]
+    fn registers() -> &'static Self::Registers;
+}
+
+/// The most RTC instances any chip has.
+pub const INSTANCES: usize = 3;
+
+struct Context {
+    overflows: u64,
+    waker: Option<Waker>,
+    deadline: Option<u64>,
+}
+
+static CONTEXTS: [Mutex<RefCell<Option<Context>>>; INSTANCES] = [
+    Mutex::new(RefCell::new(None)),
+    Mutex::new(RefCell::new(None)),
+    Mutex::new(RefCell::new(None)),
+];
+
+/// Rounds up, so that a timeout never fires early.
+pub fn duration_to_ticks(duration: Duration) -> Result<u64, Error> {
+    let ticks = (duration.as_nanos() * u128::from(FREQUENCY) + 999_999_999)
+        / 1_000_000_000;
+    if ticks > u128::from(u64::MAX) {
+        return Err(Error::TooLong);
+    }
+    Ok(ticks as u64)
+}
+
+/// Combines the counted overflows with the counter register.
+pub fn ticks(overflows: u64, counter: u32) -> u64 {
+    (overflows << COUNTER_BITS) | u64::from(counter)
+}
+
+/// The compare register value to arm for a `deadline` after `now`, if it is
+/// within range of the counter.
+///
+/// Further deadlines must be re-armed on overflow once they come within
+/// range, closer ones may fire slightly late.
+pub fn compare(deadline: u64, now: u64) -> Option<u32> {
+    if deadline - now <= COUNTER_MASK {
+        Some((deadline.max(now + MIN_AHEAD) & COUNTER_MASK) as u32)
+    } else {
+        None
+    }
+}
+
+impl Context {
+    fn ticks<C>(&self, rtc: &impl Registers<C>) -> u64 {
+        let mut counter = rtc.counter();
+        let mut overflows = self.overflows;
+        // An overflow that has not yet been handled by the interrupt
+        if rtc.event(Event::Overflow) {
+            counter = rtc.counter();
+            overflows += 1;
+        }
+        ticks(overflows, counter)
+    }
+
+    /// Arm the compare for the current deadline, returns whether it has
+    /// already been reached.
+    fn arm<C>(&mut self, rtc: &impl Registers<C>) -> bool {
+        let deadline = match self.deadline {
+            Some(deadline) => deadline,
+            None => return false,
+        };
+        let now = self.ticks(rtc);
+        if deadline <= now {
+            return true;
+        }
+        // Further deadlines are re-armed by the overflow interrupt once they
+        // come within range
+        if let Some(compare) = compare(deadline, now) {
+            rtc.clear(Event::Compare);
+            rtc.set_compare(compare);
+            rtc.enable_interrupt(Event::Compare);
+        }
+        false
+    }
+}
+
+impl<'b, C: Chip, T: Instance<C>> Rtc<'b, C, T> {
+    pub fn new(_rtc: &'b mut T) -> Self {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            assert!(context.is_none());
+            let rtc = T::registers();
+
+            rtc.trigger(Task::Stop);
+            rtc.trigger(Task::Clear);
+            rtc.set_prescaler(0);
+            rtc.clear(Event::Overflow);
+            rtc.enable_interrupt(Event::Overflow);
+            rtc.trigger(Task::Start);
+
+            context.replace(Context {
+                overflows: 0,
+                waker: None,
+                deadline: None,
+            });
+
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        });
+
+        Rtc {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Ticks at [`FREQUENCY`] since this timer was created.
+    pub fn ticks(&self) -> u64 {
+        free(|c| Self::with_context(c, |context| context.ticks(T::registers())))
+    }
+
+    fn deadline_after(&self, ticks: Result<u64, Error>) -> Result<u64, Error> {
+        ticks.and_then(|ticks| {
+            self.ticks().checked_add(ticks).ok_or(Error::TooLong)
+        })
+    }
+
+    fn with_context<R>(
+        c: &CriticalSection,
+        f: impl FnOnce(&mut Context) -> R,
+    ) -> R {
+        let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+        f(context.as_mut().unwrap())
+    }
+
+    fn poll_deadline(cx: &mut task::Context<'_>, deadline: u64) -> Poll<()> {
+        free(|c| {
+            Self::with_context(c, |context| {
+                context.deadline = Some(deadline);
+                if context.arm(T::registers()) {
+                    context.deadline = None;
+                    context.waker = None;
+                    Poll::Ready(())
+                } else {
+                    context.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            })
+        })
+    }
+
+    fn cancel() {
+        free(|c| {
+            Self::with_context(c, |context| {
+                context.deadline = None;
+                context.waker = None;
+                T::registers().disable_interrupt(Event::Compare);
+            })
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            let rtc = T::registers();
+            if rtc.event(Event::Overflow) {
+                rtc.clear(Event::Overflow);
+                context.overflows += 1;
+            }
+            if rtc.event(Event::Compare) {
+                rtc.clear(Event::Compare);
+                rtc.disable_interrupt(Event::Compare);
+            }
+            if context.arm(rtc) {
+                if let Some(waker) = context.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b, C: Chip, T: Instance<C>> Drop for Rtc<'b, C, T> {
+    fn drop(&mut self) {
+        free(|c| {
+            CONTEXTS[T::INDEX].borrow(c).borrow_mut().take().unwrap();
+
+            let rtc = T::registers();
+            rtc.trigger(Task::Stop);
+            rtc.disable_interrupt(Event::Overflow);
+            rtc.disable_interrupt(Event::Compare);
+        });
+    }
+}
+
+impl<'a, 'b: 'a, C: Chip, T: Instance<C>> embrio_core::timer::Timer
+    for &'a mut Rtc<'b, C, T>
+{
+    type Error = Error;
+
+    type Timeout = Timeout<'a, 'b, C, T>;
+
+    type Interval = Interval<'a, 'b, C, T>;
+
+    fn timeout(self, duration: Duration) -> Self::Timeout {
+        match self.deadline_after(duration_to_ticks(duration)) {
+            Ok(deadline) => Timeout {
+                rtc: Some(self),
+                deadline,
+                error: None,
+            },
+            Err(error) => Timeout {
+                rtc: Some(self),
+                deadline: 0,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn interval(self, duration: Duration) -> Self::Interval {
+        let period = duration_to_ticks(duration).map(|period| period.max(1));
+        match self.deadline_after(period) {
+            Ok(deadline) => Interval {
+                _rtc: self,
+                period: period.unwrap(),
+                deadline,
+                error: None,
+            },
+            Err(error) => Interval {
+                _rtc: self,
+                period: 0,
+                deadline: 0,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self> {
+        let rtc = Pin::get_mut(timeout).rtc.take();
+        if rtc.is_some() {
+            Rtc::<C, T>::cancel();
+        }
+        rtc
+    }
+}
+
+impl<'a, 'b: 'a, C: Chip, T: Instance<C>> Future for Timeout<'a, 'b, C, T> {
+    type Output = Result<&'a mut Rtc<'b, C, T>, Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        if let Some(error) = self.error.take() {
+            self.rtc.take();
+            return Poll::Ready(Err(error));
+        }
+        match Rtc::<C, T>::poll_deadline(cx, self.deadline) {
+            Poll::Ready(()) => Poll::Ready(Ok(self.rtc.take().unwrap())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a, 'b: 'a, C: Chip, T: Instance<C>> Drop for Timeout<'a, 'b, C, T> {
+    fn drop(&mut self) {
+        if self.rtc.is_some() {
+            Rtc::<C, T>::cancel();
+        }
+    }
+}
+
+impl<'a, 'b: 'a, C: Chip, T: Instance<C>> Stream for Interval<'a, 'b, C, T> {
+    type Item = Result<(), Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // A failed interval reports its error once, then ends
+        if self.period == 0 {
+            return Poll::Ready(self.error.take().map(Err));
+        }
+        match Rtc::<C, T>::poll_deadline(cx, self.deadline) {
+            Poll::Ready(()) => {
+                // Measured from the previous deadline so the ticks don't drift
+                self.deadline += self.period;
+                Poll::Ready(Some(Ok(())))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a, 'b: 'a, C: Chip, T: Instance<C>> Drop for Interval<'a, 'b, C, T> {
+    fn drop(&mut self) {
+        Rtc::<C, T>::cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_round_up() {
+        assert_eq!(duration_to_ticks(Duration::from_secs(0)), Ok(0));
+        assert_eq!(duration_to_ticks(Duration::from_secs(1)), Ok(32_768));
+        assert_eq!(duration_to_ticks(Duration::from_millis(1)), Ok(33));
+        assert_eq!(duration_to_ticks(Duration::from_nanos(1)), Ok(1));
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(1 << 24)),
+            Ok((1 << 24) * 32_768)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(1 << 49)),
+            Err(Error::TooLong)
+        );
+    }
+
+    #[test]
+    fn compares() {
+        assert_eq!(ticks(3, 5), 3 << 24 | 5);
+        assert_eq!(compare(100, 10), Some(100));
+        assert_eq!(compare(11, 10), Some(12));
+        assert_eq!(compare(ticks(2, 1), ticks(1, 2)), Some(1));
+        assert_eq!(compare(ticks(2, 3), ticks(1, 2)), None);
+    }
+}
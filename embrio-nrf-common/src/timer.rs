@@ -0,0 +1,638 @@
+//! The TIMER peripherals, counters of a configurable width running from the
+//! 16MHz HFCLK.
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use futures_core::stream::Stream;
+
+use crate::{
+    clock::{self, Hfclk},
+    Chip,
+};
+
+const FREQUENCY: u128 = 16_000_000;
+
+// A compare must be set at least this far ahead of the counter to be
+// guaranteed to trigger, covering the write after the counter was captured
+const MIN_AHEAD: u32 = 2;
+
+/// Number of compare channels, each can run an independent timeout.
+pub const CHANNELS: usize = 4;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The duration can't be represented by the counter with the configured
+    /// bit width and prescaler
+    TooLong,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BitMode {
+    Bits8,
+    Bits16,
+    Bits24,
+    Bits32,
+}
+
+impl BitMode {
+    pub fn bits(self) -> u32 {
+        match self {
+            BitMode::Bits8 => 8,
+            BitMode::Bits16 => 16,
+            BitMode::Bits24 => 24,
+            BitMode::Bits32 => 32,
+        }
+    }
+
+    pub fn mask(self) -> u32 {
+        (((1u64) << self.bits()) - 1) as u32
+    }
+}
+
+#[derive(Debug)]
+pub struct Builder<C, T> {
+    timer: T,
+    bit_mode: Option<BitMode>,
+    prescaler: u8,
+    _marker: PhantomData<C>,
+}
+
+/// A free running counter with a compare channel per timeout.
+///
+/// The timer itself uses channel 0, use [`channels`](Timer::channels) to run
+/// a timeout on each channel independently. It keeps the HFCLK crystal
+/// running for accuracy, prefer an RTC where that isn't needed.
+#[derive(Debug)]
+pub struct Timer<C: clock::Registers, T: Instance<C>> {
+    timer: T,
+    bit_mode: BitMode,
+    prescaler: u8,
+    _hfclk: Hfclk<C>,
+}
+
+#[derive(Debug)]
+pub struct Channel<'a, C: clock::Registers, T: Instance<C>> {
+    timer: &'a Timer<C, T>,
+    index: usize,
+}
+
+#[derive(Debug)]
+pub struct Timeout<'a, S: Compare> {
+    source: Option<&'a mut S>,
+    error: Option<Error>,
+}
+
+#[derive(Debug)]
+pub struct Interval<'a, S: Compare> {
+    source: &'a mut S,
+    period: u32,
+    error: Option<Error>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Task {
+    Start,
+    Stop,
+    Clear,
+    Capture(usize),
+}
+
+/// Access to a TIMER's registers, implemented by each chip crate for its PAC
+/// register block.
+pub trait Registers<C> {
+    fn trigger(&self, task: Task);
+    fn disable_shorts(&self);
+    fn set_bit_mode(&self, bit_mode: BitMode);
+    fn set_prescaler(&self, prescaler: u8);
+    fn compare(&self, index: usize) -> u32;
+    fn set_compare(&self, index: usize, value: u32);
+    fn event(&self, index: usize) -> bool;
+    fn clear_event(&self, index: usize);
+    fn enable_interrupt(&self, index: usize);
+    fn disable_interrupt(&self, index: usize);
+}
+
+pub type Wakers = Mutex<RefCell<[Option<Waker>; CHANNELS]>>;
+
+/// A TIMER peripheral, implemented by each chip crate for its instances.
+///
+/// # Safety
+///
+/// Each implementation must be a distinct TIMER peripheral, with its own
+/// registers and wakers that nothing else uses. Chip crates seal their own
+/// instance trait on top of this so only they provide it.
+pub unsafe trait Instance<C: Chip> {
+    type Registers: Registers<C> + 'static;
+
+    #[doc(hidden)]
+    const INTERRUPT: C::Interrupt;
+    /// The widest bit mode supported by this instance
+    #[doc(hidden)]
+    const MAX_BIT_MODE: BitMode;
+
+    #[doc(hidden)]
+    fn registers() -> &'static Self::Registers;
+
+    /// One slot per compare channel
+    #[doc(hidden)]
+    fn wakers() -> &'static Wakers;
+}
+
+mod sealed {
+    use super::{BitMode, Instance};
+    use crate::Chip;
+
+    pub trait Compare {
+        type Chip: Chip;
+        type Instance: Instance<Self::Chip>;
+
+        fn index(&self) -> usize;
+        fn bit_mode(&self) -> BitMode;
+        fn prescaler(&self) -> u8;
+    }
+}
+
+use self::sealed::Compare;
+
+/// Rounds up so that a timeout never fires early, and to at least one tick so
+/// that the compare is in the future.
+pub fn duration_to_ticks(
+    duration: Duration,
+    bit_mode: BitMode,
+    prescaler: u8,
+) -> Result<u32, Error> {
+    let frequency = FREQUENCY >> prescaler;
+    let ticks = (duration.as_nanos() * frequency + 999_999_999) / 1_000_000_000;
+    if ticks > bit_mode.mask().into() {
+        return Err(Error::TooLong);
+    }
+    Ok((ticks as u32).max(1))
+}
+
+/// The compare a period on from the `previous` one, measured from it so that
+/// the ticks don't drift unless they have fallen behind `now`.
+pub fn next_compare(
+    previous: u32,
+    now: u32,
+    period: u32,
+    bit_mode: BitMode,
+) -> u32 {
+    let mask = bit_mode.mask();
+    let elapsed = now.wrapping_sub(previous) & mask;
+    let base = if elapsed >= period { now } else { previous };
+    let compare = base.wrapping_add(period) & mask;
+    if compare.wrapping_sub(now) & mask < MIN_AHEAD {
+        now.wrapping_add(MIN_AHEAD) & mask
+    } else {
+        compare
+    }
+}
+
+/// The compare `ticks` on from `start`, re-checked against the counter `now`
+/// after it was first written. If that leaves it less than `MIN_AHEAD` away,
+/// or already passed, it is moved on so it still triggers.
+pub fn arm_compare(start: u32, now: u32, ticks: u32, bit_mode: BitMode) -> u32 {
+    let mask = bit_mode.mask();
+    let elapsed = now.wrapping_sub(start) & mask;
+    if ticks.saturating_sub(elapsed) < MIN_AHEAD {
+        now.wrapping_add(MIN_AHEAD) & mask
+    } else {
+        start.wrapping_add(ticks) & mask
+    }
+}
+
+impl<C, T> Builder<C, T> {
+    /// Defaults to the widest bit mode supported by the instance and a
+    /// prescaler of 4 (1MHz).
+    pub fn new(timer: T) -> Self {
+        Builder {
+            timer,
+            bit_mode: None,
+            prescaler: 4,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn bit_mode(mut self, bit_mode: BitMode) -> Self {
+        self.bit_mode = Some(bit_mode);
+        self
+    }
+
+    /// The counter runs at `16MHz / 2^prescaler`, up to a maximum of 9.
+    pub fn prescaler(mut self, prescaler: u8) -> Self {
+        assert!(prescaler <= 9);
+        self.prescaler = prescaler;
+        self
+    }
+}
+
+fn configure<C>(timer: &impl Registers<C>, bit_mode: BitMode, prescaler: u8) {
+    timer.trigger(Task::Stop);
+    timer.trigger(Task::Clear);
+    timer.disable_shorts();
+    for index in 0..CHANNELS {
+        timer.disable_interrupt(index);
+        timer.clear_event(index);
+    }
+    timer.set_bit_mode(bit_mode);
+    timer.set_prescaler(prescaler);
+    timer.trigger(Task::Start);
+}
+
+fn registers<S: Compare>(
+) -> &'static <S::Instance as Instance<S::Chip>>::Registers {
+    S::Instance::registers()
+}
+
+/// The counter can only be read by capturing it into the channel's own CC,
+/// so the re-check after writing the compare displaces it and it is written
+/// again at least `MIN_AHEAD` past that capture.
+fn arm<S: Compare>(source: &S, ticks: u32) {
+    let timer = registers::<S>();
+    let index = source.index();
+    let bit_mode = source.bit_mode();
+    free(|_| {
+        timer.trigger(Task::Capture(index));
+        let start = timer.compare(index);
+        timer.clear_event(index);
+        timer.set_compare(index, start.wrapping_add(ticks) & bit_mode.mask());
+
+        timer.trigger(Task::Capture(index));
+        let now = timer.compare(index);
+        // Unless it triggered in between, which stays latched
+        if !timer.event(index) {
+            let compare = arm_compare(start, now, ticks, bit_mode);
+            timer.set_compare(index, compare);
+        }
+        timer.enable_interrupt(index);
+    });
+}
+
+fn poll_compare<S: Compare>(source: &S, cx: &mut task::Context<'_>) -> bool {
+    let timer = registers::<S>();
+    let index = source.index();
+    free(|c| {
+        S::Instance::wakers().borrow(c).borrow_mut()[index] =
+            Some(cx.waker().clone());
+    });
+    if timer.event(index) {
+        timer.clear_event(index);
+        true
+    } else {
+        timer.enable_interrupt(index);
+        false
+    }
+}
+
+/// Move the compare on by a period after it fires, measured from the previous
+/// compare so that the ticks don't drift unless they have fallen behind.
+fn advance<S: Compare>(source: &S, period: u32) {
+    let timer = registers::<S>();
+    let index = source.index();
+    free(|_| {
+        let previous = timer.compare(index);
+        timer.trigger(Task::Capture(index));
+        let now = timer.compare(index);
+        let compare = next_compare(previous, now, period, source.bit_mode());
+        timer.set_compare(index, compare);
+        timer.enable_interrupt(index);
+    });
+}
+
+fn cancel<S: Compare>(source: &S) {
+    let timer = registers::<S>();
+    let index = source.index();
+    timer.disable_interrupt(index);
+    timer.clear_event(index);
+}
+
+fn timeout<S: Compare>(source: &mut S, duration: Duration) -> Timeout<'_, S> {
+    match duration_to_ticks(duration, source.bit_mode(), source.prescaler()) {
+        Ok(ticks) => {
+            arm(source, ticks);
+            Timeout {
+                source: Some(source),
+                error: None,
+            }
+        }
+        Err(error) => Timeout {
+            source: Some(source),
+            error: Some(error),
+        },
+    }
+}
+
+fn cancel_timeout<'a, S: Compare>(
+    timeout: Pin<&mut Timeout<'a, S>>,
+) -> Option<&'a mut S> {
+    let source = Pin::get_mut(timeout).source.take();
+    if let Some(source) = &source {
+        cancel(&**source);
+    }
+    source
+}
+
+fn interval<S: Compare>(source: &mut S, duration: Duration) -> Interval<'_, S> {
+    match duration_to_ticks(duration, source.bit_mode(), source.prescaler()) {
+        Ok(period) => {
+            arm(source, period);
+            Interval {
+                source,
+                period,
+                error: None,
+            }
+        }
+        Err(error) => Interval {
+            source,
+            period: 0,
+            error: Some(error),
+        },
+    }
+}
+
+impl<C: clock::Registers, T: Instance<C>> Builder<C, T> {
+    pub fn build(self) -> Timer<C, T> {
+        let bit_mode = self.bit_mode.unwrap_or(T::MAX_BIT_MODE);
+        assert!(bit_mode.bits() <= T::MAX_BIT_MODE.bits());
+
+        let hfclk = Hfclk::request();
+        configure(T::registers(), bit_mode, self.prescaler);
+
+        unsafe { NVIC::unmask(T::INTERRUPT) };
+
+        Timer {
+            timer: self.timer,
+            bit_mode,
+            prescaler: self.prescaler,
+            _hfclk: hfclk,
+        }
+    }
+}
+
+impl<C: clock::Registers, T: Instance<C>> Timer<C, T> {
+    pub fn new(timer: T) -> Self {
+        Builder::new(timer).build()
+    }
+
+    pub fn channels(&mut self) -> [Channel<'_, C, T>; CHANNELS] {
+        let timer = &*self;
+        [
+            Channel { timer, index: 0 },
+            Channel { timer, index: 1 },
+            Channel { timer, index: 2 },
+            Channel { timer, index: 3 },
+        ]
+    }
+
+    /// Access the timer for driving other peripherals through PPI, `restore`
+    /// must be called before it's used as a `Timer` again.
+    #[doc(hidden)]
+    pub fn registers(&mut self) -> &T::Registers {
+        T::registers()
+    }
+
+    #[doc(hidden)]
+    pub fn restore(&mut self) {
+        configure(T::registers(), self.bit_mode, self.prescaler);
+    }
+
+    /// Wake the channels with a compare event, the events are left for them
+    /// to observe so their interrupts are disabled until re-armed.
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let timer = T::registers();
+            let mut wakers = T::wakers().borrow(c).borrow_mut();
+            for (index, waker) in wakers.iter_mut().enumerate() {
+                if timer.event(index) {
+                    timer.disable_interrupt(index);
+                    if let Some(waker) = waker.take() {
+                        waker.wake();
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl<C: clock::Registers, T: Instance<C>> Compare for Timer<C, T> {
+    type Chip = C;
+    type Instance = T;
+
+    fn index(&self) -> usize {
+        0
+    }
+
+    fn bit_mode(&self) -> BitMode {
+        self.bit_mode
+    }
+
+    fn prescaler(&self) -> u8 {
+        self.prescaler
+    }
+}
+
+impl<'c, C: clock::Registers, T: Instance<C>> Compare for Channel<'c, C, T> {
+    type Chip = C;
+    type Instance = T;
+
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn bit_mode(&self) -> BitMode {
+        self.timer.bit_mode
+    }
+
+    fn prescaler(&self) -> u8 {
+        self.timer.prescaler
+    }
+}
+
+impl<'a, C: clock::Registers, T: Instance<C>> embrio_core::timer::Timer
+    for &'a mut Timer<C, T>
+{
+    type Error = Error;
+
+    type Timeout = Timeout<'a, Timer<C, T>>;
+
+    type Interval = Interval<'a, Timer<C, T>>;
+
+    fn timeout(self, duration: Duration) -> Self::Timeout {
+        timeout(self, duration)
+    }
+
+    fn interval(self, duration: Duration) -> Self::Interval {
+        interval(self, duration)
+    }
+
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self> {
+        cancel_timeout(timeout)
+    }
+}
+
+impl<'a, 'c: 'a, C: clock::Registers, T: Instance<C>> embrio_core::timer::Timer
+    for &'a mut Channel<'c, C, T>
+{
+    type Error = Error;
+
+    type Timeout = Timeout<'a, Channel<'c, C, T>>;
+
+    type Interval = Interval<'a, Channel<'c, C, T>>;
+
+    fn timeout(self, duration: Duration) -> Self::Timeout {
+        timeout(self, duration)
+    }
+
+    fn interval(self, duration: Duration) -> Self::Interval {
+        interval(self, duration)
+    }
+
+    fn cancel(timeout: Pin<&mut Self::Timeout>) -> Option<Self> {
+        cancel_timeout(timeout)
+    }
+}
+
+impl<'a, S: Compare> Future for Timeout<'a, S> {
+    type Output = Result<&'a mut S, Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        if let Some(error) = self.error.take() {
+            self.source.take();
+            return Poll::Ready(Err(error));
+        }
+        let source = self
+            .source
+            .as_mut()
+            .expect("Timeout polled after completion");
+        if poll_compare(&**source, cx) {
+            Poll::Ready(Ok(self.source.take().unwrap()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, S: Compare> Drop for Timeout<'a, S> {
+    fn drop(&mut self) {
+        if let Some(source) = &self.source {
+            cancel(&**source);
+        }
+    }
+}
+
+impl<'a, S: Compare> Stream for Interval<'a, S> {
+    type Item = Result<(), Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // A failed interval reports its error once, then ends
+        if self.period == 0 {
+            return Poll::Ready(self.error.take().map(Err));
+        }
+        if poll_compare(&*self.source, cx) {
+            advance(&*self.source, self.period);
+            Poll::Ready(Some(Ok(())))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, S: Compare> Drop for Interval<'a, S> {
+    fn drop(&mut self) {
+        cancel(&*self.source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks() {
+        assert_eq!(
+            duration_to_ticks(Duration::from_millis(1), BitMode::Bits32, 4),
+            Ok(1000)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(1), BitMode::Bits32, 4),
+            Ok(1_000_000)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(1), BitMode::Bits32, 0),
+            Ok(16_000_000)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(1), BitMode::Bits16, 9),
+            Ok(31_250)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_nanos(1), BitMode::Bits8, 4),
+            Ok(1)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(0), BitMode::Bits8, 4),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn too_long() {
+        assert_eq!(
+            duration_to_ticks(Duration::from_micros(255), BitMode::Bits8, 4),
+            Ok(255)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_micros(256), BitMode::Bits8, 4),
+            Err(Error::TooLong)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(3), BitMode::Bits16, 9),
+            Err(Error::TooLong)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(4295), BitMode::Bits32, 4),
+            Err(Error::TooLong)
+        );
+    }
+
+    #[test]
+    fn next_compares() {
+        assert_eq!(next_compare(100, 105, 10, BitMode::Bits8), 110);
+        assert_eq!(next_compare(250, 2, 10, BitMode::Bits8), 4);
+        assert_eq!(next_compare(100, 130, 10, BitMode::Bits8), 140);
+        assert_eq!(next_compare(!0, 3, 10, BitMode::Bits32), 9);
+        // Too close to the counter to be sure to trigger
+        assert_eq!(next_compare(100, 109, 10, BitMode::Bits8), 111);
+        assert_eq!(next_compare(250, 3, 10, BitMode::Bits8), 5);
+    }
+
+    #[test]
+    fn arm_compares() {
+        assert_eq!(arm_compare(100, 101, 10, BitMode::Bits8), 110);
+        assert_eq!(arm_compare(250, 2, 10, BitMode::Bits8), 4);
+        // Less than `MIN_AHEAD` left after the write
+        assert_eq!(arm_compare(100, 101, 1, BitMode::Bits8), 103);
+        assert_eq!(arm_compare(100, 109, 10, BitMode::Bits8), 111);
+        // Passed while it was written
+        assert_eq!(arm_compare(100, 112, 10, BitMode::Bits8), 114);
+        assert_eq!(arm_compare(!0, 1, 1, BitMode::Bits32), 3);
+    }
+}
@@ -0,0 +1,52 @@
+/// The hardware only supports even parity.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Parity {
+    None,
+    Even,
+}
+
+/// A receive error, reported once the bytes received before it have been
+/// read.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Received bytes were lost, either in hardware or because the receive
+    /// buffer was full.
+    Overrun,
+    Parity,
+    Framing,
+    /// The line was held low for longer than a frame.
+    Break,
+}
+
+impl Error {
+    /// Decodes the ERRORSRC register. A break also causes a framing error, so
+    /// the more specific error is preferred when several are flagged at once.
+    pub fn from_errorsrc(bits: u32) -> Option<Self> {
+        if bits & (1 << 3) != 0 {
+            Some(Error::Break)
+        } else if bits & (1 << 2) != 0 {
+            Some(Error::Framing)
+        } else if bits & (1 << 1) != 0 {
+            Some(Error::Parity)
+        } else if bits & 1 != 0 {
+            Some(Error::Overrun)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn errorsrc() {
+        assert_eq!(Error::from_errorsrc(0), None);
+        assert_eq!(Error::from_errorsrc(0b0001), Some(Error::Overrun));
+        assert_eq!(Error::from_errorsrc(0b0010), Some(Error::Parity));
+        assert_eq!(Error::from_errorsrc(0b0011), Some(Error::Parity));
+        assert_eq!(Error::from_errorsrc(0b0100), Some(Error::Framing));
+        assert_eq!(Error::from_errorsrc(0b1100), Some(Error::Break));
+    }
+}
@@ -0,0 +1,206 @@
+use core::{
+    cell::RefCell,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use nrf51::{Interrupt, ADC};
+
+use self::mode::{Reference, Resolution, Scaling};
+
+pub mod mode;
+
+#[derive(Debug)]
+pub struct Adc<'b> {
+    _marker: PhantomData<(&'b mut ADC, &'b mut NVIC)>,
+}
+
+/// Converts analog inputs `AIN0` to `AIN7`, selected by channel number.
+#[derive(Debug)]
+pub struct Converter<'a, 'b: 'a, R, V, S> {
+    mode: (R, V, S),
+    _marker: PhantomData<&'a mut Adc<'b>>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    Idle,
+    Busy,
+    Done(u16),
+}
+
+struct Context {
+    adc: &'static ADC,
+    waker: Option<Waker>,
+    state: State,
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl<'b> Adc<'b> {
+    pub(crate) fn new(adc: &'b mut ADC) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                adc: unsafe { erase_lifetime(adc) },
+                waker: None,
+                state: State::Idle,
+            });
+        });
+
+        Adc {
+            _marker: PhantomData,
+        }
+    }
+
+    /// The resolution, reference and input scaling are chosen by the type of
+    /// the returned converter.
+    pub fn init<'a, R: Resolution, V: Reference, S: Scaling>(
+        &'a mut self,
+    ) -> Converter<'a, 'b, R, V, S>
+    where
+        'b: 'a,
+    {
+        let mode = free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            let mut mode = None;
+            context.adc.config.write(|w| {
+                mode = Some((R::apply(w), V::apply(w), S::apply(w)));
+                w
+            });
+            context.adc.intenset.write(|w| w.end().set());
+            context.adc.enable.write(|w| w.enable().enabled());
+
+            unsafe { NVIC::unmask(Interrupt::ADC) };
+
+            mode.expect("write is guaranteed to set this")
+        });
+
+        Converter {
+            mode,
+            _marker: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            if context.adc.events_end.read().bits() == 1 {
+                context.adc.events_end.reset();
+                let result = context.adc.result.read().result().bits();
+                context.state = State::Done(result);
+                if let Some(waker) = context.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Adc<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+
+            context.adc.tasks_stop.write(|w| unsafe { w.bits(1) });
+            context.adc.intenclr.write(|w| w.end().clear());
+            context.adc.enable.write(|w| w.enable().disabled());
+        });
+    }
+}
+
+impl<'a, 'b: 'a, R: Resolution, V: Reference, S: Scaling>
+    Converter<'a, 'b, R, V, S>
+{
+    /// Convert a reading into the voltage at the input pin in millivolts,
+    /// `supply` is only used when the reference is derived from it.
+    pub fn millivolts(&self, raw: u16, supply: u32) -> u32 {
+        mode::millivolts::<R, V, S>(raw, supply)
+    }
+}
+
+impl<'a, 'b: 'a, R, V, S> embrio_core::adc::Adc for Converter<'a, 'b, R, V, S> {
+    type Channel = usize;
+
+    type Error = !;
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        channel: usize,
+    ) -> Poll<Result<u16, !>> {
+        assert!(channel < 8);
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            match context.state {
+                State::Idle => {
+                    context
+                        .adc
+                        .config
+                        .modify(|_, w| unsafe { w.psel().bits(1 << channel) });
+                    context.state = State::Busy;
+                    context.waker = Some(cx.waker().clone());
+                    context.adc.tasks_start.write(|w| unsafe { w.bits(1) });
+                    Poll::Pending
+                }
+                State::Busy => {
+                    context.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                State::Done(result) => {
+                    context.state = State::Idle;
+                    Poll::Ready(Ok(result))
+                }
+            }
+        })
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if let State::Busy = context.state {
+                context.adc.tasks_stop.write(|w| unsafe { w.bits(1) });
+                context.adc.events_end.reset();
+            }
+            context.waker = None;
+            context.state = State::Idle;
+        });
+    }
+}
+
+impl<'a, 'b: 'a, R, V, S> Drop for Converter<'a, 'b, R, V, S> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            // Discard any conversion left behind by a cancelled read
+            context.adc.tasks_stop.write(|w| unsafe { w.bits(1) });
+            context.adc.events_end.reset();
+            context.adc.enable.write(|w| w.enable().disabled());
+            context.waker = None;
+            context.state = State::Idle;
+        });
+    }
+}
@@ -0,0 +1,183 @@
+use nrf51::adc::config;
+
+pub trait Resolution: Sized {
+    const BITS: u32;
+
+    fn apply(w: &mut config::W) -> Self;
+}
+
+pub trait Reference: Sized {
+    /// The reference voltage in millivolts, `supply` is the supply voltage in
+    /// millivolts and is only used by references derived from it.
+    fn millivolts(supply: u32) -> u32;
+
+    fn apply(w: &mut config::W) -> Self;
+}
+
+/// The prescaling applied to the analog input before it is compared with the
+/// reference.
+pub trait Scaling: Sized {
+    const NUMERATOR: u32;
+    const DENOMINATOR: u32;
+
+    fn apply(w: &mut config::W) -> Self;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Bits8 {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Bits9 {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Bits10 {
+    _reserved: (),
+}
+
+/// The internal 1.2V band gap reference
+#[derive(Debug, Copy, Clone)]
+pub struct BandGap {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SupplyOneHalf {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SupplyOneThird {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Full {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TwoThirds {
+    _reserved: (),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct OneThird {
+    _reserved: (),
+}
+
+impl Resolution for Bits8 {
+    const BITS: u32 = 8;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.res()._8bit();
+        Bits8 { _reserved: () }
+    }
+}
+
+impl Resolution for Bits9 {
+    const BITS: u32 = 9;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.res()._9bit();
+        Bits9 { _reserved: () }
+    }
+}
+
+impl Resolution for Bits10 {
+    const BITS: u32 = 10;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.res()._10bit();
+        Bits10 { _reserved: () }
+    }
+}
+
+impl Reference for BandGap {
+    fn millivolts(_supply: u32) -> u32 {
+        1200
+    }
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.refsel().vbg();
+        BandGap { _reserved: () }
+    }
+}
+
+impl Reference for SupplyOneHalf {
+    fn millivolts(supply: u32) -> u32 {
+        supply / 2
+    }
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.refsel().supply_one_half_prescaling();
+        SupplyOneHalf { _reserved: () }
+    }
+}
+
+impl Reference for SupplyOneThird {
+    fn millivolts(supply: u32) -> u32 {
+        supply / 3
+    }
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.refsel().supply_one_third_prescaling();
+        SupplyOneThird { _reserved: () }
+    }
+}
+
+impl Scaling for Full {
+    const NUMERATOR: u32 = 1;
+    const DENOMINATOR: u32 = 1;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.inpsel().analog_input_no_prescaling();
+        Full { _reserved: () }
+    }
+}
+
+impl Scaling for TwoThirds {
+    const NUMERATOR: u32 = 2;
+    const DENOMINATOR: u32 = 3;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.inpsel().analog_input_two_thirds_prescaling();
+        TwoThirds { _reserved: () }
+    }
+}
+
+impl Scaling for OneThird {
+    const NUMERATOR: u32 = 1;
+    const DENOMINATOR: u32 = 3;
+
+    #[inline(always)]
+    fn apply(w: &mut config::W) -> Self {
+        w.inpsel().analog_input_one_third_prescaling();
+        OneThird { _reserved: () }
+    }
+}
+
+/// Convert a raw reading into the voltage at the input pin in millivolts.
+pub fn millivolts<R: Resolution, V: Reference, S: Scaling>(
+    raw: u16,
+    supply: u32,
+) -> u32 {
+    embrio_util::adc::millivolts(
+        raw,
+        R::BITS,
+        V::millivolts(supply),
+        S::NUMERATOR,
+        S::DENOMINATOR,
+    )
+}
@@ -0,0 +1,218 @@
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    ptr,
+    task::{self, Poll},
+};
+
+use embrio_core::storage::{check, Error, Storage};
+use nrf51::{FICR, NVMC};
+
+const WORD: usize = 4;
+
+/// The code flash, the CPU stalls while a page is erased or a word written
+/// so long operations yield after each page to let other tasks run.
+#[derive(Debug)]
+pub struct Flash<'b> {
+    _marker: PhantomData<&'b mut NVMC>,
+}
+
+/// A page aligned region of flash implementing [`Storage`], with offsets
+/// relative to its start.
+#[derive(Debug)]
+pub struct Partition<'a, 'b: 'a> {
+    start: usize,
+    len: usize,
+    page_size: usize,
+    progress: Option<Progress>,
+    _marker: PhantomData<&'a mut Flash<'b>>,
+}
+
+/// How far through a multi-page operation we are, if it is polled with other
+/// arguments it was abandoned and a new operation starts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Progress {
+    erase: bool,
+    offset: usize,
+    len: usize,
+    done: usize,
+}
+
+// Safety: only accessed through an exclusively borrowed `Flash`
+fn nvmc() -> &'static nrf51::nvmc::RegisterBlock {
+    unsafe { &*NVMC::ptr() }
+}
+
+fn ficr() -> &'static nrf51::ficr::RegisterBlock {
+    unsafe { &*FICR::ptr() }
+}
+
+fn wait_ready(nvmc: &nrf51::nvmc::RegisterBlock) {
+    while nvmc.ready.read().bits() == 0 {}
+}
+
+impl<'b> Flash<'b> {
+    pub(crate) fn new(_nvmc: &'b mut NVMC) -> Self {
+        Flash {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn page_size(&self) -> usize {
+        ficr().codepagesize.read().bits() as usize
+    }
+
+    pub fn size(&self) -> usize {
+        self.page_size() * ficr().codesize.read().bits() as usize
+    }
+
+    /// `len` bytes of flash starting `start` bytes from its beginning, both
+    /// must be multiples of the page size.
+    ///
+    /// Nothing stops this from overlapping the running program, the linker
+    /// script should reserve the pages used.
+    pub fn partition<'a>(
+        &'a mut self,
+        start: usize,
+        len: usize,
+    ) -> Partition<'a, 'b> {
+        let page_size = self.page_size();
+        assert!(start % page_size == 0 && len % page_size == 0);
+        assert!(start + len <= self.size());
+        Partition {
+            start,
+            len,
+            page_size,
+            progress: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b: 'a> Partition<'a, 'b> {
+    /// Runs `step` on the next part of the operation, up to the end of the
+    /// current page, until the whole range is done.
+    fn poll_pages(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        erase: bool,
+        offset: usize,
+        len: usize,
+        step: impl FnOnce(&nrf51::nvmc::RegisterBlock, usize, usize),
+    ) -> Poll<()> {
+        let mut progress = match self.progress {
+            Some(progress)
+                if progress.erase == erase
+                    && progress.offset == offset
+                    && progress.len == len =>
+            {
+                progress
+            }
+            _ => Progress {
+                erase,
+                offset,
+                len,
+                done: 0,
+            },
+        };
+
+        if progress.done < len {
+            let position = offset + progress.done;
+            let page_end = (position / self.page_size + 1) * self.page_size;
+            let count = (page_end - position).min(len - progress.done);
+            step(nvmc(), progress.done, count);
+            nvmc().config.write(|w| w.wen().ren());
+            progress.done += count;
+        }
+
+        if progress.done < len {
+            self.progress = Some(progress);
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            self.progress = None;
+            Poll::Ready(())
+        }
+    }
+}
+
+impl<'a, 'b: 'a> Storage for Partition<'a, 'b> {
+    type Error = !;
+
+    fn capacity(&self) -> usize {
+        self.len
+    }
+
+    fn read_size(&self) -> usize {
+        1
+    }
+
+    fn write_size(&self) -> usize {
+        WORD
+    }
+
+    fn erase_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Poll<Result<(), Error<!>>> {
+        check(self.len, 1, offset, buf.len())?;
+        let address = (self.start + offset) as *const u8;
+        for (i, byte) in buf.iter_mut().enumerate() {
+            // Safety: within the flash, which is mapped from address 0
+            *byte = unsafe { ptr::read_volatile(address.add(i)) };
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        data: &[u8],
+    ) -> Poll<Result<(), Error<!>>> {
+        let this = Pin::get_mut(self);
+        check(this.len, WORD, offset, data.len())?;
+        let address = this.start + offset;
+        this.poll_pages(cx, false, offset, data.len(), |nvmc, done, count| {
+            nvmc.config.write(|w| w.wen().wen());
+            for i in (done..done + count).step_by(WORD) {
+                let word = u32::from_le_bytes([
+                    data[i],
+                    data[i + 1],
+                    data[i + 2],
+                    data[i + 3],
+                ]);
+                // Safety: within the flash, which is mapped from address 0
+                unsafe {
+                    ptr::write_volatile((address + i) as *mut u32, word);
+                }
+                wait_ready(nvmc);
+            }
+        })
+        .map(Ok)
+    }
+
+    fn poll_erase(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        offset: usize,
+        len: usize,
+    ) -> Poll<Result<(), Error<!>>> {
+        let this = Pin::get_mut(self);
+        check(this.len, this.page_size, offset, len)?;
+        let address = this.start + offset;
+        this.poll_pages(cx, true, offset, len, |nvmc, done, _| {
+            nvmc.config.write(|w| w.wen().een());
+            nvmc.erasepage
+                .write(|w| unsafe { w.bits((address + done) as u32) });
+            wait_ready(nvmc);
+        })
+        .map(Ok)
+    }
+}
@@ -1,32 +1,140 @@
-use nrf51::GPIO;
+use embrio_nrf_common::gpio::{self, Registers};
+use nrf51::{Interrupt, GPIO, GPIOTE};
 
-pub use self::pin::Pin;
+use crate::Nrf51;
 
 pub mod mode;
-mod pin;
 
-macro_rules! pin {
-    ($s:expr) => {
-        Pin<'a, mode::Unconfigured>
+/// Pin `N` of the GPIO port, the pin number is only part of the type so this
+/// is zero sized.
+pub type Pin<'a, const N: u8, Mode> = gpio::Pin<'a, Nrf51, N, Mode>;
+
+/// A [`Pin`] that has had its number moved to runtime with `degrade`, so pins
+/// in different positions can be stored together, e.g. in an array.
+pub type AnyPin<'a, Mode> = gpio::AnyPin<'a, Nrf51, Mode>;
+
+/// Pins driven together as an [`embrio_core::gpio::Port`], or read together as
+/// an [`embrio_core::gpio::InputPort`], pin `n` of the group is `pins[n]`.
+pub type Port<'a, Mode, const W: usize> = gpio::Port<'a, Nrf51, Mode, W>;
+
+pub(crate) fn arm_sense(pin: usize, level: bool) {
+    gpio::arm_sense::<Nrf51>(pin, level)
+}
+
+pub(crate) fn interrupt() {
+    gpio::interrupt::<Nrf51>()
+}
+
+// Safety: each pin only writes its own PIN_CNF register and bits in the
+// atomic OUTSET/OUTCLR registers, SENSE and the PORT event are only touched
+// from critical sections in the shared driver
+fn gpio() -> &'static nrf51::gpio::RegisterBlock {
+    unsafe { &*GPIO::ptr() }
+}
+
+fn gpiote() -> &'static nrf51::gpiote::RegisterBlock {
+    unsafe { &*GPIOTE::ptr() }
+}
+
+impl Registers for Nrf51 {
+    const GPIOTE: Interrupt = Interrupt::GPIOTE;
+
+    fn pin_cnf(pin: usize) -> u32 {
+        gpio().pin_cnf[pin].read().bits()
+    }
+
+    fn set_pin_cnf(pin: usize, bits: u32) {
+        gpio().pin_cnf[pin].write(|w| unsafe { w.bits(bits) });
+    }
+
+    fn out() -> u32 {
+        gpio().out.read().bits()
     }
+
+    fn set_out(bits: u32) {
+        gpio().outset.write(|w| unsafe { w.bits(bits) });
+    }
+
+    fn clear_out(bits: u32) {
+        gpio().outclr.write(|w| unsafe { w.bits(bits) });
+    }
+
+    fn input() -> u32 {
+        gpio().in_.read().bits()
+    }
+
+    fn port_event() -> bool {
+        gpiote().events_port.read().bits() == 1
+    }
+
+    fn clear_port_event() {
+        gpiote().events_port.reset();
+    }
+
+    fn enable_port_interrupt() {
+        gpiote().intenset.write(|w| w.port().set());
+    }
+
+    /// The nRF51 has no LATCH register, DETECT follows the pins directly.
+    fn latch() -> Option<u32> {
+        None
+    }
+
+    fn clear_latch(_bits: u32) {}
 }
 
 macro_rules! pins {
-    ($($i:expr),*) => {
+    ($($name:ident: $i:expr),*) => {
         #[derive(Debug)]
-        pub struct Pins<'a>($(pub pin!($i)),*);
+        pub struct Pins<'a> {
+            $(pub $name: Pin<'a, { $i }, mode::Unconfigured>),*
+        }
 
         impl<'a> Pins<'a> {
-            pub(crate) fn new(gpio: &'a mut GPIO) -> Self {
-                Pins($(Pin::new(&*gpio, $i)),*)
+            pub(crate) fn new(_gpio: &'a mut GPIO) -> Self {
+                // Safety: each pin is created once, borrowing the GPIO
+                // peripheral for 'a
+                unsafe {
+                    Pins {
+                        $($name: Pin::new()),*
+                    }
+                }
             }
         }
     }
 }
 
 pins! {
-     0,  1,  2,  3,  4,  5,  6,  7,
-     8,  9, 10, 11, 12, 13, 14, 15,
-    16, 17, 18, 19, 20, 21, 22, 23,
-    24, 25, 26, 27, 28, 29, 30, 31
+    p0_00: 0,
+    p0_01: 1,
+    p0_02: 2,
+    p0_03: 3,
+    p0_04: 4,
+    p0_05: 5,
+    p0_06: 6,
+    p0_07: 7,
+    p0_08: 8,
+    p0_09: 9,
+    p0_10: 10,
+    p0_11: 11,
+    p0_12: 12,
+    p0_13: 13,
+    p0_14: 14,
+    p0_15: 15,
+    p0_16: 16,
+    p0_17: 17,
+    p0_18: 18,
+    p0_19: 19,
+    p0_20: 20,
+    p0_21: 21,
+    p0_22: 22,
+    p0_23: 23,
+    p0_24: 24,
+    p0_25: 25,
+    p0_26: 26,
+    p0_27: 27,
+    p0_28: 28,
+    p0_29: 29,
+    p0_30: 30,
+    p0_31: 31
 }
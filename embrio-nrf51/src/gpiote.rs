@@ -0,0 +1,177 @@
+use core::marker::PhantomData;
+
+use nrf51::{gpiote::config, GPIOTE};
+
+use crate::{
+    gpio::{
+        self,
+        mode::{Input, InputMode, Output, OutputMode},
+    },
+    ppi,
+};
+
+pub const CHANNELS: usize = 4;
+
+/// Which pin transitions generate an event, or what triggering the task does
+/// to the pin.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Polarity {
+    LoToHi,
+    HiToLo,
+    Toggle,
+}
+
+/// An unused channel, bind it to a pin with [`event`](Channel::event) or
+/// [`task`](Channel::task).
+#[derive(Debug)]
+pub struct Channel<'a> {
+    index: usize,
+    _marker: PhantomData<&'a GPIOTE>,
+}
+
+/// Generates an [`Event`](ppi::Event) when its input pin changes.
+#[derive(Debug)]
+pub struct EventChannel<'a, Mode> {
+    channel: Channel<'a>,
+    pin: gpio::AnyPin<'a, Input<Mode>>,
+}
+
+/// Drives its output pin when its [`Task`](ppi::Task) is triggered, the pin is
+/// no longer controlled through GPIO until the channel is freed.
+#[derive(Debug)]
+pub struct TaskChannel<'a, Mode> {
+    channel: Channel<'a>,
+    pin: gpio::AnyPin<'a, Output<Mode>>,
+}
+
+macro_rules! channel {
+    ($i:expr) => {
+        Channel<'a>
+    }
+}
+
+macro_rules! channels {
+    ($($i:expr),*) => {
+        #[derive(Debug)]
+        pub struct Channels<'a>($(pub channel!($i)),*);
+
+        impl<'a> Channels<'a> {
+            pub(crate) fn new(gpiote: &'a mut GPIOTE) -> Self {
+                Channels($(Channel::new(&*gpiote, $i)),*)
+            }
+        }
+    }
+}
+
+channels! { 0, 1, 2, 3 }
+
+// Safety: each channel only writes its own CONFIG, TASKS_OUT and EVENTS_IN
+// registers
+fn gpiote() -> &'static nrf51::gpiote::RegisterBlock {
+    unsafe { &*GPIOTE::ptr() }
+}
+
+impl Polarity {
+    fn apply(self, w: &mut config::W) -> &mut config::W {
+        match self {
+            Polarity::LoToHi => w.polarity().lo_to_hi(),
+            Polarity::HiToLo => w.polarity().hi_to_lo(),
+            Polarity::Toggle => w.polarity().toggle(),
+        }
+    }
+}
+
+impl<'a> Channel<'a> {
+    fn new(_gpiote: &'a GPIOTE, index: usize) -> Self {
+        Channel {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn event<Mode: InputMode>(
+        self,
+        pin: gpio::AnyPin<'a, Input<Mode>>,
+        polarity: Polarity,
+    ) -> EventChannel<'a, Mode> {
+        gpiote().events_in[self.index].reset();
+        gpiote().config[self.index].write(|w| {
+            unsafe { w.psel().bits(pin.get_id() as u8) };
+            polarity.apply(w.mode().event())
+        });
+        EventChannel { channel: self, pin }
+    }
+
+    /// The pin is driven to `initial` straight away.
+    pub fn task<Mode: OutputMode>(
+        self,
+        pin: gpio::AnyPin<'a, Output<Mode>>,
+        polarity: Polarity,
+        initial: bool,
+    ) -> TaskChannel<'a, Mode> {
+        self.configure_task(pin.get_id(), polarity, initial);
+        TaskChannel { channel: self, pin }
+    }
+
+    pub(crate) fn configure_task(
+        &self,
+        pin: usize,
+        polarity: Polarity,
+        initial: bool,
+    ) {
+        gpiote().config[self.index].write(|w| {
+            unsafe { w.psel().bits(pin as u8) };
+            polarity.apply(w.mode().task().outinit().bit(initial))
+        });
+    }
+
+    pub(crate) fn reset(&self) {
+        gpiote().config[self.index].reset();
+    }
+
+    pub(crate) fn out_task(&self) -> ppi::Task {
+        // Safety: this is a task register
+        unsafe { ppi::Task::from_register(&gpiote().tasks_out[self.index]) }
+    }
+}
+
+impl<'a, Mode> EventChannel<'a, Mode> {
+    pub fn event(&self) -> ppi::Event {
+        // Safety: this is an event register
+        unsafe { ppi::Event::from_register(&gpiote().events_in[self.index()]) }
+    }
+
+    pub fn index(&self) -> usize {
+        self.channel.index
+    }
+
+    pub fn free(self) -> (Channel<'a>, gpio::AnyPin<'a, Input<Mode>>) {
+        self.channel.reset();
+        (self.channel, self.pin)
+    }
+}
+
+impl<'a, Mode> TaskChannel<'a, Mode> {
+    pub fn task(&self) -> ppi::Task {
+        self.channel.out_task()
+    }
+
+    pub fn index(&self) -> usize {
+        self.channel.index
+    }
+
+    /// Trigger the task from software.
+    pub fn trigger(&mut self) {
+        gpiote().tasks_out[self.index()].write(|w| unsafe { w.bits(1) });
+    }
+
+    /// The pin returns to the level set through GPIO.
+    pub fn free(self) -> (Channel<'a>, gpio::AnyPin<'a, Output<Mode>>) {
+        self.channel.reset();
+        (self.channel, self.pin)
+    }
+}
@@ -2,37 +2,124 @@
 #![feature(
     arbitrary_self_types,
     const_fn,
+    const_generics,
     in_band_lifetimes,
-    never_type,
-    specialization
+    never_type
 )]
 // workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
 #![allow(clippy::missing_safety_doc)]
 
-mod zst_ref;
-
+pub mod adc;
+pub mod clock;
+pub mod flash;
 pub mod gpio;
+pub mod gpiote;
+pub mod power;
+pub mod ppi;
+pub mod pwm;
+pub mod radio;
+pub mod rng;
+pub mod rtc;
+pub mod spi;
+pub mod temp;
 pub mod timer;
+pub mod twi;
 pub mod uart;
+pub mod wdt;
 
 use core::{cell::UnsafeCell, ptr};
 
 use cortex_m::interrupt::{free, Mutex};
 use nrf51::interrupt;
 
-use self::{gpio::Pins, uart::Uart};
+use embrio_nrf_common::Chip;
+
+use self::{
+    adc::Adc, clock::Clock, flash::Flash, gpio::Pins, power::Power,
+    radio::Radio, rng::Rng, rtc::Rtc, spi::Spi, temp::Temp, twi::Twi,
+    uart::Uart, wdt::Wdt,
+};
+
+/// The nRF51 family, implementing the register access of the drivers shared
+/// with other families through `embrio-nrf-common`.
+#[derive(Debug)]
+pub enum Nrf51 {}
+
+impl Chip for Nrf51 {
+    type Interrupt = nrf51::Interrupt;
+}
 
+/// The nRF51 peripherals wrapped in their drivers.
+///
+/// Drivers needing an accurate HFCLK keep its crystal oscillator running
+/// while in use, and nothing stops it again until
+/// [`clock::release_unused_hfclk`] is called. Install that as the executor's
+/// sleep hook, with `Executor::with_sleep_hook`, to stop it whenever idle.
 pub struct EmbrioNrf51<'b> {
+    pub adc: Adc<'b>,
+    pub clock: Clock<'b>,
+    pub flash: Flash<'b>,
+    pub gpiote: gpiote::Channels<'b>,
     pub pins: Pins<'b>,
+    pub power: Power<'b>,
+    pub ppi: ppi::Channels<'b>,
+    pub radio: Radio<'b>,
+    pub rng: Rng<'b>,
+    /// Low power timers, these should be preferred over the TIMER peripherals
+    /// as they allow the HFCLK to stop while the executor is idle.
+    pub rtc0: Rtc<'b, nrf51::RTC0>,
+    pub rtc1: Rtc<'b, nrf51::RTC1>,
+    pub spi0: Spi<'b, nrf51::SPI0>,
+    pub spi1: Spi<'b, nrf51::SPI1>,
+    pub temp: Temp<'b>,
+    pub twi0: Twi<'b, nrf51::TWI0>,
+    pub twi1: Twi<'b, nrf51::TWI1>,
     pub uart: Uart<'b>,
+    pub wdt: Wdt<'b>,
 }
 
 impl<'b> EmbrioNrf51<'b> {
     pub fn new(nrf51: &'b mut nrf51::Peripherals) -> EmbrioNrf51<'b> {
+        let adc = Adc::new(&mut nrf51.ADC);
+        // Safety: CLOCK is exclusively borrowed for 'b through `nrf51`
+        let clock = unsafe { Clock::new() };
+        let flash = Flash::new(&mut nrf51.NVMC);
+        let gpiote = gpiote::Channels::new(&mut nrf51.GPIOTE);
         let pins = Pins::new(&mut nrf51.GPIO);
+        let power = Power::new(&mut nrf51.POWER);
+        let ppi = ppi::Channels::new(&mut nrf51.PPI);
+        let radio = Radio::new(&mut nrf51.RADIO);
+        let rng = Rng::new(&mut nrf51.RNG);
+        let rtc0 = Rtc::new(&mut nrf51.RTC0);
+        let rtc1 = Rtc::new(&mut nrf51.RTC1);
+        let spi0 = Spi::new(&mut nrf51.SPI0);
+        let spi1 = Spi::new(&mut nrf51.SPI1);
+        let temp = Temp::new(&mut nrf51.TEMP);
+        let twi0 = Twi::new(&mut nrf51.TWI0);
+        let twi1 = Twi::new(&mut nrf51.TWI1);
         let uart = Uart::new(&mut nrf51.UART0);
-
-        EmbrioNrf51 { pins, uart }
+        let wdt = Wdt::new(&mut nrf51.WDT);
+
+        EmbrioNrf51 {
+            adc,
+            clock,
+            flash,
+            gpiote,
+            pins,
+            power,
+            ppi,
+            radio,
+            rng,
+            rtc0,
+            rtc1,
+            spi0,
+            spi1,
+            temp,
+            twi0,
+            twi1,
+            uart,
+            wdt,
+        }
     }
 
     pub fn take() -> Option<EmbrioNrf51<'static>> {
@@ -84,11 +171,58 @@ impl<'b> EmbrioNrf51<'b> {
     }
 }
 
+#[interrupt]
+fn ADC() {
+    adc::Adc::interrupt()
+}
+
+#[interrupt]
+fn GPIOTE() {
+    gpio::interrupt()
+}
+
+#[interrupt]
+fn RADIO() {
+    radio::Radio::interrupt()
+}
+
+#[interrupt]
+fn RNG() {
+    rng::Rng::interrupt()
+}
+
+#[interrupt]
+fn RTC0() {
+    rtc::Rtc::<nrf51::RTC0>::interrupt()
+}
+
+#[interrupt]
+fn RTC1() {
+    rtc::Rtc::<nrf51::RTC1>::interrupt()
+}
+
+#[interrupt]
+fn SPI0_TWI0() {
+    spi::Spi::<nrf51::SPI0>::interrupt();
+    twi::Twi::<nrf51::TWI0>::interrupt();
+}
+
+#[interrupt]
+fn SPI1_TWI1() {
+    spi::Spi::<nrf51::SPI1>::interrupt();
+    twi::Twi::<nrf51::TWI1>::interrupt();
+}
+
 #[interrupt]
 fn UART0() {
     uart::Uart::interrupt()
 }
 
+#[interrupt]
+fn TEMP() {
+    temp::Temp::interrupt()
+}
+
 #[interrupt]
 fn TIMER0() {
     timer::Timer::<nrf51::TIMER0>::interrupt()
@@ -98,3 +232,8 @@ fn TIMER0() {
 fn TIMER1() {
     timer::Timer::<nrf51::TIMER1>::interrupt()
 }
+
+#[interrupt]
+fn TIMER2() {
+    timer::Timer::<nrf51::TIMER2>::interrupt()
+}
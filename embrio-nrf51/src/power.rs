@@ -0,0 +1,102 @@
+use core::marker::PhantomData;
+
+use nrf51::POWER;
+
+use crate::gpio::{
+    self,
+    mode::{Input, InputMode},
+};
+
+#[derive(Debug)]
+pub struct Power<'b> {
+    _marker: PhantomData<&'b mut POWER>,
+}
+
+/// The causes of resets since the reasons were last cleared, if none are set
+/// it was a power on reset.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ResetReason(u32);
+
+impl ResetReason {
+    pub fn power_on(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn pin(self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+
+    pub fn watchdog(self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+
+    /// A reset requested by software through the AIRCR.
+    pub fn soft(self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+
+    pub fn lockup(self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+
+    /// Woken from System OFF by a pin.
+    pub fn wake_from_off(self) -> bool {
+        self.0 & (1 << 16) != 0
+    }
+
+    /// Woken from System OFF by the LPCOMP.
+    pub fn lpcomp(self) -> bool {
+        self.0 & (1 << 17) != 0
+    }
+
+    /// Woken from System OFF by the debug interface.
+    pub fn debug_interface(self) -> bool {
+        self.0 & (1 << 18) != 0
+    }
+}
+
+impl<'b> Power<'b> {
+    pub(crate) fn new(_power: &'b mut POWER) -> Self {
+        Power {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reasons accumulate over resets until cleared.
+    pub fn reset_reason(&self) -> ResetReason {
+        // Safety: `self` exclusively borrows the POWER peripheral
+        let power = unsafe { &*POWER::ptr() };
+        ResetReason(power.resetreas.read().bits())
+    }
+
+    pub fn clear_reset_reason(&mut self) {
+        let power = unsafe { &*POWER::ptr() };
+        let bits = power.resetreas.read().bits();
+        // Flags are cleared by writing them back
+        power.resetreas.write(|w| unsafe { w.bits(bits) });
+    }
+
+    /// Wake from System OFF once `pin` reaches `level`, it must stay in this
+    /// mode until then.
+    ///
+    /// A pin already at `level` wakes the device immediately.
+    pub fn wake_on<Mode: InputMode>(
+        &mut self,
+        pin: &gpio::AnyPin<'_, Input<Mode>>,
+        level: bool,
+    ) {
+        gpio::arm_sense(pin.get_id(), level);
+    }
+
+    /// Enter System OFF, drawing under 1µA until woken by a pin set with
+    /// [`wake_on`](Power::wake_on) which resets the device. RAM is not
+    /// retained.
+    pub fn system_off(self) -> ! {
+        let power = unsafe { &*POWER::ptr() };
+        power.systemoff.write(|w| w.systemoff().enter());
+        // While debugging System OFF is emulated and execution continues
+        loop {
+            cortex_m::asm::wfe();
+        }
+    }
+}
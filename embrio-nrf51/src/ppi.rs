@@ -0,0 +1,104 @@
+use core::marker::PhantomData;
+
+use nrf51::PPI;
+
+/// Number of programmable channels, the remaining channels are fixed by
+/// hardware.
+pub const CHANNELS: usize = 16;
+
+/// A peripheral event that can trigger a [`Task`] through a [`Channel`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Event(u32);
+
+/// A peripheral task that can be triggered by an [`Event`] through a
+/// [`Channel`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Task(u32);
+
+/// Triggers a task whenever an event occurs, without involving the CPU.
+#[derive(Debug)]
+pub struct Channel<'a> {
+    index: usize,
+    _marker: PhantomData<&'a PPI>,
+}
+
+macro_rules! channel {
+    ($i:expr) => {
+        Channel<'a>
+    }
+}
+
+macro_rules! channels {
+    ($($i:expr),*) => {
+        #[derive(Debug)]
+        pub struct Channels<'a>($(pub channel!($i)),*);
+
+        impl<'a> Channels<'a> {
+            pub(crate) fn new(ppi: &'a mut PPI) -> Self {
+                Channels($(Channel::new(&*ppi, $i)),*)
+            }
+        }
+    }
+}
+
+channels! {
+     0,  1,  2,  3,  4,  5,  6,  7,
+     8,  9, 10, 11, 12, 13, 14, 15
+}
+
+// Safety: each channel only writes its own registers and bit in the atomic
+// CHENSET/CHENCLR registers
+fn ppi() -> &'static nrf51::ppi::RegisterBlock {
+    unsafe { &*PPI::ptr() }
+}
+
+impl Event {
+    /// # Safety
+    ///
+    /// `register` must be one of a peripheral's `EVENTS_*` registers.
+    pub unsafe fn from_register<R>(register: &R) -> Self {
+        Event(register as *const R as u32)
+    }
+}
+
+impl Task {
+    /// # Safety
+    ///
+    /// `register` must be one of a peripheral's `TASKS_*` registers.
+    pub unsafe fn from_register<R>(register: &R) -> Self {
+        Task(register as *const R as u32)
+    }
+}
+
+impl<'a> Channel<'a> {
+    fn new(_ppi: &'a PPI, index: usize) -> Self {
+        Channel {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Replaces any previous connection, leaving the channel disabled.
+    pub fn connect(&mut self, event: Event, task: Task) {
+        self.disable();
+        let channel = &ppi().ch[self.index];
+        channel.eep.write(|w| unsafe { w.bits(event.0) });
+        channel.tep.write(|w| unsafe { w.bits(task.0) });
+    }
+
+    pub fn enable(&mut self) {
+        ppi().chenset.write(|w| unsafe { w.bits(1 << self.index) });
+    }
+
+    pub fn disable(&mut self) {
+        ppi().chenclr.write(|w| unsafe { w.bits(1 << self.index) });
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        ppi().chen.read().bits() & (1 << self.index) != 0
+    }
+}
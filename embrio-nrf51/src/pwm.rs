@@ -0,0 +1,198 @@
+use core::time::Duration;
+
+use crate::{
+    gpio::{
+        self,
+        mode::{self, PushPull},
+    },
+    gpiote::{self, Polarity},
+    ppi,
+    timer::{Timer, TimerInstance},
+};
+
+/// Maximum number of channels, the last compare register ends each period.
+pub const CHANNELS: usize = 3;
+
+const PERIOD: usize = 3;
+
+// 32bits @ 16MHz == max period of ~4 minutes 28 seconds
+const FREQUENCY: u128 = 16_000_000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    PeriodOutOfRange,
+}
+
+/// The resources for one channel, the pin toggles through the GPIOTE task,
+/// triggered via the PPI channels by the timer reaching its duty cycle and
+/// the end of the period, without involving the CPU.
+#[derive(Debug)]
+pub struct Output<'b> {
+    pub pin: gpio::AnyPin<'b, mode::Output<PushPull>>,
+    pub gpiote: gpiote::Channel<'b>,
+    pub ppi: [ppi::Channel<'b>; 2],
+}
+
+/// Channel `n` is driven by `outputs[n]` and the timer's compare register
+/// `n`.
+pub struct Channels<'a, 'b: 'a, T: TimerInstance> {
+    timer: &'a mut Timer<T>,
+    outputs: &'a mut [Output<'b>],
+    period: u32,
+    duty: [u32; CHANNELS],
+    enabled: [bool; CHANNELS],
+}
+
+fn period_ticks(period: Duration) -> Result<u32, Error> {
+    let ticks = period.as_nanos() * FREQUENCY / 1_000_000_000;
+    if ticks < 2 || ticks > u32::max_value().into() {
+        return Err(Error::PeriodOutOfRange);
+    }
+    Ok(ticks as u32)
+}
+
+impl<'a, 'b: 'a, T: TimerInstance> Channels<'a, 'b, T> {
+    /// Start a PWM with up to [`CHANNELS`] outputs, all initially disabled.
+    pub fn new(
+        timer: &'a mut Timer<T>,
+        outputs: &'a mut [Output<'b>],
+        period: Duration,
+    ) -> Result<Self, Error> {
+        assert!(outputs.len() <= CHANNELS);
+
+        let period = period_ticks(period)?;
+
+        {
+            let timer = timer.registers();
+            timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+            timer.intenclr.write(|w| unsafe { w.bits(!0) });
+            timer.bitmode.write(|w| w.bitmode()._32bit());
+            timer.prescaler.write(|w| unsafe { w.prescaler().bits(0) });
+            timer.shorts.write(|w| w.compare3_clear().enabled());
+
+            // Safety: these are all event registers
+            let end = unsafe {
+                ppi::Event::from_register(&timer.events_compare[PERIOD])
+            };
+            for (channel, output) in outputs.iter_mut().enumerate() {
+                let duty = unsafe {
+                    ppi::Event::from_register(&timer.events_compare[channel])
+                };
+                let toggle = output.gpiote.out_task();
+                output.ppi[0].connect(duty, toggle);
+                output.ppi[1].connect(end, toggle);
+            }
+        }
+
+        let mut channels = Channels {
+            timer,
+            outputs,
+            period,
+            duty: [0; CHANNELS],
+            enabled: [false; CHANNELS],
+        };
+        channels.restart();
+        Ok(channels)
+    }
+
+    // Toggling only stays in sync if every output starts the period in the
+    // same state, so any change restarts the period from the beginning.
+    fn restart(&mut self) {
+        let timer = self.timer.registers();
+
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        timer.cc[PERIOD].write(|w| unsafe { w.bits(self.period) });
+
+        for (channel, output) in self.outputs.iter_mut().enumerate() {
+            let duty = self.duty[channel];
+            let connected =
+                self.enabled[channel] && duty > 0 && duty < self.period;
+            for ppi in &mut output.ppi {
+                if connected {
+                    ppi.enable();
+                } else {
+                    ppi.disable();
+                }
+            }
+            if !self.enabled[channel] {
+                output.gpiote.reset();
+                continue;
+            }
+            // Writing the config drives the pin to its initial level
+            output.gpiote.configure_task(
+                output.pin.get_id(),
+                Polarity::Toggle,
+                duty > 0,
+            );
+            if connected {
+                timer.cc[channel].write(|w| unsafe { w.bits(duty) });
+            }
+        }
+
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+    }
+}
+
+impl<'a, 'b: 'a, T: TimerInstance> embrio_core::pwm::Pwm
+    for Channels<'a, 'b, T>
+{
+    type Channel = usize;
+
+    type Error = Error;
+
+    fn period(&self) -> Duration {
+        let nanos = u128::from(self.period) * 1_000_000_000 / FREQUENCY;
+        Duration::from_nanos(nanos as u64)
+    }
+
+    fn set_period(&mut self, period: Duration) -> Result<(), Error> {
+        let period = period_ticks(period)?;
+        for duty in &mut self.duty {
+            *duty = (u64::from(*duty) * u64::from(period)
+                / u64::from(self.period)) as u32;
+        }
+        self.period = period;
+        self.restart();
+        Ok(())
+    }
+
+    fn max_duty(&self) -> u32 {
+        self.period
+    }
+
+    fn duty(&self, channel: usize) -> u32 {
+        self.duty[channel]
+    }
+
+    fn set_duty(&mut self, channel: usize, duty: u32) {
+        assert!(channel < self.outputs.len());
+        self.duty[channel] = duty.min(self.period);
+        self.restart();
+    }
+
+    fn enable(&mut self, channel: usize) {
+        assert!(channel < self.outputs.len());
+        self.enabled[channel] = true;
+        self.restart();
+    }
+
+    fn disable(&mut self, channel: usize) {
+        assert!(channel < self.outputs.len());
+        self.enabled[channel] = false;
+        self.restart();
+    }
+}
+
+impl<'a, 'b: 'a, T: TimerInstance> Drop for Channels<'a, 'b, T> {
+    fn drop(&mut self) {
+        for output in self.outputs.iter_mut() {
+            for ppi in &mut output.ppi {
+                ppi.disable();
+            }
+            output.gpiote.reset();
+        }
+
+        self.timer.restore();
+    }
+}
@@ -0,0 +1,92 @@
+use core::{
+    convert::Infallible,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use embrio_ble::{
+    link::{Channel, ADVERTISING_ACCESS_ADDRESS, ADVERTISING_CRC_INIT},
+    pdu, radio,
+};
+
+use super::{cancel, poll_operation, Config, Direction, Format, Transceiver};
+
+/// The advertising channels only, the radio calculates the CRC and whitens
+/// packets itself.
+impl<'a, 'b: 'a> radio::Radio for Transceiver<'a, 'b> {
+    type Error = Infallible;
+
+    fn set_channel(self: Pin<&mut Self>, channel: Channel) {
+        assert!(channel.is_advertising());
+        self.get_mut().reconfigure(&Config::ble(
+            ADVERTISING_ACCESS_ADDRESS,
+            ADVERTISING_CRC_INIT,
+            channel.frequency(),
+            channel.whitening_iv(),
+        ));
+    }
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        pdu: &[u8],
+    ) -> Poll<Result<(), Infallible>> {
+        let this = self.get_mut();
+        debug_assert_eq!(this.format, Format::BLE);
+        match poll_operation(
+            cx,
+            &mut this.started,
+            Direction::Tx,
+            |buffer| buffer[..pdu.len()].copy_from_slice(pdu),
+            |_, _| (),
+        ) {
+            Poll::Ready(_) => {
+                this.started = false;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_recv(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Infallible>> {
+        let this = self.get_mut();
+        debug_assert_eq!(this.format, Format::BLE);
+        assert!(buf.len() >= pdu::MAX_LEN);
+        loop {
+            let crc_ok = match poll_operation(
+                cx,
+                &mut this.started,
+                Direction::Rx,
+                |_| (),
+                |crc_ok, buffer| {
+                    buf[..pdu::MAX_LEN]
+                        .copy_from_slice(&buffer[..pdu::MAX_LEN]);
+                    crc_ok
+                },
+            ) {
+                Poll::Ready(crc_ok) => crc_ok,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.started = false;
+            // Corrupted packets are dropped and the radio restarted
+            if crc_ok {
+                if let Ok(packet) = Format::BLE.decode(buf) {
+                    let len = Format::BLE.header_len() + packet.payload.len();
+                    return Poll::Ready(Ok(len));
+                }
+            }
+        }
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let this = self.get_mut();
+        if this.started {
+            cancel();
+            this.started = false;
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use core::{
+    cell::RefCell,
+    cmp,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use nrf51::{Interrupt, RADIO};
+
+use crate::clock::Hfclk;
+
+pub use embrio_nrf_common::radio::{Error, Format, Packet};
+
+#[cfg(feature = "ble")]
+mod ble;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+    Nrf1Mbit,
+    Nrf2Mbit,
+    Nrf250Kbit,
+    Ble1Mbit,
+}
+
+/// An on-air address of `base_len` bytes of `base`, starting from its most
+/// significant byte, followed by `prefix`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Address {
+    pub base: u32,
+    pub prefix: u8,
+    /// Between 2 and 4 bytes.
+    pub base_len: u8,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Crc {
+    /// Between 1 and 3 bytes.
+    pub len: u8,
+    pub poly: u32,
+    pub init: u32,
+    /// Whether the address is left out of the CRC calculation.
+    pub skip_address: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Config {
+    pub mode: Mode,
+    /// The channel in MHz above 2400MHz, up to 100.
+    pub frequency: u8,
+    pub address: Address,
+    pub crc: Option<Crc>,
+    /// The initial value for data whitening, or `None` to disable it.
+    pub whitening: Option<u8>,
+    pub format: Format,
+}
+
+#[derive(Debug)]
+pub struct Radio<'b> {
+    _marker: PhantomData<(&'b mut RADIO, &'b mut NVIC)>,
+}
+
+/// Sends and receives packets in the configured [`Format`], the HFCLK runs
+/// from the crystal for as long as this exists.
+#[derive(Debug)]
+pub struct Transceiver<'a, 'b: 'a> {
+    format: Format,
+    /// Whether an operation through `embrio_ble::radio::Radio` is started.
+    #[cfg(feature = "ble")]
+    started: bool,
+    _hfclk: Hfclk,
+    _marker: PhantomData<&'a mut Radio<'b>>,
+}
+
+#[derive(Debug)]
+pub struct Send<'t, 'c> {
+    packet: &'c [u8],
+    /// Why the packet can't be sent, reported on the first poll.
+    error: Option<Error>,
+    started: bool,
+    _marker: PhantomData<&'t mut ()>,
+}
+
+#[derive(Debug)]
+pub struct Recv<'t, 'c> {
+    format: Format,
+    buf: Option<&'c mut [u8]>,
+    started: bool,
+    _marker: PhantomData<&'t mut ()>,
+}
+
+/// The longest packet in any [`Format`], an `S0`, length and `S1` byte then
+/// a 255 byte payload.
+const BUFFER_LEN: usize = 3 + 255;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Direction {
+    Tx,
+    Rx,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    Idle,
+    Busy,
+    Done { crc_ok: bool },
+}
+
+struct Context {
+    radio: &'static RADIO,
+    waker: Option<Waker>,
+    state: State,
+    /// PACKETPTR always points here rather than at a caller's buffer, which
+    /// could be freed while the radio still uses it if the future is leaked.
+    buffer: [u8; BUFFER_LEN],
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl Config {
+    /// The nRF proprietary 1Mbit mode with a 16-bit CRC.
+    pub fn nrf(address: Address) -> Self {
+        Config {
+            mode: Mode::Nrf1Mbit,
+            frequency: 7,
+            address,
+            crc: Some(Crc {
+                len: 2,
+                poly: 0x1_1021,
+                init: 0xFFFF,
+                skip_address: false,
+            }),
+            whitening: None,
+            format: Format::NRF,
+        }
+    }
+
+    /// BLE 1Mbit, the whitening value is the channel index with bit 6 set.
+    pub fn ble(
+        access_address: u32,
+        crc_init: u32,
+        frequency: u8,
+        whitening: u8,
+    ) -> Self {
+        Config {
+            mode: Mode::Ble1Mbit,
+            frequency,
+            address: Address {
+                base: access_address << 8,
+                prefix: (access_address >> 24) as u8,
+                base_len: 3,
+            },
+            crc: Some(Crc {
+                len: 3,
+                poly: 0x65B,
+                init: crc_init,
+                skip_address: true,
+            }),
+            whitening: Some(whitening),
+            format: Format::BLE,
+        }
+    }
+}
+
+/// Stop any operation in progress, waiting until the radio can no longer
+/// access the packet buffer.
+fn disable(radio: &RADIO) {
+    radio.events_disabled.reset();
+    radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+    while radio.events_disabled.read().bits() == 0 {}
+    radio.events_disabled.reset();
+}
+
+fn configure(radio: &RADIO, config: &Config) {
+    assert!(config.frequency <= 100);
+    assert!(2 <= config.address.base_len && config.address.base_len <= 4);
+
+    disable(radio);
+
+    radio.mode.write(|w| match config.mode {
+        Mode::Nrf1Mbit => w.mode().nrf_1mbit(),
+        Mode::Nrf2Mbit => w.mode().nrf_2mbit(),
+        Mode::Nrf250Kbit => w.mode().nrf_250kbit(),
+        Mode::Ble1Mbit => w.mode().ble_1mbit(),
+    });
+    radio
+        .frequency
+        .write(|w| unsafe { w.frequency().bits(config.frequency) });
+
+    let format = &config.format;
+    radio.pcnf0.write(|w| unsafe { w.bits(format.pcnf0()) });
+    radio.pcnf1.write(|w| unsafe {
+        w.bits(
+            format
+                .pcnf1(config.address.base_len - 1, config.whitening.is_some()),
+        )
+    });
+    if let Some(iv) = config.whitening {
+        radio
+            .datawhiteiv
+            .write(|w| unsafe { w.datawhiteiv().bits(iv) });
+    }
+
+    radio
+        .base0
+        .write(|w| unsafe { w.bits(config.address.base) });
+    radio
+        .prefix0
+        .write(|w| unsafe { w.ap0().bits(config.address.prefix) });
+    radio.txaddress.write(|w| unsafe { w.txaddress().bits(0) });
+    radio.rxaddresses.write(|w| w.addr0().enabled());
+
+    match config.crc {
+        Some(crc) => {
+            assert!(1 <= crc.len && crc.len <= 3);
+            let skip = (crc.skip_address as u32) << 8;
+            radio
+                .crccnf
+                .write(|w| unsafe { w.bits(u32::from(crc.len) | skip) });
+            radio
+                .crcpoly
+                .write(|w| unsafe { w.crcpoly().bits(crc.poly) });
+            radio
+                .crcinit
+                .write(|w| unsafe { w.crcinit().bits(crc.init) });
+        }
+        None => radio.crccnf.write(|w| unsafe { w.bits(0) }),
+    }
+
+    radio
+        .shorts
+        .write(|w| w.ready_start().enabled().end_disable().enabled());
+}
+
+/// Starts the radio on the packet buffer once `prepare` has filled it, then
+/// resolves with `finish` given whether the received CRC was valid and the
+/// packet buffer.
+fn poll_operation<R>(
+    cx: &mut task::Context<'_>,
+    started: &mut bool,
+    direction: Direction,
+    prepare: impl FnOnce(&mut [u8]),
+    finish: impl FnOnce(bool, &[u8]) -> R,
+) -> Poll<R> {
+    free(|c| {
+        let mut context = CONTEXT.borrow(c).borrow_mut();
+        let context = context.as_mut().unwrap();
+        if !*started {
+            *started = true;
+            disable(context.radio);
+            prepare(&mut context.buffer);
+            context.state = State::Busy;
+            let packet = context.buffer.as_ptr() as u32;
+            let radio = context.radio;
+            radio.packetptr.write(|w| unsafe { w.bits(packet) });
+            match direction {
+                Direction::Tx => {
+                    radio.tasks_txen.write(|w| unsafe { w.bits(1) })
+                }
+                Direction::Rx => {
+                    radio.tasks_rxen.write(|w| unsafe { w.bits(1) })
+                }
+            }
+        }
+        match context.state {
+            State::Done { crc_ok } => {
+                context.state = State::Idle;
+                Poll::Ready(finish(crc_ok, &context.buffer))
+            }
+            _ => {
+                context.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    })
+}
+
+fn cancel() {
+    free(|c| {
+        let mut context = CONTEXT.borrow(c).borrow_mut();
+        let context = context.as_mut().unwrap();
+        if context.state == State::Busy {
+            disable(context.radio);
+            context.radio.events_end.reset();
+        }
+        context.state = State::Idle;
+        context.waker = None;
+    })
+}
+
+impl<'b> Radio<'b> {
+    pub(crate) fn new(radio: &'b mut RADIO) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                radio: unsafe { erase_lifetime(radio) },
+                waker: None,
+                state: State::Idle,
+                buffer: [0; BUFFER_LEN],
+            });
+        });
+
+        Radio {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn init<'a>(&'a mut self, config: &Config) -> Transceiver<'a, 'b>
+    where
+        'b: 'a,
+    {
+        // The radio needs the accuracy of the crystal oscillator
+        let hfclk = Hfclk::request();
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            configure(context.radio, config);
+            context.radio.events_end.reset();
+            context.radio.intenset.write(|w| w.end().set());
+            context.state = State::Idle;
+
+            unsafe { NVIC::unmask(Interrupt::RADIO) };
+        });
+
+        Transceiver {
+            format: config.format,
+            #[cfg(feature = "ble")]
+            started: false,
+            _hfclk: hfclk,
+            _marker: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            if context.radio.events_end.read().bits() == 1 {
+                context.radio.events_end.reset();
+                let crc_ok = context.radio.crcstatus.read().bits() == 1;
+                context.state = State::Done { crc_ok };
+                if let Some(waker) = context.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Radio<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            // A leaked `Transceiver` may have left it running on the buffer
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+            disable(context.radio);
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Transceiver<'a, 'b> {
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Switch to another channel, address or mode, for example to hop between
+    /// BLE advertising channels.
+    pub fn reconfigure(&mut self, config: &Config) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            configure(context.radio, config);
+        });
+        self.format = config.format;
+    }
+
+    /// Send a packet encoded with [`Format::encode`], it is copied into the
+    /// driver's packet buffer when the send starts.
+    ///
+    /// Fails without sending if the packet is not valid in the format.
+    pub fn send<'t, 'c>(&'t mut self, packet: &'c [u8]) -> Send<'t, 'c> {
+        Send {
+            packet,
+            error: self.format.decode(packet).err(),
+            started: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Resolves once a packet with a matching address has been received into
+    /// `buf`, which must be at least [`Format::buffer_len`] long.
+    pub fn recv<'t, 'c>(&'t mut self, buf: &'c mut [u8]) -> Recv<'t, 'c> {
+        assert!(buf.len() >= self.format.buffer_len());
+        Recv {
+            format: self.format,
+            buf: Some(buf),
+            started: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b: 'a> Drop for Transceiver<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            context.radio.intenclr.write(|w| w.end().clear());
+            disable(context.radio);
+            context.radio.shorts.reset();
+        });
+    }
+}
+
+impl<'t, 'c> Future for Send<'t, 'c> {
+    type Output = Result<(), Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Err(error));
+        }
+        let packet = this.packet;
+        poll_operation(
+            cx,
+            &mut this.started,
+            Direction::Tx,
+            |buffer| {
+                let len = cmp::min(packet.len(), BUFFER_LEN);
+                buffer[..len].copy_from_slice(&packet[..len]);
+            },
+            |_, _| Ok(()),
+        )
+    }
+}
+
+impl<'t, 'c> Drop for Send<'t, 'c> {
+    fn drop(&mut self) {
+        cancel();
+    }
+}
+
+impl<'t, 'c> Future for Recv<'t, 'c> {
+    type Output = Result<Packet<'c>, Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let len = this.format.buffer_len();
+        let buf = this.buf.as_mut().unwrap();
+        let crc_ok = match poll_operation(
+            cx,
+            &mut this.started,
+            Direction::Rx,
+            |_| (),
+            |crc_ok, buffer| {
+                buf[..len].copy_from_slice(&buffer[..len]);
+                crc_ok
+            },
+        ) {
+            Poll::Ready(crc_ok) => crc_ok,
+            Poll::Pending => return Poll::Pending,
+        };
+        let buf = this.buf.take().unwrap();
+        if !crc_ok {
+            return Poll::Ready(Err(Error::Crc));
+        }
+        Poll::Ready(this.format.decode(buf))
+    }
+}
+
+impl<'t, 'c> Drop for Recv<'t, 'c> {
+    fn drop(&mut self) {
+        cancel();
+    }
+}
@@ -0,0 +1,173 @@
+use core::{
+    cell::RefCell,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use futures_util::task::noop_waker_ref;
+use nrf51::{Interrupt, RNG};
+
+#[derive(Debug)]
+pub struct Rng<'b> {
+    _marker: PhantomData<(&'b mut RNG, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct FillBytes<'a, 'b: 'a> {
+    rng: &'a mut Rng<'b>,
+    buf: &'a mut [u8],
+    filled: usize,
+}
+
+struct Context {
+    rng: &'static RNG,
+    waker: Option<Waker>,
+    value: Option<u8>,
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl<'b> Rng<'b> {
+    pub(crate) fn new(rng: &'b mut RNG) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                rng: unsafe { erase_lifetime(rng) },
+                waker: None,
+                value: None,
+            });
+            let context = context.as_mut().unwrap();
+            context.rng.intenset.write(|w| w.valrdy().set());
+
+            unsafe { NVIC::unmask(Interrupt::RNG) };
+        });
+
+        Rng {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Bias correction gives a uniform distribution of bits, at the cost of
+    /// generating values more slowly. It is disabled by default.
+    pub fn set_bias_correction(&mut self, enabled: bool) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.rng.config.write(|w| w.dercen().bit(enabled));
+        });
+    }
+
+    pub fn fill_bytes<'a>(&'a mut self, buf: &'a mut [u8]) -> FillBytes<'a, 'b>
+    where
+        'b: 'a,
+    {
+        FillBytes {
+            rng: self,
+            buf,
+            filled: 0,
+        }
+    }
+
+    fn poll_byte(&mut self, cx: &mut task::Context<'_>) -> Poll<u8> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if let Some(value) = context.value.take() {
+                return Poll::Ready(value);
+            }
+            context.waker = Some(cx.waker().clone());
+            context.rng.tasks_start.write(|w| unsafe { w.bits(1) });
+            Poll::Pending
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            if context.rng.events_valrdy.read().bits() == 1 {
+                context.rng.events_valrdy.reset();
+                // Only generate values while someone is waiting on them
+                context.rng.tasks_stop.write(|w| unsafe { w.bits(1) });
+                context.value = Some(context.rng.value.read().value().bits());
+                if let Some(waker) = context.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Rng<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+
+            context.rng.tasks_stop.write(|w| unsafe { w.bits(1) });
+            context.rng.intenclr.write(|w| w.valrdy().clear());
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Future for FillBytes<'a, 'b> {
+    type Output = ();
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = &mut *self;
+        while this.filled < this.buf.len() {
+            match this.rng.poll_byte(cx) {
+                Poll::Ready(value) => {
+                    this.buf[this.filled] = value;
+                    this.filled += 1;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(())
+    }
+}
+
+/// Blocks until enough values have been generated, prefer
+/// [`fill_bytes`](Rng::fill_bytes) from async code.
+impl<'b> rand_core::RngCore for Rng<'b> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut cx = task::Context::from_waker(noop_waker_ref());
+        let mut future = Rng::fill_bytes(self, dest);
+        while Pin::new(&mut future).poll(&mut cx).is_pending() {}
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        rand_core::RngCore::fill_bytes(self, dest);
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+use core::{
+    cell::RefCell,
+    cmp,
+    marker::PhantomData,
+    ops::Deref,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use embrio_core::spi::{self, Operation, Phase, Polarity};
+use nrf51::{spi0, Interrupt, SPI0, SPI1};
+
+use crate::gpio::{
+    self,
+    mode::{Floating, Input, Output, PushPull},
+};
+
+pub use nrf51::spi0::frequency::FREQUENCY_A;
+
+#[derive(Debug)]
+pub struct Spi<'b, T: Instance> {
+    _marker: PhantomData<(&'b mut T, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct Master<'a, 'b: 'a, T: Instance> {
+    _marker: PhantomData<(
+        &'a mut Spi<'b, T>,
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    )>,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+pub trait Instance:
+    sealed::Sealed + Deref<Target = spi0::RegisterBlock>
+{
+    #[doc(hidden)]
+    const INDEX: usize;
+    #[doc(hidden)]
+    const INTERRUPT: Interrupt;
+}
+
+impl sealed::Sealed for SPI0 {}
+impl sealed::Sealed for SPI1 {}
+
+impl Instance for SPI0 {
+    const INDEX: usize = 0;
+    const INTERRUPT: Interrupt = Interrupt::SPI0_TWI0;
+}
+
+impl Instance for SPI1 {
+    const INDEX: usize = 1;
+    const INTERRUPT: Interrupt = Interrupt::SPI1_TWI1;
+}
+
+// Value of the ENABLE register when the shared instance is in SPI mode
+const ENABLED: u32 = 1;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    Idle,
+    Busy,
+    /// Busy for an aborted operation, returning to idle once the bytes
+    /// already in flight have been clocked out.
+    Aborting,
+    Done,
+}
+
+// The SPI peripheral is double buffered with a single byte each way, so bytes
+// are moved through this buffer a chunk at a time by the interrupt.
+struct Context {
+    spi: &'static spi0::RegisterBlock,
+    waker: Option<Waker>,
+    state: State,
+    buffer: [u8; 16],
+    len: u8,
+    sent: u8,
+    received: u8,
+}
+
+static CONTEXTS: [Mutex<RefCell<Option<Context>>>; 2] = [
+    Mutex::new(RefCell::new(None)),
+    Mutex::new(RefCell::new(None)),
+];
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl<'b, T: Instance> Spi<'b, T> {
+    pub(crate) fn new(spi: &'b mut T) -> Self {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                spi: unsafe { erase_lifetime(&**spi) },
+                waker: None,
+                state: State::Idle,
+                buffer: [0; 16],
+                len: 0,
+                sent: 0,
+                received: 0,
+            });
+        });
+
+        Spi {
+            _marker: PhantomData,
+        }
+    }
+
+    /// The instance shares its hardware with the TWI master of the same
+    /// number, so only one of them may be initialized at a time.
+    pub fn init<'a>(
+        &'a mut self,
+        sck: &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        mosi: &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        miso: &'a mut gpio::AnyPin<'b, Input<Floating>>,
+        frequency: FREQUENCY_A,
+        mode: spi::Mode,
+    ) -> Master<'a, 'b, T>
+    where
+        'b: 'a,
+    {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            assert!(context.spi.enable.read().bits() == 0);
+
+            context
+                .spi
+                .pselsck
+                .write(|w| unsafe { w.bits(sck.get_id() as u32) });
+            context
+                .spi
+                .pselmosi
+                .write(|w| unsafe { w.bits(mosi.get_id() as u32) });
+            context
+                .spi
+                .pselmiso
+                .write(|w| unsafe { w.bits(miso.get_id() as u32) });
+            context
+                .spi
+                .frequency
+                .write(|w| w.frequency().variant(frequency));
+            context.spi.config.write(|w| {
+                w.order()
+                    .clear_bit()
+                    .cpha()
+                    .bit(mode.phase == Phase::CaptureOnSecondTransition)
+                    .cpol()
+                    .bit(mode.polarity == Polarity::IdleHigh)
+            });
+            context.spi.intenset.write(|w| w.ready().set());
+            context.spi.enable.write(|w| w.enable().enabled());
+
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        });
+
+        Master {
+            _marker: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            // The interrupt is shared with TWI
+            if context.spi.enable.read().bits() != ENABLED {
+                return;
+            }
+            if context.spi.events_ready.read().bits() == 1 {
+                context.spi.events_ready.reset();
+                let byte = context.spi.rxd.read().bits() as u8;
+                context.buffer[context.received as usize] = byte;
+                context.received += 1;
+                if context.sent < context.len {
+                    let byte = context.buffer[context.sent as usize];
+                    context.sent += 1;
+                    context.spi.txd.write(|w| unsafe { w.bits(byte.into()) });
+                }
+                if context.received == context.len {
+                    context.state = match context.state {
+                        State::Aborting => State::Idle,
+                        _ => State::Done,
+                    };
+                    if let Some(waker) = context.waker.take() {
+                        waker.wake();
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl<'b, T: Instance> Drop for Spi<'b, T> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context =
+                CONTEXTS[T::INDEX].borrow(c).borrow_mut().take().unwrap();
+
+            if context.spi.enable.read().bits() == ENABLED {
+                disable(context.spi);
+            }
+        });
+    }
+}
+
+impl<'a, 'b: 'a, T: Instance> Drop for Master<'a, 'b, T> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            disable(context.spi);
+            context.waker = None;
+            context.state = State::Idle;
+        });
+    }
+}
+
+// Release the pins and hardware so the instance can be initialized again
+fn disable(spi: &spi0::RegisterBlock) {
+    spi.enable.write(|w| w.enable().disabled());
+    spi.intenclr.write(|w| w.ready().clear());
+
+    spi.pselsck.reset();
+    spi.pselmosi.reset();
+    spi.pselmiso.reset();
+}
+
+impl<'a, 'b: 'a, T: Instance> Master<'a, 'b, T> {
+    /// Start exchanging a chunk of bytes, or collect the result of the
+    /// previously started chunk once it's done.
+    fn poll_exchange(
+        cx: &mut task::Context<'_>,
+        operation: Operation<'_>,
+    ) -> Poll<Result<usize, !>> {
+        let len = match &operation {
+            Operation::Read(buf) | Operation::Transfer(buf) => buf.len(),
+            Operation::Write(buf) => buf.len(),
+        };
+        if len == 0 {
+            return Poll::Ready(Ok(0));
+        }
+
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            let len = cmp::min(len, context.buffer.len());
+            // A chunk for another request, from an operation dropped without
+            // aborting, is thrown away
+            if context.state == State::Done && context.len as usize != len {
+                context.state = State::Idle;
+            }
+            match context.state {
+                State::Idle => {
+                    let chunk = &mut context.buffer[..len];
+                    match &operation {
+                        Operation::Write(buf) => {
+                            chunk.copy_from_slice(&buf[..len])
+                        }
+                        Operation::Transfer(buf) => {
+                            chunk.copy_from_slice(&buf[..len])
+                        }
+                        Operation::Read(_) => {
+                            chunk.iter_mut().for_each(|byte| *byte = 0)
+                        }
+                    }
+                    context.len = len as u8;
+                    context.received = 0;
+                    context.sent = cmp::min(len, 2) as u8;
+                    context.state = State::Busy;
+                    context.waker = Some(cx.waker().clone());
+                    // Fill both the TXD register and its buffer
+                    for &byte in &context.buffer[..context.sent as usize] {
+                        context
+                            .spi
+                            .txd
+                            .write(|w| unsafe { w.bits(byte.into()) });
+                    }
+                    Poll::Pending
+                }
+                State::Busy | State::Aborting => {
+                    context.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                State::Done => {
+                    match operation {
+                        Operation::Read(buf) | Operation::Transfer(buf) => {
+                            buf[..len].copy_from_slice(&context.buffer[..len])
+                        }
+                        Operation::Write(_) => {}
+                    }
+                    context.state = State::Idle;
+                    Poll::Ready(Ok(len))
+                }
+            }
+        })
+    }
+}
+
+impl<'a, 'b: 'a, T: Instance> spi::Bus for Master<'a, 'b, T> {
+    type Error = !;
+
+    fn poll_transfer(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        Self::poll_exchange(cx, Operation::Transfer(buf))
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        Self::poll_exchange(cx, Operation::Write(buf))
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        Self::poll_exchange(cx, Operation::Read(buf))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            match context.state {
+                State::Busy | State::Aborting => {
+                    context.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                _ => Poll::Ready(Ok(())),
+            }
+        })
+    }
+
+    /// Bytes already written to TXD are still clocked out, but no more are
+    /// queued and the chunk is discarded.
+    fn abort(self: Pin<&mut Self>) {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            match context.state {
+                State::Busy => {
+                    context.len = context.sent;
+                    context.state = State::Aborting;
+                }
+                State::Done => context.state = State::Idle,
+                State::Idle | State::Aborting => {}
+            }
+            context.waker = None;
+        })
+    }
+}
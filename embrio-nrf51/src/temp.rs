@@ -0,0 +1,212 @@
+use core::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use nrf51::{Interrupt, TEMP};
+
+#[derive(Debug)]
+pub struct Temp<'b> {
+    _marker: PhantomData<(&'b mut TEMP, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct Read<'a, 'b: 'a> {
+    _marker: PhantomData<&'a mut Temp<'b>>,
+}
+
+/// A temperature in fixed-point quarter degrees Celsius, the resolution of the
+/// sensor.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Temperature(i32);
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    Idle,
+    Busy,
+    Done(i32),
+}
+
+struct Context {
+    temp: &'static TEMP,
+    waker: Option<Waker>,
+    state: State,
+}
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+/// TEMP holds a 10-bit two's complement value, sign extended from bit 9.
+fn from_raw(raw: u32) -> i32 {
+    let raw = raw & 0x3FF;
+    if raw & 0x200 != 0 {
+        (raw | 0xFFFF_FC00) as i32
+    } else {
+        raw as i32
+    }
+}
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl Temperature {
+    pub fn from_quarter_degrees(quarters: i32) -> Self {
+        Temperature(quarters)
+    }
+
+    pub fn quarter_degrees(self) -> i32 {
+        self.0
+    }
+
+    pub fn millidegrees(self) -> i32 {
+        self.0 * 250
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.abs();
+        write!(f, "{}{}.{:02}", sign, abs / 4, (abs % 4) * 25)
+    }
+}
+
+impl<'b> Temp<'b> {
+    pub(crate) fn new(temp: &'b mut TEMP) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                temp: unsafe { erase_lifetime(temp) },
+                waker: None,
+                state: State::Idle,
+            });
+            let context = context.as_mut().unwrap();
+            context.temp.intenset.write(|w| w.datardy().set());
+
+            unsafe { NVIC::unmask(Interrupt::TEMP) };
+        });
+
+        Temp {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn read<'a>(&'a mut self) -> Read<'a, 'b>
+    where
+        'b: 'a,
+    {
+        Read {
+            _marker: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            if context.temp.events_datardy.read().bits() == 1 {
+                context.temp.events_datardy.reset();
+                // The sensor draws extra current until stopped
+                context.temp.tasks_stop.write(|w| unsafe { w.bits(1) });
+                context.state =
+                    State::Done(from_raw(context.temp.temp.read().bits()));
+                if let Some(waker) = context.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Temp<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+
+            context.temp.tasks_stop.write(|w| unsafe { w.bits(1) });
+            context.temp.intenclr.write(|w| w.datardy().clear());
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Future for Read<'a, 'b> {
+    type Output = Temperature;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            match context.state {
+                State::Idle => {
+                    context.state = State::Busy;
+                    context.waker = Some(cx.waker().clone());
+                    context.temp.tasks_start.write(|w| unsafe { w.bits(1) });
+                    Poll::Pending
+                }
+                State::Busy => {
+                    context.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                State::Done(quarters) => {
+                    context.state = State::Idle;
+                    Poll::Ready(Temperature(quarters))
+                }
+            }
+        })
+    }
+}
+
+/// Stops a measurement still in progress, so the next read starts afresh.
+impl<'a, 'b: 'a> Drop for Read<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if context.state == State::Busy {
+                context.temp.tasks_stop.write(|w| unsafe { w.bits(1) });
+                context.temp.events_datardy.reset();
+            }
+            context.state = State::Idle;
+            context.waker = None;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let temperature = Temperature::from_quarter_degrees(-5);
+        assert_eq!(temperature.quarter_degrees(), -5);
+        assert_eq!(temperature.millidegrees(), -1250);
+        assert_eq!(Temperature::from_quarter_degrees(94).millidegrees(), 23500);
+    }
+
+    #[test]
+    fn negative_reading() {
+        assert_eq!(from_raw(0x3FF), -1);
+        // -5°C
+        assert_eq!(from_raw(0x3EC), -20);
+        assert_eq!(from_raw(0xFFFF_FFEC), -20);
+        assert_eq!(from_raw(0x05E), 94);
+    }
+}
@@ -0,0 +1,81 @@
+use core::cell::RefCell;
+
+use cortex_m::interrupt::Mutex;
+use embrio_nrf_common::timer::{BitMode, Instance, Wakers};
+use nrf51::{timer0, Interrupt, TIMER0, TIMER1, TIMER2};
+
+use crate::Nrf51;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A TIMER peripheral whose registers can also be driven directly, e.g. by
+/// the PWM through PPI.
+pub trait TimerInstance:
+    sealed::Sealed + Instance<Nrf51, Registers = timer0::RegisterBlock>
+{
+}
+
+impl sealed::Sealed for TIMER0 {}
+impl sealed::Sealed for TIMER1 {}
+impl sealed::Sealed for TIMER2 {}
+
+impl TimerInstance for TIMER0 {}
+impl TimerInstance for TIMER1 {}
+impl TimerInstance for TIMER2 {}
+
+// Safety: the registers are only used by the `Timer` owning the instance,
+// and its interrupt
+unsafe impl Instance<Nrf51> for TIMER0 {
+    type Registers = timer0::RegisterBlock;
+
+    const INTERRUPT: Interrupt = Interrupt::TIMER0;
+    // 32bits @ 1MHz == max delay of ~1 hour 11 minutes
+    const MAX_BIT_MODE: BitMode = BitMode::Bits32;
+
+    fn registers() -> &'static timer0::RegisterBlock {
+        unsafe { &*TIMER0::ptr() }
+    }
+
+    fn wakers() -> &'static Wakers {
+        static WAKERS: Wakers =
+            Mutex::new(RefCell::new([None, None, None, None]));
+        &WAKERS
+    }
+}
+
+unsafe impl Instance<Nrf51> for TIMER1 {
+    type Registers = timer0::RegisterBlock;
+
+    const INTERRUPT: Interrupt = Interrupt::TIMER1;
+    // 16bits @ 1MHz == max delay of ~65 milliseconds
+    const MAX_BIT_MODE: BitMode = BitMode::Bits16;
+
+    fn registers() -> &'static timer0::RegisterBlock {
+        unsafe { &*TIMER1::ptr() }
+    }
+
+    fn wakers() -> &'static Wakers {
+        static WAKERS: Wakers =
+            Mutex::new(RefCell::new([None, None, None, None]));
+        &WAKERS
+    }
+}
+
+unsafe impl Instance<Nrf51> for TIMER2 {
+    type Registers = timer0::RegisterBlock;
+
+    const INTERRUPT: Interrupt = Interrupt::TIMER2;
+    const MAX_BIT_MODE: BitMode = BitMode::Bits16;
+
+    fn registers() -> &'static timer0::RegisterBlock {
+        unsafe { &*TIMER2::ptr() }
+    }
+
+    fn wakers() -> &'static Wakers {
+        static WAKERS: Wakers =
+            Mutex::new(RefCell::new([None, None, None, None]));
+        &WAKERS
+    }
+}
@@ -1,7 +1,79 @@
-mod timer0;
-mod timer1;
+use embrio_nrf_common::timer::{self, Registers, Task};
+use nrf51::timer0;
 
-pub struct Timer<T>(T);
+use crate::Nrf51;
 
-pub struct Timeout<'a, T: 'a>(Option<&'a mut Timer<T>>);
-pub struct Interval<'a, T: 'a>(&'a mut Timer<T>);
+mod instance;
+
+pub use self::instance::TimerInstance;
+pub use embrio_nrf_common::timer::{
+    BitMode, Error, Interval, Timeout, CHANNELS,
+};
+
+pub type Builder<T> = timer::Builder<Nrf51, T>;
+
+/// A free running counter with a compare channel per timeout.
+///
+/// The timer itself uses channel 0, use `channels` to run a timeout on each
+/// channel independently. It keeps the HFCLK crystal running for accuracy,
+/// prefer an RTC where that isn't needed.
+pub type Timer<T> = timer::Timer<Nrf51, T>;
+
+pub type Channel<'a, T> = timer::Channel<'a, Nrf51, T>;
+
+impl Registers<Nrf51> for timer0::RegisterBlock {
+    fn trigger(&self, task: Task) {
+        match task {
+            Task::Start => self.tasks_start.write(|w| unsafe { w.bits(1) }),
+            Task::Stop => self.tasks_stop.write(|w| unsafe { w.bits(1) }),
+            Task::Clear => self.tasks_clear.write(|w| unsafe { w.bits(1) }),
+            Task::Capture(index) => {
+                self.tasks_capture[index].write(|w| unsafe { w.bits(1) })
+            }
+        }
+    }
+
+    fn disable_shorts(&self) {
+        self.shorts.reset();
+    }
+
+    fn set_bit_mode(&self, bit_mode: BitMode) {
+        self.bitmode.write(|w| match bit_mode {
+            BitMode::Bits8 => w.bitmode()._08bit(),
+            BitMode::Bits16 => w.bitmode()._16bit(),
+            BitMode::Bits24 => w.bitmode()._24bit(),
+            BitMode::Bits32 => w.bitmode()._32bit(),
+        });
+    }
+
+    fn set_prescaler(&self, prescaler: u8) {
+        self.prescaler
+            .write(|w| unsafe { w.prescaler().bits(prescaler) });
+    }
+
+    fn compare(&self, index: usize) -> u32 {
+        self.cc[index].read().bits()
+    }
+
+    fn set_compare(&self, index: usize, value: u32) {
+        self.cc[index].write(|w| unsafe { w.bits(value) });
+    }
+
+    fn event(&self, index: usize) -> bool {
+        self.events_compare[index].read().bits() == 1
+    }
+
+    fn clear_event(&self, index: usize) {
+        self.events_compare[index].reset();
+    }
+
+    fn enable_interrupt(&self, index: usize) {
+        self.intenset
+            .write(|w| unsafe { w.bits(1 << (16 + index)) });
+    }
+
+    fn disable_interrupt(&self, index: usize) {
+        self.intenclr
+            .write(|w| unsafe { w.bits(1 << (16 + index)) });
+    }
+}
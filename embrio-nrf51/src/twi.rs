@@ -0,0 +1,466 @@
+use core::{
+    cell::RefCell,
+    marker::PhantomData,
+    ops::Deref,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use embrio_core::i2c::{self, Operation};
+use nrf51::{twi0, Interrupt, TWI0, TWI1};
+
+use crate::gpio::{
+    self,
+    mode::{Input, OpenDrain, Output, PullUp},
+};
+
+pub use nrf51::twi0::frequency::FREQUENCY_A;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// A byte was received before the previous one was read
+    Overrun,
+}
+
+#[derive(Debug)]
+pub struct Twi<'b, T: Instance> {
+    _marker: PhantomData<(&'b mut T, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct Master<'a, 'b: 'a, T: Instance> {
+    _marker: PhantomData<(
+        &'a mut Twi<'b, T>,
+        &'a mut gpio::AnyPin<'b, Output<OpenDrain>>,
+        &'a mut gpio::AnyPin<'b, Input<PullUp>>,
+    )>,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+pub trait Instance:
+    sealed::Sealed + Deref<Target = twi0::RegisterBlock>
+{
+    #[doc(hidden)]
+    const INDEX: usize;
+    #[doc(hidden)]
+    const INTERRUPT: Interrupt;
+}
+
+impl sealed::Sealed for TWI0 {}
+impl sealed::Sealed for TWI1 {}
+
+impl Instance for TWI0 {
+    const INDEX: usize = 0;
+    const INTERRUPT: Interrupt = Interrupt::SPI0_TWI0;
+}
+
+impl Instance for TWI1 {
+    const INDEX: usize = 1;
+    const INTERRUPT: Interrupt = Interrupt::SPI1_TWI1;
+}
+
+// Value of the ENABLE register when the shared instance is in TWI mode
+const ENABLED: u32 = 5;
+
+struct Events {
+    stopped: bool,
+    rxdready: bool,
+    txdsent: bool,
+    errorsrc: u32,
+}
+
+#[derive(Copy, Clone)]
+enum State {
+    Idle,
+    // Waiting for the byte before `position` to be sent
+    Writing {
+        index: usize,
+        position: usize,
+    },
+    // Waiting for the byte at `position` to be received
+    Reading {
+        index: usize,
+        position: usize,
+    },
+    Stopping {
+        result: Result<(), i2c::Error<Error>>,
+    },
+}
+
+struct Context {
+    twi: &'static twi0::RegisterBlock,
+    events: Events,
+    waker: Option<Waker>,
+    state: State,
+}
+
+static CONTEXTS: [Mutex<RefCell<Option<Context>>>; 2] = [
+    Mutex::new(RefCell::new(None)),
+    Mutex::new(RefCell::new(None)),
+];
+
+unsafe fn erase_lifetime<'a, T>(t: &'a T) -> &'static T {
+    &*(t as *const T)
+}
+
+impl<'b, T: Instance> Twi<'b, T> {
+    pub(crate) fn new(twi: &'b mut T) -> Self {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                twi: unsafe { erase_lifetime(&**twi) },
+                events: Events {
+                    stopped: false,
+                    rxdready: false,
+                    txdsent: false,
+                    errorsrc: 0,
+                },
+                waker: None,
+                state: State::Idle,
+            });
+        });
+
+        Twi {
+            _marker: PhantomData,
+        }
+    }
+
+    /// The instance shares its hardware with the SPI master of the same
+    /// number, so only one of them may be initialized at a time.
+    pub fn init<'a>(
+        &'a mut self,
+        scl: &'a mut gpio::AnyPin<'b, Output<OpenDrain>>,
+        sda: &'a mut gpio::AnyPin<'b, Input<PullUp>>,
+        frequency: FREQUENCY_A,
+    ) -> Master<'a, 'b, T>
+    where
+        'b: 'a,
+    {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+
+            assert!(context.twi.enable.read().bits() == 0);
+
+            context
+                .twi
+                .pselscl
+                .write(|w| unsafe { w.bits(scl.get_id() as u32) });
+            context
+                .twi
+                .pselsda
+                .write(|w| unsafe { w.bits(sda.get_id() as u32) });
+            context
+                .twi
+                .frequency
+                .write(|w| w.frequency().variant(frequency));
+            context.twi.intenset.write(|w| {
+                w.stopped()
+                    .set()
+                    .rxdready()
+                    .set()
+                    .txdsent()
+                    .set()
+                    .error()
+                    .set()
+            });
+            context.twi.enable.write(|w| w.enable().enabled());
+
+            unsafe { NVIC::unmask(T::INTERRUPT) };
+        });
+
+        Master {
+            _marker: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            // The interrupt is shared with SPI
+            if context.twi.enable.read().bits() != ENABLED {
+                return;
+            }
+            let twi = context.twi;
+            let events = &mut context.events;
+            if twi.events_stopped.read().bits() == 1 {
+                twi.events_stopped.reset();
+                events.stopped = true;
+            }
+            if twi.events_rxdready.read().bits() == 1 {
+                twi.events_rxdready.reset();
+                events.rxdready = true;
+            }
+            if twi.events_txdsent.read().bits() == 1 {
+                twi.events_txdsent.reset();
+                events.txdsent = true;
+            }
+            if twi.events_error.read().bits() == 1 {
+                twi.events_error.reset();
+                let errorsrc = twi.errorsrc.read().bits();
+                // Bits are cleared by writing 1 to them
+                twi.errorsrc.write(|w| unsafe { w.bits(errorsrc) });
+                events.errorsrc |= errorsrc;
+            }
+            if let Some(waker) = context.waker.take() {
+                waker.wake();
+            }
+        });
+    }
+}
+
+impl<'b, T: Instance> Drop for Twi<'b, T> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context =
+                CONTEXTS[T::INDEX].borrow(c).borrow_mut().take().unwrap();
+
+            if context.twi.enable.read().bits() == ENABLED {
+                disable(context.twi);
+            }
+        });
+    }
+}
+
+impl<'a, 'b: 'a, T: Instance> Drop for Master<'a, 'b, T> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            disable(context.twi);
+            context.waker = None;
+            context.state = State::Idle;
+        });
+    }
+}
+
+// Release the pins and hardware so the instance can be initialized again
+fn disable(twi: &twi0::RegisterBlock) {
+    twi.tasks_stop.write(|w| unsafe { w.bits(1) });
+    twi.enable.write(|w| w.enable().disabled());
+    twi.intenclr.write(|w| unsafe { w.bits(!0) });
+    twi.pselscl.reset();
+    twi.pselsda.reset();
+}
+
+fn map_errorsrc(errorsrc: u32) -> i2c::Error<Error> {
+    if errorsrc & 0b010 != 0 {
+        i2c::Error::AddressNack
+    } else if errorsrc & 0b100 != 0 {
+        i2c::Error::DataNack
+    } else {
+        i2c::Error::Other(Error::Overrun)
+    }
+}
+
+fn is_empty(operation: &Operation<'_>) -> bool {
+    match operation {
+        Operation::Read(buf) => buf.is_empty(),
+        Operation::Write(buf) => buf.is_empty(),
+    }
+}
+
+// Whether no bytes are transferred after the operation at `index`
+fn is_last(operations: &[Operation<'_>], index: usize) -> bool {
+    operations.iter().skip(index + 1).all(is_empty)
+}
+
+impl Context {
+    fn start(&mut self, operations: &[Operation<'_>], index: usize) {
+        // Empty operations are skipped without touching the bus
+        let previous = operations[..index].iter().rev().find(|o| !is_empty(o));
+        let next_is_last = is_last(operations, index);
+
+        self.state = match operations.get(index) {
+            // Nothing was transferred so there is no stop to wait for
+            None if previous.is_none() => State::Idle,
+            None => {
+                if let Some(Operation::Write(_)) = previous {
+                    self.twi.tasks_stop.write(|w| unsafe { w.bits(1) });
+                }
+                // A read will already have stopped through the BB_STOP short
+                State::Stopping { result: Ok(()) }
+            }
+            Some(Operation::Write(buf)) => {
+                if buf.is_empty() {
+                    return self.start(operations, index + 1);
+                }
+                self.twi.txd.write(|w| unsafe { w.bits(buf[0].into()) });
+                match previous {
+                    Some(Operation::Write(_)) => {}
+                    _ => self.twi.tasks_starttx.write(|w| unsafe { w.bits(1) }),
+                }
+                State::Writing { index, position: 1 }
+            }
+            Some(Operation::Read(buf)) => {
+                if buf.is_empty() {
+                    return self.start(operations, index + 1);
+                }
+                self.set_read_shorts(buf.len() == 1 && next_is_last);
+                match previous {
+                    Some(Operation::Read(_)) => {
+                        self.twi.tasks_resume.write(|w| unsafe { w.bits(1) })
+                    }
+                    _ => self.twi.tasks_startrx.write(|w| unsafe { w.bits(1) }),
+                }
+                State::Reading { index, position: 0 }
+            }
+        };
+    }
+
+    // Suspend after each received byte so it can be read before the next one
+    // arrives, or stop after the final byte of the transaction
+    fn set_read_shorts(&self, last: bool) {
+        self.twi.shorts.write(|w| {
+            if last {
+                w.bb_suspend().disabled().bb_stop().enabled()
+            } else {
+                w.bb_suspend().enabled().bb_stop().disabled()
+            }
+        });
+    }
+
+    fn poll_transaction(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Poll<Result<(), i2c::Error<Error>>> {
+        loop {
+            if self.events.errorsrc != 0 {
+                if let State::Writing { .. } | State::Reading { .. } =
+                    self.state
+                {
+                    let error = map_errorsrc(self.events.errorsrc);
+                    self.twi.tasks_stop.write(|w| unsafe { w.bits(1) });
+                    self.state = State::Stopping { result: Err(error) };
+                }
+                self.events.errorsrc = 0;
+            }
+
+            match self.state {
+                State::Idle => {
+                    self.events = Events {
+                        stopped: false,
+                        rxdready: false,
+                        txdsent: false,
+                        errorsrc: 0,
+                    };
+                    self.twi.shorts.reset();
+                    self.twi
+                        .address
+                        .write(|w| unsafe { w.bits(address.into()) });
+                    self.start(operations, 0);
+                    if let State::Idle = self.state {
+                        self.waker = None;
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+                State::Writing { index, position } => {
+                    if !self.events.txdsent {
+                        break;
+                    }
+                    self.events.txdsent = false;
+                    match &operations[index] {
+                        Operation::Write(buf) if position < buf.len() => {
+                            let byte = buf[position];
+                            self.twi
+                                .txd
+                                .write(|w| unsafe { w.bits(byte.into()) });
+                            self.state = State::Writing {
+                                index,
+                                position: position + 1,
+                            };
+                        }
+                        _ => self.start(operations, index + 1),
+                    }
+                }
+                State::Reading { index, position } => {
+                    if !self.events.rxdready {
+                        break;
+                    }
+                    self.events.rxdready = false;
+                    let next_is_last = is_last(operations, index);
+                    let byte = self.twi.rxd.read().bits() as u8;
+                    match &mut operations[index] {
+                        Operation::Read(buf) => {
+                            buf[position] = byte;
+                            let position = position + 1;
+                            if position < buf.len() {
+                                let last =
+                                    position + 1 == buf.len() && next_is_last;
+                                self.set_read_shorts(last);
+                                self.twi
+                                    .tasks_resume
+                                    .write(|w| unsafe { w.bits(1) });
+                                self.state = State::Reading { index, position };
+                            } else {
+                                self.start(operations, index + 1);
+                            }
+                        }
+                        Operation::Write(_) => unreachable!(),
+                    }
+                }
+                State::Stopping { result } => {
+                    if !self.events.stopped {
+                        break;
+                    }
+                    self.events.stopped = false;
+                    self.state = State::Idle;
+                    self.twi.shorts.reset();
+                    self.waker = None;
+                    return Poll::Ready(result);
+                }
+            }
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<'a, 'b: 'a, T: Instance> i2c::I2c for Master<'a, 'b, T> {
+    type Error = Error;
+
+    fn poll_transaction(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Poll<Result<(), i2c::Error<Self::Error>>> {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.poll_transaction(cx, address, operations)
+        })
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        free(|c| {
+            let mut context = CONTEXTS[T::INDEX].borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if let State::Idle = context.state {
+                return;
+            }
+            context.twi.shorts.reset();
+            context.twi.tasks_stop.write(|w| unsafe { w.bits(1) });
+            // A suspended read has to be resumed for the stop to happen
+            context.twi.tasks_resume.write(|w| unsafe { w.bits(1) });
+            context.waker = None;
+            context.state = State::Idle;
+        });
+    }
+}
@@ -0,0 +1,479 @@
+use core::{
+    cell::RefCell,
+    cmp,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use embrio_core::io;
+use nrf51::{Interrupt, UART0};
+
+use self::ring::RingBuffer;
+use crate::{
+    clock::Hfclk,
+    gpio::{
+        self,
+        mode::{Floating, Input, Output, PushPull},
+    },
+};
+
+pub use embrio_nrf_common::uart::{Error, Parity};
+pub use nrf51::uart0::baudrate::BAUDRATE_A;
+
+mod ring;
+
+#[derive(Debug)]
+pub struct Uart<'b> {
+    _marker: PhantomData<(&'b mut UART0, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct Tx<'a, 'b: 'a> {
+    _marker: PhantomData<(
+        &'a mut Uart<'b>,
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+    )>,
+}
+
+#[derive(Debug)]
+pub struct Rx<'a, 'b: 'a> {
+    _marker: PhantomData<(
+        &'a mut Uart<'b>,
+        &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    )>,
+}
+
+/// The pins to connect, hardware flow control is enabled when RTS and CTS
+/// pins are given.
+#[derive(Debug)]
+pub struct Pins<'a, 'b: 'a> {
+    pub tx: &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+    pub rx: &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    pub flow_control: Option<(
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    )>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Config {
+    pub baudrate: BAUDRATE_A,
+    pub parity: Parity,
+}
+
+struct Context {
+    uart: &'static mut UART0,
+    /// Taken while initialized, the baud rate is derived from the HFCLK.
+    hfclk: Option<Hfclk>,
+    /// The halves from `init` still alive, the HFCLK is released once both
+    /// are dropped.
+    halves: usize,
+    rx: RingBuffer,
+    rx_error: Option<Error>,
+    rx_waker: Option<Waker>,
+    tx: RingBuffer,
+    tx_busy: bool,
+    tx_waker: Option<Waker>,
+}
+
+// Pin select value leaving a signal unconnected
+const DISCONNECTED: u32 = 0xFFFF_FFFF;
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+unsafe fn erase_lifetime<'a, T>(t: &'a mut T) -> &'static mut T {
+    &mut *(t as *mut T)
+}
+
+impl Context {
+    /// Stop receiving and release the HFCLK once neither half is left to use
+    /// them, a byte still being sent finishes first.
+    fn release_half(&mut self) {
+        self.halves -= 1;
+        if self.halves == 0 {
+            self.uart.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+            self.hfclk = None;
+        }
+    }
+}
+
+impl Default for Config {
+    /// 115200 baud without parity.
+    fn default() -> Self {
+        Config {
+            baudrate: BAUDRATE_A::BAUD115200,
+            parity: Parity::None,
+        }
+    }
+}
+
+/// Applies the baud rate and parity, leaving flow control untouched.
+fn configure(uart: &UART0, config: Config) {
+    uart.baudrate
+        .write(|w| w.baudrate().variant(config.baudrate));
+    uart.config.modify(|_, w| match config.parity {
+        Parity::None => w.parity().excluded(),
+        Parity::Even => w.parity().included(),
+    });
+}
+
+impl<'b> Uart<'b> {
+    pub(crate) fn new(uart: &'b mut UART0) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                uart: unsafe { erase_lifetime(uart) },
+                hfclk: None,
+                halves: 0,
+                rx: RingBuffer::empty(),
+                rx_error: None,
+                rx_waker: None,
+                tx: RingBuffer::empty(),
+                tx_busy: false,
+                tx_waker: None,
+            });
+        });
+
+        Uart {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Received bytes are queued in `rx_buffer` by the interrupt until read,
+    /// and written bytes are queued in `tx_buffer` until sent, so larger
+    /// buffers tolerate longer gaps between polls. They are `'static` as the
+    /// interrupt keeps using them until the driver sees the halves dropped,
+    /// which a leaked half never is.
+    ///
+    /// To move to different pins drop the returned halves and call this again,
+    /// the baud rate and parity can be changed in place with
+    /// [`Tx::reconfigure`].
+    pub fn init<'a>(
+        &'a mut self,
+        pins: Pins<'a, 'b>,
+        config: Config,
+        tx_buffer: &'static mut [u8],
+        rx_buffer: &'static mut [u8],
+    ) -> (Tx<'a, 'b>, Rx<'a, 'b>)
+    where
+        'b: 'a,
+    {
+        let hfclk = Hfclk::request();
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.hfclk = Some(hfclk);
+            context.halves = 2;
+
+            // The pins can only be changed while disabled
+            context.uart.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            context.uart.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+            context.uart.enable.write(|w| w.enable().disabled());
+
+            context.tx = RingBuffer::new(tx_buffer);
+            context.rx = RingBuffer::new(rx_buffer);
+            context.rx_error = None;
+            context.tx_busy = false;
+
+            context
+                .uart
+                .pseltxd
+                .write(|w| unsafe { w.bits(pins.tx.get_id() as u32) });
+            context
+                .uart
+                .pselrxd
+                .write(|w| unsafe { w.bits(pins.rx.get_id() as u32) });
+            match pins.flow_control {
+                Some((rts, cts)) => {
+                    context
+                        .uart
+                        .pselrts
+                        .write(|w| unsafe { w.bits(rts.get_id() as u32) });
+                    context
+                        .uart
+                        .pselcts
+                        .write(|w| unsafe { w.bits(cts.get_id() as u32) });
+                    context.uart.config.write(|w| w.hwfc().enabled());
+                }
+                None => {
+                    context
+                        .uart
+                        .pselrts
+                        .write(|w| unsafe { w.bits(DISCONNECTED) });
+                    context
+                        .uart
+                        .pselcts
+                        .write(|w| unsafe { w.bits(DISCONNECTED) });
+                    context.uart.config.write(|w| w.hwfc().disabled());
+                }
+            }
+            configure(context.uart, config);
+
+            context.uart.events_rxdrdy.reset();
+            context.uart.events_txdrdy.reset();
+            context.uart.events_error.reset();
+            context.uart.errorsrc.write(|w| unsafe { w.bits(0b1111) });
+            context
+                .uart
+                .intenset
+                .write(|w| w.rxdrdy().set().txdrdy().set().error().set());
+            context.uart.enable.write(|w| w.enable().enabled());
+
+            context.uart.tasks_starttx.write(|w| unsafe { w.bits(1) });
+            context.uart.tasks_startrx.write(|w| unsafe { w.bits(1) });
+
+            unsafe { NVIC::unmask(Interrupt::UART0) };
+        });
+
+        (
+            Tx {
+                _marker: PhantomData,
+            },
+            Rx {
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            NVIC::unpend(Interrupt::UART0);
+            if context.uart.events_error.read().bits() == 1 {
+                context.uart.events_error.reset();
+                let bits = context.uart.errorsrc.read().bits();
+                // Flags are cleared by writing them back
+                context.uart.errorsrc.write(|w| unsafe { w.bits(bits) });
+                if let Some(error) = Error::from_errorsrc(bits) {
+                    context.rx_error.get_or_insert(error);
+                }
+                if let Some(waker) = context.rx_waker.take() {
+                    waker.wake();
+                }
+            }
+            if context.uart.events_rxdrdy.read().bits() == 1 {
+                context.uart.events_rxdrdy.reset();
+                let byte = context.uart.rxd.read().bits() as u8;
+                if !context.rx.push(byte) {
+                    context.rx_error.get_or_insert(Error::Overrun);
+                }
+                if let Some(waker) = context.rx_waker.take() {
+                    waker.wake();
+                }
+            }
+            if context.uart.events_txdrdy.read().bits() == 1 {
+                context.uart.events_txdrdy.reset();
+                match context.tx.pop() {
+                    Some(byte) => context
+                        .uart
+                        .txd
+                        .write(|w| unsafe { w.bits(byte.into()) }),
+                    None => context.tx_busy = false,
+                }
+                if let Some(waker) = context.tx_waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Uart<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+
+            NVIC::mask(Interrupt::UART0);
+
+            context.uart.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            context.uart.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+
+            context.uart.enable.write(|w| w.enable().disabled());
+            context
+                .uart
+                .intenclr
+                .write(|w| w.rxdrdy().clear().txdrdy().clear().error().clear());
+
+            context.uart.pseltxd.reset();
+            context.uart.pselrxd.reset();
+            context.uart.pselrts.reset();
+            context.uart.pselcts.reset();
+            context.uart.baudrate.reset();
+            context.uart.config.reset();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Tx<'a, 'b> {
+    /// Change the baud rate and parity of both halves without reconnecting.
+    ///
+    /// Bytes still being sent will be corrupted so flush first, and the other
+    /// end should switch over at the same time.
+    pub fn reconfigure(&mut self, config: Config) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            configure(context.uart, config);
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Drop for Tx<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            // Anything still queued is discarded, the byte in flight finishes
+            context.tx = RingBuffer::empty();
+            context.tx_waker = None;
+            context.release_half();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Drop for Rx<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.rx = RingBuffer::empty();
+            context.rx_error = None;
+            context.rx_waker = None;
+            context.release_half();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> io::Read for Rx<'a, 'b> {
+    type Error = Error;
+
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let amount = match io::BufRead::poll_fill_buf(self.as_mut(), cx) {
+            Poll::Ready(Ok(available)) => {
+                let amount = cmp::min(available.len(), buf.len());
+                buf[..amount].copy_from_slice(&available[..amount]);
+                amount
+            }
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+        io::BufRead::consume(self, amount);
+        Poll::Ready(Ok(amount))
+    }
+}
+
+impl<'a, 'b: 'a> io::BufRead for Rx<'a, 'b> {
+    fn poll_fill_buf<'c>(
+        self: Pin<&'c mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<&'c [u8], Self::Error>> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if !context.rx.is_empty() {
+                context.rx_waker = None;
+                // Safety: the interrupt only appends, and consuming requires
+                // the borrow of `self` to have ended
+                Poll::Ready(Ok(unsafe { context.rx.readable() }))
+            } else if let Some(error) = context.rx_error.take() {
+                Poll::Ready(Err(error))
+            } else {
+                context.rx_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+    }
+
+    fn consume(self: Pin<&mut Self>, amount: usize) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.rx.consume(amount);
+        })
+    }
+}
+
+impl<'a, 'b: 'a> io::Write for Tx<'a, 'b> {
+    type Error = !;
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            let amount = context.tx.extend(buf);
+            if amount == 0 {
+                context.tx_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            if !context.tx_busy {
+                // Start sending, the interrupt takes over from here
+                let byte = context.tx.pop().unwrap();
+                context.tx_busy = true;
+                context.uart.txd.write(|w| unsafe { w.bits(byte.into()) });
+            }
+            Poll::Ready(Ok(amount))
+        })
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if context.tx_busy {
+                context.tx_waker = Some(cx.waker().clone());
+                Poll::Pending
+            } else {
+                context.tx_waker = None;
+                Poll::Ready(Ok(()))
+            }
+        })
+    }
+
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match io::Write::poll_flush(self.as_mut(), cx) {
+            Poll::Ready(Ok(())) => (),
+            other => return other,
+        }
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.uart.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            Poll::Ready(Ok(()))
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use core::{cmp, ptr::NonNull, slice};
+
+/// A single producer, single consumer byte queue over `'static` storage.
+///
+/// The storage is held by pointer so the queue can live in the interrupt
+/// context while the bytes between [`readable`](RingBuffer::readable) and
+/// [`consume`](RingBuffer::consume) are handed out to the consumer, and it is
+/// `'static` as the interrupt keeps writing to it until the queue is replaced,
+/// which a leaked [`Rx`](super::Rx) never does.
+pub(crate) struct RingBuffer {
+    storage: NonNull<u8>,
+    capacity: usize,
+    start: usize,
+    len: usize,
+}
+
+// Safety: the storage is only accessed through the queue, which is itself
+// guarded by a critical section
+unsafe impl Send for RingBuffer {}
+
+impl RingBuffer {
+    pub(crate) const fn empty() -> Self {
+        RingBuffer {
+            storage: NonNull::dangling(),
+            capacity: 0,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn new(storage: &'static mut [u8]) -> Self {
+        RingBuffer {
+            storage: NonNull::from(&mut storage[..]).cast(),
+            capacity: storage.len(),
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+
+    fn wrap(&self, index: usize) -> usize {
+        if index >= self.capacity {
+            index - self.capacity
+        } else {
+            index
+        }
+    }
+
+    /// Returns whether there was space for the byte.
+    pub(crate) fn push(&mut self, byte: u8) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        let end = self.wrap(self.start + self.len);
+        unsafe { self.storage.as_ptr().add(end).write(byte) };
+        self.len += 1;
+        true
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+        let byte = unsafe { self.storage.as_ptr().add(self.start).read() };
+        self.start = self.wrap(self.start + 1);
+        self.len -= 1;
+        Some(byte)
+    }
+
+    /// Copies as many bytes as there is space for, returning how many.
+    pub(crate) fn extend(&mut self, bytes: &[u8]) -> usize {
+        let amount = cmp::min(bytes.len(), self.capacity - self.len);
+        for &byte in &bytes[..amount] {
+            self.push(byte);
+        }
+        amount
+    }
+
+    /// The oldest contiguous run of queued bytes.
+    ///
+    /// # Safety
+    ///
+    /// The returned slice must not be used after those bytes have been
+    /// consumed, or after the queue has been replaced or cleared.
+    pub(crate) unsafe fn readable<'a>(&self) -> &'a [u8] {
+        let amount = cmp::min(self.len, self.capacity - self.start);
+        slice::from_raw_parts(self.storage.as_ptr().add(self.start), amount)
+    }
+
+    pub(crate) fn consume(&mut self, amount: usize) {
+        let amount = cmp::min(amount, self.len);
+        self.start = self.wrap(self.start + amount);
+        self.len -= amount;
+        if self.len == 0 {
+            // Maximise the contiguous space available for the next read
+            self.start = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingBuffer;
+
+    #[test]
+    fn wraps_around() {
+        static mut STORAGE: [u8; 4] = [0; 4];
+        // Safety: only used by this test
+        let mut ring = RingBuffer::new(unsafe { &mut STORAGE });
+        assert_eq!(ring.extend(b"abc"), 3);
+        assert_eq!(ring.pop(), Some(b'a'));
+        assert_eq!(ring.pop(), Some(b'b'));
+        assert_eq!(ring.extend(b"defg"), 3);
+        assert!(ring.is_full());
+        assert!(!ring.push(b'h'));
+        assert_eq!(unsafe { ring.readable() }, b"cd");
+        ring.consume(2);
+        assert_eq!(unsafe { ring.readable() }, b"ef");
+        ring.consume(1);
+        assert_eq!(ring.pop(), Some(b'f'));
+        assert_eq!(ring.pop(), None);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn empty_has_no_capacity() {
+        let mut ring = RingBuffer::empty();
+        assert!(ring.is_full());
+        assert!(!ring.push(0));
+        assert_eq!(ring.extend(b"a"), 0);
+        assert_eq!(unsafe { ring.readable() }, b"");
+    }
+}
@@ -0,0 +1,157 @@
+use core::{marker::PhantomData, time::Duration};
+
+use embrio_core::watchdog::Watchdog;
+use nrf51::WDT;
+
+/// Number of reload registers, each enabled one must be fed before the
+/// watchdog is reloaded.
+pub const RELOAD_REGISTERS: usize = 8;
+
+// Ticks per second of the counter, running from the LFCLK
+const FREQUENCY: u128 = 32_768;
+
+// The counter can't be set below this
+const MIN_TICKS: u128 = 0x10;
+
+const RELOAD: u32 = 0x6E52_4635;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    TimeoutOutOfRange,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Config {
+    /// Between 0.5ms and 36 hours.
+    pub timeout: Duration,
+    /// How many of the reload registers are enabled, from 1 to
+    /// [`RELOAD_REGISTERS`].
+    pub reload_registers: usize,
+    /// Keep counting while the CPU sleeps, needed to catch a task that is
+    /// never woken.
+    pub run_while_sleeping: bool,
+    /// Keep counting while the CPU is halted by a debugger.
+    pub run_while_halted: bool,
+}
+
+#[derive(Debug)]
+pub struct Wdt<'b> {
+    _marker: PhantomData<&'b mut WDT>,
+}
+
+/// The enabled reload registers of a started watchdog.
+#[derive(Debug)]
+pub struct Reloads<'b> {
+    enabled: u32,
+    next: usize,
+    _marker: PhantomData<&'b WDT>,
+}
+
+/// One reload register, feeding it marks it as ready and the watchdog is
+/// reloaded once every enabled register is ready.
+#[derive(Debug)]
+pub struct Reload<'b> {
+    index: usize,
+    _marker: PhantomData<&'b WDT>,
+}
+
+// Safety: once started the registers are only written through a `Reload`,
+// which each own a different reload register
+fn wdt() -> &'static nrf51::wdt::RegisterBlock {
+    unsafe { &*WDT::ptr() }
+}
+
+fn timeout_ticks(timeout: Duration) -> Result<u32, Error> {
+    let ticks = timeout.as_nanos() * FREQUENCY / 1_000_000_000;
+    if ticks < MIN_TICKS || ticks - 1 > u32::max_value().into() {
+        return Err(Error::TimeoutOutOfRange);
+    }
+    Ok((ticks - 1) as u32)
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            timeout: Duration::from_secs(2),
+            reload_registers: 1,
+            run_while_sleeping: true,
+            run_while_halted: false,
+        }
+    }
+}
+
+impl<'b> Wdt<'b> {
+    pub(crate) fn new(_wdt: &'b mut WDT) -> Self {
+        Wdt {
+            _marker: PhantomData,
+        }
+    }
+
+    /// The watchdog keeps running through a soft reset, only a watchdog or
+    /// power on reset stops it.
+    pub fn is_running(&self) -> bool {
+        wdt().runstatus.read().bits() & 1 == 1
+    }
+
+    /// Start the watchdog, it can't be stopped or reconfigured afterwards.
+    ///
+    /// If it was already running the existing configuration is kept, and the
+    /// reloads are for the registers it has enabled.
+    pub fn start(self, config: Config) -> Result<Reloads<'b>, Error> {
+        let wdt = wdt();
+        if !self.is_running() {
+            assert!(
+                config.reload_registers > 0
+                    && config.reload_registers <= RELOAD_REGISTERS
+            );
+            let ticks = timeout_ticks(config.timeout)?;
+            wdt.crv.write(|w| unsafe { w.bits(ticks) });
+            wdt.rren.write(|w| unsafe {
+                w.bits((1 << config.reload_registers) - 1)
+            });
+            wdt.config.write(|w| {
+                w.sleep()
+                    .bit(config.run_while_sleeping)
+                    .halt()
+                    .bit(config.run_while_halted)
+            });
+            wdt.tasks_start.write(|w| unsafe { w.bits(1) });
+        }
+
+        Ok(Reloads {
+            enabled: wdt.rren.read().bits(),
+            next: 0,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<'b> Iterator for Reloads<'b> {
+    type Item = Reload<'b>;
+
+    fn next(&mut self) -> Option<Reload<'b>> {
+        while self.next < RELOAD_REGISTERS {
+            let index = self.next;
+            self.next += 1;
+            if self.enabled & (1 << index) != 0 {
+                return Some(Reload {
+                    index,
+                    _marker: PhantomData,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl<'b> Reload<'b> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'b> Watchdog for Reload<'b> {
+    fn feed(&mut self) {
+        wdt().rr[self.index].write(|w| unsafe { w.bits(RELOAD) });
+    }
+}
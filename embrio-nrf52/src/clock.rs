@@ -0,0 +1,88 @@
+use embrio_nrf_common::clock::{self, Event, Registers, Task};
+use nrf52832_pac::CLOCK;
+
+use crate::Nrf52;
+
+pub use embrio_nrf_common::clock::LfclkSource;
+
+pub type Clock<'b> = clock::Clock<'b, Nrf52>;
+
+pub type Hfclk = clock::Hfclk<Nrf52>;
+
+/// Stop the crystal oscillator if no [`Hfclk`] references are left.
+///
+/// Restarting it takes time, so rather than stopping it as soon as the last
+/// reference is dropped this is run before the executor sleeps, see
+/// [`Executor::with_sleep_hook`].
+///
+/// [`Executor::with_sleep_hook`]: embrio_executor::Executor::with_sleep_hook
+pub fn release_unused_hfclk() {
+    clock::release_unused_hfclk::<Nrf52>()
+}
+
+// Safety: the HFCLK tasks are only used from critical sections in the shared
+// driver, and the LFCLK only through an exclusively borrowed `Clock`
+fn clock() -> &'static nrf52832_pac::clock::RegisterBlock {
+    unsafe { &*CLOCK::ptr() }
+}
+
+impl Registers for Nrf52 {
+    fn trigger(task: Task) {
+        let clock = clock();
+        match task {
+            Task::HfclkStart => {
+                clock.tasks_hfclkstart.write(|w| unsafe { w.bits(1) })
+            }
+            Task::HfclkStop => {
+                clock.tasks_hfclkstop.write(|w| unsafe { w.bits(1) })
+            }
+            Task::LfclkStart => {
+                clock.tasks_lfclkstart.write(|w| unsafe { w.bits(1) })
+            }
+            Task::LfclkStop => {
+                clock.tasks_lfclkstop.write(|w| unsafe { w.bits(1) })
+            }
+        }
+    }
+
+    fn event(event: Event) -> bool {
+        let clock = clock();
+        let bits = match event {
+            Event::HfclkStarted => clock.events_hfclkstarted.read().bits(),
+            Event::LfclkStarted => clock.events_lfclkstarted.read().bits(),
+        };
+        bits == 1
+    }
+
+    fn clear(event: Event) {
+        let clock = clock();
+        match event {
+            Event::HfclkStarted => clock.events_hfclkstarted.reset(),
+            Event::LfclkStarted => clock.events_lfclkstarted.reset(),
+        }
+    }
+
+    fn hfclk_xtal() -> bool {
+        clock().hfclkstat.read().src().is_xtal()
+    }
+
+    fn lfclk_running() -> bool {
+        clock().lfclkstat.read().state().is_running()
+    }
+
+    fn lfclk_source() -> LfclkSource {
+        match clock().lfclksrc.read().bits() & 0b11 {
+            0 => LfclkSource::Rc,
+            1 => LfclkSource::Xtal,
+            _ => LfclkSource::Synth,
+        }
+    }
+
+    fn set_lfclk_source(source: LfclkSource) {
+        clock().lfclksrc.write(|w| match source {
+            LfclkSource::Rc => w.src().rc(),
+            LfclkSource::Xtal => w.src().xtal(),
+            LfclkSource::Synth => w.src().synth(),
+        });
+    }
+}
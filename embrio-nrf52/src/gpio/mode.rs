@@ -0,0 +1,4 @@
+pub use embrio_nrf_common::gpio::mode::{
+    Disabled, Floating, Input, InputMode, OpenDrain, Output, OutputMode,
+    PinMode, PullDown, PullUp, PushPull, Unconfigured,
+};
@@ -0,0 +1,157 @@
+#![no_std]
+#![feature(const_generics, never_type)]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+pub mod clock;
+pub mod gpio;
+pub mod rtc;
+pub mod timer;
+pub mod uarte;
+
+use core::{cell::UnsafeCell, ptr};
+
+use cortex_m::interrupt::{free, Mutex};
+use embrio_nrf_common::Chip;
+use nrf52832_pac::interrupt;
+
+use self::{clock::Clock, gpio::Pins, rtc::Rtc, uarte::Uarte};
+
+/// The nRF52 family, implementing the register access of the drivers shared
+/// with other families through `embrio-nrf-common`.
+#[derive(Debug)]
+pub enum Nrf52 {}
+
+impl Chip for Nrf52 {
+    type Interrupt = nrf52832_pac::Interrupt;
+}
+
+pub struct EmbrioNrf52<'b> {
+    pub clock: Clock<'b>,
+    pub pins: Pins<'b>,
+    /// Low power timers, these should be preferred over the TIMER peripherals
+    /// as they allow the HFCLK to stop while the executor is idle.
+    pub rtc0: Rtc<'b, nrf52832_pac::RTC0>,
+    pub rtc1: Rtc<'b, nrf52832_pac::RTC1>,
+    pub rtc2: Rtc<'b, nrf52832_pac::RTC2>,
+    pub uarte: Uarte<'b>,
+}
+
+impl<'b> EmbrioNrf52<'b> {
+    pub fn new(nrf52: &'b mut nrf52832_pac::Peripherals) -> EmbrioNrf52<'b> {
+        // Safety: CLOCK is exclusively borrowed for 'b through `nrf52`
+        let clock = unsafe { Clock::new() };
+        let pins = Pins::new(&mut nrf52.P0);
+        let rtc0 = Rtc::new(&mut nrf52.RTC0);
+        let rtc1 = Rtc::new(&mut nrf52.RTC1);
+        let rtc2 = Rtc::new(&mut nrf52.RTC2);
+        let uarte = Uarte::new(&mut nrf52.UARTE0);
+
+        EmbrioNrf52 {
+            clock,
+            pins,
+            rtc0,
+            rtc1,
+            rtc2,
+            uarte,
+        }
+    }
+
+    pub fn take() -> Option<EmbrioNrf52<'static>> {
+        struct StaticPeripherals {
+            nrf52: nrf52832_pac::Peripherals,
+        }
+
+        struct StaticContext {
+            flag: UnsafeCell<bool>,
+            peripherals: UnsafeCell<Option<StaticPeripherals>>,
+        }
+
+        // Safety: We return a non-Send `EmbrioNrf52`, so the
+        // static references to the peripherals cannot leak across contexts
+        unsafe impl Sync for StaticContext {}
+        unsafe impl Send for StaticContext {}
+
+        static CONTEXT: Mutex<StaticContext> = Mutex::new(StaticContext {
+            flag: UnsafeCell::new(false),
+            peripherals: UnsafeCell::new(None),
+        });
+
+        free(|c| {
+            let nrf52 = nrf52832_pac::Peripherals::take()?;
+
+            let context = CONTEXT.borrow(c);
+
+            // Safety: This flag is only accessed from within this critical
+            // section
+            unsafe {
+                let flag = context.flag.get();
+                if ptr::read_volatile(flag) {
+                    return None;
+                }
+                ptr::write_volatile(flag, true);
+            }
+
+            // Safety: The above flag guarantees the following code is only run
+            // once
+            let peripherals = unsafe { &mut *context.peripherals.get() };
+
+            peripherals.replace(StaticPeripherals { nrf52 });
+
+            let peripherals = peripherals.as_mut().unwrap();
+            let nrf52 = &mut peripherals.nrf52;
+
+            Some(EmbrioNrf52::new(nrf52))
+        })
+    }
+}
+
+#[interrupt]
+fn GPIOTE() {
+    gpio::interrupt()
+}
+
+#[interrupt]
+fn RTC0() {
+    rtc::Rtc::<nrf52832_pac::RTC0>::interrupt()
+}
+
+#[interrupt]
+fn RTC1() {
+    rtc::Rtc::<nrf52832_pac::RTC1>::interrupt()
+}
+
+#[interrupt]
+fn RTC2() {
+    rtc::Rtc::<nrf52832_pac::RTC2>::interrupt()
+}
+
+#[interrupt]
+fn UARTE0_UART0() {
+    uarte::Uarte::interrupt()
+}
+
+#[interrupt]
+fn TIMER0() {
+    timer::Timer::<nrf52832_pac::TIMER0>::interrupt()
+}
+
+#[interrupt]
+fn TIMER1() {
+    timer::Timer::<nrf52832_pac::TIMER1>::interrupt()
+}
+
+#[interrupt]
+fn TIMER2() {
+    timer::Timer::<nrf52832_pac::TIMER2>::interrupt()
+}
+
+#[interrupt]
+fn TIMER3() {
+    timer::Timer::<nrf52832_pac::TIMER3>::interrupt()
+}
+
+#[interrupt]
+fn TIMER4() {
+    timer::Timer::<nrf52832_pac::TIMER4>::interrupt()
+}
@@ -0,0 +1,108 @@
+use embrio_nrf_common::rtc::{self, Event, Registers, Task};
+use nrf52832_pac::{rtc0, Interrupt, RTC0, RTC1, RTC2};
+
+use crate::Nrf52;
+
+pub use embrio_nrf_common::rtc::{Error, FREQUENCY};
+
+/// A low power [`Timer`](embrio_core::timer::Timer) which keeps running while
+/// the HFCLK is stopped, at the cost of ~30µs resolution.
+///
+/// The 24-bit counter is extended to 64 bits by counting overflows, so
+/// `ticks` will not wrap.
+pub type Rtc<'b, T> = rtc::Rtc<'b, Nrf52, T>;
+
+pub type Timeout<'a, 'b, T> = rtc::Timeout<'a, 'b, Nrf52, T>;
+
+pub type Interval<'a, 'b, T> = rtc::Interval<'a, 'b, Nrf52, T>;
+
+pub trait Instance: rtc::Instance<Nrf52> {}
+
+impl<T: rtc::Instance<Nrf52>> Instance for T {}
+
+impl Registers<Nrf52> for rtc0::RegisterBlock {
+    fn trigger(&self, task: Task) {
+        match task {
+            Task::Start => self.tasks_start.write(|w| unsafe { w.bits(1) }),
+            Task::Stop => self.tasks_stop.write(|w| unsafe { w.bits(1) }),
+            Task::Clear => self.tasks_clear.write(|w| unsafe { w.bits(1) }),
+        }
+    }
+
+    fn set_prescaler(&self, prescaler: u16) {
+        self.prescaler
+            .write(|w| unsafe { w.prescaler().bits(prescaler) });
+    }
+
+    fn counter(&self) -> u32 {
+        self.counter.read().bits()
+    }
+
+    fn event(&self, event: Event) -> bool {
+        let bits = match event {
+            Event::Overflow => self.events_ovrflw.read().bits(),
+            Event::Compare => self.events_compare[0].read().bits(),
+        };
+        bits == 1
+    }
+
+    fn clear(&self, event: Event) {
+        match event {
+            Event::Overflow => self.events_ovrflw.reset(),
+            Event::Compare => self.events_compare[0].reset(),
+        }
+    }
+
+    fn enable_interrupt(&self, event: Event) {
+        match event {
+            Event::Overflow => self.intenset.write(|w| w.ovrflw().set()),
+            Event::Compare => self.intenset.write(|w| w.compare0().set()),
+        }
+    }
+
+    fn disable_interrupt(&self, event: Event) {
+        match event {
+            Event::Overflow => self.intenclr.write(|w| w.ovrflw().clear()),
+            Event::Compare => self.intenclr.write(|w| w.compare0().clear()),
+        }
+    }
+
+    fn set_compare(&self, value: u32) {
+        self.cc[0].write(|w| unsafe { w.bits(value) });
+    }
+}
+
+// Safety: the registers are only used by the `Rtc` exclusively borrowing the
+// instance, from critical sections
+impl rtc::Instance<Nrf52> for RTC0 {
+    type Registers = rtc0::RegisterBlock;
+
+    const INDEX: usize = 0;
+    const INTERRUPT: Interrupt = Interrupt::RTC0;
+
+    fn registers() -> &'static rtc0::RegisterBlock {
+        unsafe { &*RTC0::ptr() }
+    }
+}
+
+impl rtc::Instance<Nrf52> for RTC1 {
+    type Registers = rtc0::RegisterBlock;
+
+    const INDEX: usize = 1;
+    const INTERRUPT: Interrupt = Interrupt::RTC1;
+
+    fn registers() -> &'static rtc0::RegisterBlock {
+        unsafe { &*RTC1::ptr() }
+    }
+}
+
+impl rtc::Instance<Nrf52> for RTC2 {
+    type Registers = rtc0::RegisterBlock;
+
+    const INDEX: usize = 2;
+    const INTERRUPT: Interrupt = Interrupt::RTC2;
+
+    fn registers() -> &'static rtc0::RegisterBlock {
+        unsafe { &*RTC2::ptr() }
+    }
+}
@@ -0,0 +1,50 @@
+use core::cell::RefCell;
+
+use cortex_m::interrupt::Mutex;
+use embrio_nrf_common::timer::{BitMode, Instance, Wakers};
+use nrf52832_pac::{timer0, Interrupt, TIMER0, TIMER1, TIMER2, TIMER3, TIMER4};
+
+use crate::Nrf52;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A TIMER peripheral whose registers can also be driven directly, e.g.
+/// through PPI.
+pub trait TimerInstance:
+    sealed::Sealed + Instance<Nrf52, Registers = timer0::RegisterBlock>
+{
+}
+
+macro_rules! instances {
+    ($($timer:ident),*) => {
+        $(
+            impl sealed::Sealed for $timer {}
+
+            impl TimerInstance for $timer {}
+
+            // Safety: the registers are only used by the `Timer` owning the
+            // instance, and its interrupt
+            unsafe impl Instance<Nrf52> for $timer {
+                type Registers = timer0::RegisterBlock;
+
+                const INTERRUPT: Interrupt = Interrupt::$timer;
+                // 32bits @ 1MHz == max delay of ~1 hour 11 minutes
+                const MAX_BIT_MODE: BitMode = BitMode::Bits32;
+
+                fn registers() -> &'static timer0::RegisterBlock {
+                    unsafe { &*$timer::ptr() }
+                }
+
+                fn wakers() -> &'static Wakers {
+                    static WAKERS: Wakers =
+                        Mutex::new(RefCell::new([None, None, None, None]));
+                    &WAKERS
+                }
+            }
+        )*
+    }
+}
+
+instances!(TIMER0, TIMER1, TIMER2, TIMER3, TIMER4);
@@ -0,0 +1,63 @@
+use core::{cmp, ptr::NonNull, slice};
+
+/// Most bytes EasyDMA can move in one transfer, MAXCNT is only 8 bits wide.
+const MAX_TRANSFER: usize = 255;
+
+/// Storage for EasyDMA transfers.
+///
+/// The storage is held by pointer so it can live in the interrupt context
+/// while the peripheral reads or writes it between polls, it is `'static` so
+/// a transfer outliving the halves (e.g. when one is leaked) stays in bounds.
+pub(crate) struct DmaBuffer {
+    storage: NonNull<u8>,
+    capacity: usize,
+}
+
+// Safety: the storage is only accessed through the buffer, which is itself
+// guarded by a critical section
+unsafe impl Send for DmaBuffer {}
+
+impl DmaBuffer {
+    pub(crate) fn empty() -> Self {
+        DmaBuffer {
+            storage: NonNull::dangling(),
+            capacity: 0,
+        }
+    }
+
+    pub(crate) fn new(storage: &'static mut [u8]) -> Self {
+        DmaBuffer {
+            storage: NonNull::from(&mut storage[..]).cast(),
+            capacity: storage.len(),
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The PTR and MAXCNT register values for a transfer of at most `len`
+    /// bytes into or out of the storage starting at `offset`.
+    pub(crate) fn transfer(&self, offset: usize, len: usize) -> (u32, u32) {
+        let len = cmp::min(len, self.capacity - offset);
+        let ptr = self.storage.as_ptr() as usize + offset;
+        (ptr as u32, cmp::min(len, MAX_TRANSFER) as u32)
+    }
+
+    /// # Safety
+    ///
+    /// No transfer may be writing to `start..end` while the slice is used, and
+    /// it must not be used after the buffer has been replaced.
+    pub(crate) unsafe fn get<'a>(&self, start: usize, end: usize) -> &'a [u8] {
+        assert!(start <= end && end <= self.capacity);
+        slice::from_raw_parts(self.storage.as_ptr().add(start), end - start)
+    }
+
+    /// # Safety
+    ///
+    /// No transfer may be reading from the storage while the slice is alive.
+    pub(crate) unsafe fn get_mut(&mut self, len: usize) -> &mut [u8] {
+        let len = cmp::min(len, self.capacity);
+        slice::from_raw_parts_mut(self.storage.as_ptr(), len)
+    }
+}
@@ -0,0 +1,605 @@
+use core::{
+    cell::RefCell,
+    cmp,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use cortex_m::{
+    interrupt::{free, Mutex},
+    peripheral::NVIC,
+};
+use embrio_core::io;
+use nrf52832_pac::{Interrupt, UARTE0};
+
+use self::buffer::DmaBuffer;
+use crate::{
+    clock::Hfclk,
+    gpio::{
+        self,
+        mode::{Floating, Input, Output, PushPull},
+    },
+};
+
+pub use embrio_nrf_common::uart::{Error, Parity};
+pub use nrf52832_pac::uarte0::baudrate::BAUDRATE_A;
+
+mod buffer;
+
+/// A UART moving bytes to and from RAM with EasyDMA, so the CPU is
+/// interrupted once per transfer rather than once per byte.
+#[derive(Debug)]
+pub struct Uarte<'b> {
+    _marker: PhantomData<(&'b mut UARTE0, &'b mut NVIC)>,
+}
+
+#[derive(Debug)]
+pub struct Tx<'a, 'b: 'a> {
+    _marker: PhantomData<(
+        &'a mut Uarte<'b>,
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+    )>,
+}
+
+#[derive(Debug)]
+pub struct Rx<'a, 'b: 'a> {
+    _marker: PhantomData<(
+        &'a mut Uarte<'b>,
+        &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    )>,
+}
+
+/// The pins to connect, hardware flow control is enabled when RTS and CTS
+/// pins are given.
+#[derive(Debug)]
+pub struct Pins<'a, 'b: 'a> {
+    pub tx: &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+    pub rx: &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    pub flow_control: Option<(
+        &'a mut gpio::AnyPin<'b, Output<PushPull>>,
+        &'a mut gpio::AnyPin<'b, Input<Floating>>,
+    )>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Config {
+    pub baudrate: BAUDRATE_A,
+    pub parity: Parity,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum RxState {
+    /// Not receiving, bytes `read..len` of the buffer are waiting to be read.
+    Idle {
+        read: usize,
+        len: usize,
+    },
+    Receiving,
+    /// STOPRX has been triggered, waiting for the transfer to end.
+    Stopping,
+    /// The transfer has ended, waiting for the receiver to time out.
+    Stopped {
+        received: usize,
+    },
+    /// Moving bytes left in the FIFO after the `received` ones.
+    Flushing {
+        received: usize,
+    },
+}
+
+struct Context {
+    uarte: &'static mut UARTE0,
+    /// Taken while initialized, the baud rate is derived from the HFCLK.
+    hfclk: Option<Hfclk>,
+    /// The halves from `init` still alive, the HFCLK is released once both
+    /// are dropped.
+    halves: usize,
+    rx: DmaBuffer,
+    rx_state: RxState,
+    rx_error: Option<Error>,
+    rx_waker: Option<Waker>,
+    tx: DmaBuffer,
+    tx_busy: bool,
+    tx_waker: Option<Waker>,
+}
+
+// Pin select value leaving a signal unconnected
+const DISCONNECTED: u32 = 0xFFFF_FFFF;
+
+// Bytes the receiver can still take into its FIFO after STOPRX, space is left
+// at the end of the receive buffer to flush them into
+const FIFO_SPACE: usize = 5;
+
+static CONTEXT: Mutex<RefCell<Option<Context>>> =
+    Mutex::new(RefCell::new(None));
+
+unsafe fn erase_lifetime<'a, T>(t: &'a mut T) -> &'static mut T {
+    &mut *(t as *mut T)
+}
+
+impl Default for Config {
+    /// 115200 baud without parity.
+    fn default() -> Self {
+        Config {
+            baudrate: BAUDRATE_A::BAUD115200,
+            parity: Parity::None,
+        }
+    }
+}
+
+/// Applies the baud rate and parity, leaving flow control untouched.
+fn configure(uarte: &UARTE0, config: Config) {
+    uarte
+        .baudrate
+        .write(|w| w.baudrate().variant(config.baudrate));
+    uarte.config.modify(|_, w| match config.parity {
+        Parity::None => w.parity().excluded(),
+        Parity::Even => w.parity().included(),
+    });
+}
+
+impl Context {
+    fn start_rx(&mut self) {
+        let capacity = self.rx.capacity();
+        let (ptr, maxcnt) = self.rx.transfer(0, capacity - FIFO_SPACE);
+        self.uarte.rxd.ptr.write(|w| unsafe { w.bits(ptr) });
+        self.uarte.rxd.maxcnt.write(|w| unsafe { w.bits(maxcnt) });
+        self.uarte.events_rxdrdy.reset();
+        self.uarte.intenset.write(|w| w.rxdrdy().set());
+        self.uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+        self.rx_state = RxState::Receiving;
+    }
+
+    /// Hand over the bytes received so far, the interrupt finishes stopping.
+    fn stop_rx(&mut self) {
+        self.uarte.intenclr.write(|w| w.rxdrdy().clear());
+        self.uarte.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+        self.rx_state = RxState::Stopping;
+    }
+
+    /// Block until nothing is being received, discarding what has been, so
+    /// the buffer can be released.
+    fn abort_rx(&mut self) {
+        match self.rx_state {
+            RxState::Idle { .. } => return,
+            RxState::Receiving => self.stop_rx(),
+            _ => (),
+        }
+        if let RxState::Flushing { .. } = self.rx_state {
+            while self.uarte.events_endrx.read().bits() == 0 {}
+        } else {
+            while self.uarte.events_rxto.read().bits() == 0 {}
+        }
+        self.uarte.events_rxdrdy.reset();
+        self.uarte.events_endrx.reset();
+        self.uarte.events_rxto.reset();
+        self.rx_state = RxState::Idle { read: 0, len: 0 };
+    }
+
+    /// Block until nothing is being sent, so the buffer can be released.
+    fn abort_tx(&mut self) {
+        if self.tx_busy {
+            while self.uarte.events_endtx.read().bits() == 0 {}
+            self.uarte.events_endtx.reset();
+            self.uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            self.tx_busy = false;
+        }
+    }
+
+    /// Release the HFCLK once neither half is left to use it.
+    fn release_half(&mut self) {
+        self.halves -= 1;
+        if self.halves == 0 {
+            self.hfclk = None;
+        }
+    }
+}
+
+impl<'b> Uarte<'b> {
+    pub(crate) fn new(uarte: &'b mut UARTE0) -> Self {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            assert!(context.is_none());
+            context.replace(Context {
+                uarte: unsafe { erase_lifetime(uarte) },
+                hfclk: None,
+                halves: 0,
+                rx: DmaBuffer::empty(),
+                rx_state: RxState::Idle { read: 0, len: 0 },
+                rx_error: None,
+                rx_waker: None,
+                tx: DmaBuffer::empty(),
+                tx_busy: false,
+                tx_waker: None,
+            });
+        });
+
+        Uarte {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Bytes are received into `rx_buffer` and sent from `tx_buffer`, which
+    /// EasyDMA requires to be in RAM, each transfer is limited to 255 bytes.
+    /// They are `'static` as EasyDMA keeps using them until the driver sees
+    /// the halves dropped, which a leaked half never is.
+    ///
+    /// Bytes are only received while a read is waiting, a few arriving just
+    /// after it completes are kept but more may be lost unless flow control
+    /// is used. `rx_buffer` must be longer than those few bytes.
+    ///
+    /// To move to different pins drop the returned halves and call this again,
+    /// the baud rate and parity can be changed in place with
+    /// [`Tx::reconfigure`].
+    pub fn init<'a>(
+        &'a mut self,
+        pins: Pins<'a, 'b>,
+        config: Config,
+        tx_buffer: &'static mut [u8],
+        rx_buffer: &'static mut [u8],
+    ) -> (Tx<'a, 'b>, Rx<'a, 'b>)
+    where
+        'b: 'a,
+    {
+        assert!(!tx_buffer.is_empty() && rx_buffer.len() > FIFO_SPACE);
+        let hfclk = Hfclk::request();
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.hfclk = Some(hfclk);
+            context.halves = 2;
+
+            // The pins can only be changed while disabled
+            context.uarte.enable.write(|w| w.enable().disabled());
+
+            context.tx = DmaBuffer::new(tx_buffer);
+            context.rx = DmaBuffer::new(rx_buffer);
+            context.rx_state = RxState::Idle { read: 0, len: 0 };
+            context.rx_error = None;
+            context.tx_busy = false;
+
+            context
+                .uarte
+                .psel
+                .txd
+                .write(|w| unsafe { w.bits(pins.tx.get_id() as u32) });
+            context
+                .uarte
+                .psel
+                .rxd
+                .write(|w| unsafe { w.bits(pins.rx.get_id() as u32) });
+            match pins.flow_control {
+                Some((rts, cts)) => {
+                    context
+                        .uarte
+                        .psel
+                        .rts
+                        .write(|w| unsafe { w.bits(rts.get_id() as u32) });
+                    context
+                        .uarte
+                        .psel
+                        .cts
+                        .write(|w| unsafe { w.bits(cts.get_id() as u32) });
+                    context.uarte.config.write(|w| w.hwfc().enabled());
+                }
+                None => {
+                    context
+                        .uarte
+                        .psel
+                        .rts
+                        .write(|w| unsafe { w.bits(DISCONNECTED) });
+                    context
+                        .uarte
+                        .psel
+                        .cts
+                        .write(|w| unsafe { w.bits(DISCONNECTED) });
+                    context.uarte.config.write(|w| w.hwfc().disabled());
+                }
+            }
+            configure(context.uarte, config);
+
+            context.uarte.events_rxdrdy.reset();
+            context.uarte.events_endrx.reset();
+            context.uarte.events_rxto.reset();
+            context.uarte.events_endtx.reset();
+            context.uarte.events_error.reset();
+            context.uarte.errorsrc.write(|w| unsafe { w.bits(0b1111) });
+            context.uarte.intenset.write(|w| {
+                w.endrx().set().rxto().set().endtx().set().error().set()
+            });
+            context.uarte.enable.write(|w| w.enable().enabled());
+
+            unsafe { NVIC::unmask(Interrupt::UARTE0_UART0) };
+        });
+
+        (
+            Tx {
+                _marker: PhantomData,
+            },
+            Rx {
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn interrupt() {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = match context.as_mut() {
+                Some(context) => context,
+                None => return,
+            };
+            NVIC::unpend(Interrupt::UARTE0_UART0);
+            if context.uarte.events_error.read().bits() == 1 {
+                context.uarte.events_error.reset();
+                let bits = context.uarte.errorsrc.read().bits();
+                // Flags are cleared by writing them back
+                context.uarte.errorsrc.write(|w| unsafe { w.bits(bits) });
+                if let Some(error) = Error::from_errorsrc(bits) {
+                    context.rx_error.get_or_insert(error);
+                }
+                // Report it along with whatever was received before it
+                if context.rx_state == RxState::Receiving {
+                    context.stop_rx();
+                }
+                if let Some(waker) = context.rx_waker.take() {
+                    waker.wake();
+                }
+            }
+            if context.uarte.events_rxdrdy.read().bits() == 1 {
+                context.uarte.events_rxdrdy.reset();
+                // Someone is waiting, so stop at the first byte rather than
+                // when the buffer is full
+                if context.rx_state == RxState::Receiving {
+                    context.stop_rx();
+                }
+            }
+            if context.uarte.events_endrx.read().bits() == 1 {
+                context.uarte.events_endrx.reset();
+                let amount = context.uarte.rxd.amount.read().bits() as usize;
+                match context.rx_state {
+                    RxState::Receiving | RxState::Stopping => {
+                        if context.rx_state == RxState::Receiving {
+                            context.stop_rx();
+                        }
+                        context.rx_state =
+                            RxState::Stopped { received: amount };
+                    }
+                    RxState::Flushing { received } => {
+                        context.rx_state = RxState::Idle {
+                            read: 0,
+                            len: received + amount,
+                        };
+                        if let Some(waker) = context.rx_waker.take() {
+                            waker.wake();
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            if context.uarte.events_rxto.read().bits() == 1 {
+                context.uarte.events_rxto.reset();
+                if let RxState::Stopped { received } = context.rx_state {
+                    let (ptr, maxcnt) =
+                        context.rx.transfer(received, FIFO_SPACE);
+                    context.uarte.rxd.ptr.write(|w| unsafe { w.bits(ptr) });
+                    context
+                        .uarte
+                        .rxd
+                        .maxcnt
+                        .write(|w| unsafe { w.bits(maxcnt) });
+                    context.uarte.tasks_flushrx.write(|w| unsafe { w.bits(1) });
+                    context.rx_state = RxState::Flushing { received };
+                }
+            }
+            if context.uarte.events_endtx.read().bits() == 1 {
+                context.uarte.events_endtx.reset();
+                // The transmitter keeps running until stopped
+                context.uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+                context.tx_busy = false;
+                if let Some(waker) = context.tx_waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+    }
+}
+
+impl<'b> Drop for Uarte<'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let context = CONTEXT.borrow(c).borrow_mut().take().unwrap();
+
+            NVIC::mask(Interrupt::UARTE0_UART0);
+
+            context.uarte.enable.write(|w| w.enable().disabled());
+            context.uarte.intenclr.write(|w| unsafe { w.bits(!0) });
+
+            context.uarte.psel.txd.reset();
+            context.uarte.psel.rxd.reset();
+            context.uarte.psel.rts.reset();
+            context.uarte.psel.cts.reset();
+            context.uarte.baudrate.reset();
+            context.uarte.config.reset();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Tx<'a, 'b> {
+    /// Change the baud rate and parity of both halves without reconnecting.
+    ///
+    /// Bytes still being sent will be corrupted so flush first, and the other
+    /// end should switch over at the same time.
+    pub fn reconfigure(&mut self, config: Config) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            configure(context.uarte, config);
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Drop for Tx<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            // The transfer in flight finishes, it's reading the buffer
+            context.abort_tx();
+            context.tx = DmaBuffer::empty();
+            context.tx_waker = None;
+            context.release_half();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> Drop for Rx<'a, 'b> {
+    fn drop(&mut self) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            context.abort_rx();
+            context.rx = DmaBuffer::empty();
+            context.rx_error = None;
+            context.rx_waker = None;
+            context.release_half();
+        });
+    }
+}
+
+impl<'a, 'b: 'a> io::Read for Rx<'a, 'b> {
+    type Error = Error;
+
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let amount = match io::BufRead::poll_fill_buf(self.as_mut(), cx) {
+            Poll::Ready(Ok(available)) => {
+                let amount = cmp::min(available.len(), buf.len());
+                buf[..amount].copy_from_slice(&available[..amount]);
+                amount
+            }
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+        io::BufRead::consume(self, amount);
+        Poll::Ready(Ok(amount))
+    }
+}
+
+impl<'a, 'b: 'a> io::BufRead for Rx<'a, 'b> {
+    fn poll_fill_buf<'c>(
+        self: Pin<&'c mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<&'c [u8], Self::Error>> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            match context.rx_state {
+                RxState::Idle { read, len } if read < len => {
+                    context.rx_waker = None;
+                    // Safety: nothing is received until these bytes have been
+                    // consumed, which requires the borrow of `self` to have
+                    // ended
+                    Poll::Ready(Ok(unsafe { context.rx.get(read, len) }))
+                }
+                RxState::Idle { .. } => {
+                    if let Some(error) = context.rx_error.take() {
+                        return Poll::Ready(Err(error));
+                    }
+                    context.start_rx();
+                    context.rx_waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                _ => {
+                    context.rx_waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+    }
+
+    fn consume(self: Pin<&mut Self>, amount: usize) {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if let RxState::Idle { read, len } = context.rx_state {
+                let read = cmp::min(read + amount, len);
+                context.rx_state = RxState::Idle { read, len };
+            }
+        })
+    }
+}
+
+impl<'a, 'b: 'a> io::Write for Tx<'a, 'b> {
+    type Error = !;
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if context.tx_busy {
+                context.tx_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            // `buf` may be in flash, which EasyDMA can't read from
+            let (ptr, maxcnt) = context.tx.transfer(0, buf.len());
+            let amount = maxcnt as usize;
+            // Safety: nothing is being sent
+            unsafe { context.tx.get_mut(amount) }
+                .copy_from_slice(&buf[..amount]);
+            context.uarte.txd.ptr.write(|w| unsafe { w.bits(ptr) });
+            context
+                .uarte
+                .txd
+                .maxcnt
+                .write(|w| unsafe { w.bits(maxcnt) });
+            context.tx_busy = true;
+            context.uarte.tasks_starttx.write(|w| unsafe { w.bits(1) });
+            Poll::Ready(Ok(amount))
+        })
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        free(|c| {
+            let mut context = CONTEXT.borrow(c).borrow_mut();
+            let context = context.as_mut().unwrap();
+            if context.tx_busy {
+                context.tx_waker = Some(cx.waker().clone());
+                Poll::Pending
+            } else {
+                context.tx_waker = None;
+                Poll::Ready(Ok(()))
+            }
+        })
+    }
+
+    /// The transmitter is already stopped after each transfer, so this only
+    /// flushes.
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        io::Write::poll_flush(self, cx)
+    }
+}
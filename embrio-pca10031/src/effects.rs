@@ -0,0 +1,20 @@
+//! Colour sequences for the RGB LED, played one colour per tick of a timer.
+//! They are plain iterators so can be combined, e.g. a fade in chained with a
+//! fade out and cycled to breathe.
+
+use embrio_core::gpio::Output;
+
+pub use embrio_util::rgb::{
+    blink, fade, play, Blink, Color, Fade, Light, Play, RgbPwm,
+};
+
+use crate::led::RgbLed;
+
+/// Components are rounded to fully on or off.
+impl<'b> Light for RgbLed<'b> {
+    fn set(&mut self, color: Color) {
+        self.red.set_state(color.r >= 0x80);
+        self.green.set_state(color.g >= 0x80);
+        self.blue.set_state(color.b >= 0x80);
+    }
+}
@@ -0,0 +1,44 @@
+use embrio_core::gpio::Output;
+use embrio_nrf51::gpio::{
+    mode::{self, PushPull},
+    Pin,
+};
+
+/// An LED that lights while its pin is low, as an [`Output`] that is high
+/// while the LED is lit.
+#[derive(Debug)]
+pub struct Led<P> {
+    pin: P,
+}
+
+/// The RGB LED, each colour is a separate active low [`Led`].
+#[derive(Debug)]
+pub struct RgbLed<'b> {
+    pub red: Led<Pin<'b, 21, mode::Output<PushPull>>>,
+    pub green: Led<Pin<'b, 22, mode::Output<PushPull>>>,
+    pub blue: Led<Pin<'b, 23, mode::Output<PushPull>>>,
+}
+
+impl<P: Output> Led<P> {
+    /// The LED starts off.
+    pub fn new(pin: P) -> Self {
+        pin.set_high();
+        Led { pin }
+    }
+
+    /// Returns the pin, e.g. to drive the LED from a PWM instead, duty cycles
+    /// are inverted since the LED lights while the pin is low.
+    pub fn free(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: Output> Output for Led<P> {
+    fn state(&self) -> bool {
+        !self.pin.state()
+    }
+
+    fn set_state(&self, state: bool) {
+        self.pin.set_state(!state);
+    }
+}
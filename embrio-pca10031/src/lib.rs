@@ -0,0 +1,107 @@
+//! Board support for the nRF51 Dongle (PCA10031), built from the pins of an
+//! [`EmbrioNrf51`](embrio_nrf51::EmbrioNrf51).
+
+#![no_std]
+
+pub mod effects;
+mod led;
+
+pub use self::led::{Led, RgbLed};
+
+use embrio_nrf51::gpio::{
+    mode::{Floating, Input, Output, PushPull, Unconfigured},
+    Pin, Pins,
+};
+
+/// The board's named pins, taken from [`Pins`].
+#[derive(Debug)]
+pub struct Board<'b> {
+    pub led: RgbLed<'b>,
+    pub uart: Uart<'b>,
+    pub gpio: Gpio<'b>,
+}
+
+/// Connected to the interface chip, which bridges them to USB serial, with
+/// optional hardware flow control.
+#[derive(Debug)]
+pub struct Uart<'b> {
+    pub tx: Pin<'b, 9, Output<PushPull>>,
+    pub rx: Pin<'b, 11, Input<Floating>>,
+    pub rts: Pin<'b, 8, Output<PushPull>>,
+    pub cts: Pin<'b, 10, Input<Floating>>,
+}
+
+/// The pins not used on the board itself.
+#[derive(Debug)]
+pub struct Gpio<'b> {
+    pub p0_00: Pin<'b, 0, Unconfigured>,
+    pub p0_01: Pin<'b, 1, Unconfigured>,
+    pub p0_02: Pin<'b, 2, Unconfigured>,
+    pub p0_03: Pin<'b, 3, Unconfigured>,
+    pub p0_04: Pin<'b, 4, Unconfigured>,
+    pub p0_05: Pin<'b, 5, Unconfigured>,
+    pub p0_06: Pin<'b, 6, Unconfigured>,
+    pub p0_07: Pin<'b, 7, Unconfigured>,
+    pub p0_12: Pin<'b, 12, Unconfigured>,
+    pub p0_13: Pin<'b, 13, Unconfigured>,
+    pub p0_14: Pin<'b, 14, Unconfigured>,
+    pub p0_15: Pin<'b, 15, Unconfigured>,
+    pub p0_16: Pin<'b, 16, Unconfigured>,
+    pub p0_17: Pin<'b, 17, Unconfigured>,
+    pub p0_18: Pin<'b, 18, Unconfigured>,
+    pub p0_19: Pin<'b, 19, Unconfigured>,
+    pub p0_20: Pin<'b, 20, Unconfigured>,
+    pub p0_24: Pin<'b, 24, Unconfigured>,
+    pub p0_25: Pin<'b, 25, Unconfigured>,
+    pub p0_26: Pin<'b, 26, Unconfigured>,
+    pub p0_27: Pin<'b, 27, Unconfigured>,
+    pub p0_28: Pin<'b, 28, Unconfigured>,
+    pub p0_29: Pin<'b, 29, Unconfigured>,
+    pub p0_30: Pin<'b, 30, Unconfigured>,
+    pub p0_31: Pin<'b, 31, Unconfigured>,
+}
+
+impl<'b> Board<'b> {
+    pub fn new(pins: Pins<'b>) -> Self {
+        Board {
+            led: RgbLed {
+                red: Led::new(pins.p0_21.output().push_pull()),
+                green: Led::new(pins.p0_22.output().push_pull()),
+                blue: Led::new(pins.p0_23.output().push_pull()),
+            },
+            uart: Uart {
+                tx: pins.p0_09.output().push_pull(),
+                rx: pins.p0_11.input().floating(),
+                rts: pins.p0_08.output().push_pull(),
+                cts: pins.p0_10.input().floating(),
+            },
+            gpio: Gpio {
+                p0_00: pins.p0_00,
+                p0_01: pins.p0_01,
+                p0_02: pins.p0_02,
+                p0_03: pins.p0_03,
+                p0_04: pins.p0_04,
+                p0_05: pins.p0_05,
+                p0_06: pins.p0_06,
+                p0_07: pins.p0_07,
+                p0_12: pins.p0_12,
+                p0_13: pins.p0_13,
+                p0_14: pins.p0_14,
+                p0_15: pins.p0_15,
+                p0_16: pins.p0_16,
+                p0_17: pins.p0_17,
+                p0_18: pins.p0_18,
+                p0_19: pins.p0_19,
+                p0_20: pins.p0_20,
+                p0_24: pins.p0_24,
+                p0_25: pins.p0_25,
+                p0_26: pins.p0_26,
+                p0_27: pins.p0_27,
+                p0_28: pins.p0_28,
+                p0_29: pins.p0_29,
+                p0_30: pins.p0_30,
+                p0_31: pins.p0_31,
+            },
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::{adc::Adc, timer::Timer};
+use futures_core::stream::Stream;
+use futures_util::ready;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error<A, T> {
+    Adc(A),
+    Timer(T),
+}
+
+/// Convert a raw reading into the voltage at the input pin in millivolts.
+///
+/// The reading has a resolution of `bits`, `reference` is the reference
+/// voltage in millivolts and the input is prescaled by `numerator /
+/// denominator` before it is compared with the reference.
+pub fn millivolts(
+    raw: u16,
+    bits: u32,
+    reference: u32,
+    numerator: u32,
+    denominator: u32,
+) -> u32 {
+    let max = (1u64 << bits) - 1;
+    let scaled = u64::from(raw) * u64::from(reference) * u64::from(denominator);
+    (scaled / (max * u64::from(numerator))) as u32
+}
+
+/// A [`Stream`] of readings from a single channel, starting a conversion on
+/// each tick of an interval.
+///
+/// If a conversion takes longer than the period, ticks will be missed or
+/// delayed depending on the timer implementation.
+pub struct Samples<A: Adc, T: Timer> {
+    adc: A,
+    channel: A::Channel,
+    interval: T::Interval,
+    converting: bool,
+}
+
+impl<A: Adc, T: Timer> Samples<A, T> {
+    pub fn new(
+        adc: A,
+        channel: A::Channel,
+        timer: T,
+        period: Duration,
+    ) -> Self {
+        Samples {
+            adc,
+            channel,
+            interval: timer.interval(period),
+            converting: false,
+        }
+    }
+}
+
+impl<A: Adc, T: Timer> Stream for Samples<A, T> {
+    type Item = Result<u16, Error<A::Error, T::Error>>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // Safety: `adc` and `interval` are only accessed through new pinned
+        // references
+        let Samples {
+            adc,
+            channel,
+            interval,
+            converting,
+        } = unsafe { Pin::get_unchecked_mut(self) };
+        let adc = unsafe { Pin::new_unchecked(adc) };
+        let interval = unsafe { Pin::new_unchecked(interval) };
+
+        if !*converting {
+            match ready!(interval.poll_next(cx)) {
+                Some(Ok(())) => *converting = true,
+                Some(Err(err)) => {
+                    return Poll::Ready(Some(Err(Error::Timer(err))))
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+
+        let result = ready!(adc.poll_read(cx, *channel));
+        *converting = false;
+        Poll::Ready(Some(result.map_err(Error::Adc)))
+    }
+}
+
+impl<A: Adc, T: Timer> Drop for Samples<A, T> {
+    fn drop(&mut self) {
+        if self.converting {
+            // Safety: `adc` is pinned in place until it is dropped after this
+            unsafe { Pin::new_unchecked(&mut self.adc) }.abort();
+        }
+    }
+}
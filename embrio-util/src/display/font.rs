@@ -0,0 +1,197 @@
+use core::str::Chars;
+
+use super::frame::{Frame, WIDTH};
+
+/// The first character with a glyph, the font covers printable ASCII.
+const FIRST: char = ' ';
+
+#[rustfmt::skip]
+const GLYPHS: [[u8; 5]; 95] = [
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // space
+    [0b00100, 0b00100, 0b00100, 0b00000, 0b00100], // '!'
+    [0b01010, 0b01010, 0b00000, 0b00000, 0b00000], // '"'
+    [0b01010, 0b11111, 0b01010, 0b11111, 0b01010], // '#'
+    [0b01111, 0b10100, 0b01110, 0b00101, 0b11110], // '$'
+    [0b11001, 0b11010, 0b00100, 0b01011, 0b10011], // '%'
+    [0b01100, 0b10010, 0b01101, 0b10010, 0b01101], // '&'
+    [0b00100, 0b00100, 0b00000, 0b00000, 0b00000], // '\''
+    [0b00010, 0b00100, 0b00100, 0b00100, 0b00010], // '('
+    [0b01000, 0b00100, 0b00100, 0b00100, 0b01000], // ')'
+    [0b00000, 0b01010, 0b00100, 0b01010, 0b00000], // '*'
+    [0b00000, 0b00100, 0b01110, 0b00100, 0b00000], // '+'
+    [0b00000, 0b00000, 0b00000, 0b00100, 0b01000], // ','
+    [0b00000, 0b00000, 0b01110, 0b00000, 0b00000], // '-'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00100], // '.'
+    [0b00001, 0b00010, 0b00100, 0b01000, 0b10000], // '/'
+    [0b01110, 0b10011, 0b10101, 0b11001, 0b01110], // '0'
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b01110], // '1'
+    [0b11110, 0b00001, 0b01110, 0b10000, 0b11111], // '2'
+    [0b11110, 0b00001, 0b00110, 0b00001, 0b11110], // '3'
+    [0b00110, 0b01010, 0b10010, 0b11111, 0b00010], // '4'
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b11110], // '5'
+    [0b01110, 0b10000, 0b11110, 0b10001, 0b01110], // '6'
+    [0b11111, 0b00010, 0b00100, 0b01000, 0b01000], // '7'
+    [0b01110, 0b10001, 0b01110, 0b10001, 0b01110], // '8'
+    [0b01110, 0b10001, 0b01111, 0b00001, 0b01110], // '9'
+    [0b00000, 0b00100, 0b00000, 0b00100, 0b00000], // ':'
+    [0b00000, 0b00100, 0b00000, 0b00100, 0b01000], // ';'
+    [0b00010, 0b00100, 0b01000, 0b00100, 0b00010], // '<'
+    [0b00000, 0b01110, 0b00000, 0b01110, 0b00000], // '='
+    [0b01000, 0b00100, 0b00010, 0b00100, 0b01000], // '>'
+    [0b01110, 0b00001, 0b00110, 0b00000, 0b00100], // '?'
+    [0b01110, 0b10001, 0b10111, 0b10000, 0b01110], // '@'
+    [0b01110, 0b10001, 0b11111, 0b10001, 0b10001], // 'A'
+    [0b11110, 0b10001, 0b11110, 0b10001, 0b11110], // 'B'
+    [0b01111, 0b10000, 0b10000, 0b10000, 0b01111], // 'C'
+    [0b11110, 0b10001, 0b10001, 0b10001, 0b11110], // 'D'
+    [0b11111, 0b10000, 0b11110, 0b10000, 0b11111], // 'E'
+    [0b11111, 0b10000, 0b11110, 0b10000, 0b10000], // 'F'
+    [0b01111, 0b10000, 0b10011, 0b10001, 0b01110], // 'G'
+    [0b10001, 0b10001, 0b11111, 0b10001, 0b10001], // 'H'
+    [0b01110, 0b00100, 0b00100, 0b00100, 0b01110], // 'I'
+    [0b00111, 0b00001, 0b00001, 0b10001, 0b01110], // 'J'
+    [0b10010, 0b10100, 0b11000, 0b10100, 0b10010], // 'K'
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b11111], // 'L'
+    [0b10001, 0b11011, 0b10101, 0b10001, 0b10001], // 'M'
+    [0b10001, 0b11001, 0b10101, 0b10011, 0b10001], // 'N'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b01110], // 'O'
+    [0b11110, 0b10001, 0b11110, 0b10000, 0b10000], // 'P'
+    [0b01110, 0b10001, 0b10101, 0b10010, 0b01101], // 'Q'
+    [0b11110, 0b10001, 0b11110, 0b10010, 0b10001], // 'R'
+    [0b01111, 0b10000, 0b01110, 0b00001, 0b11110], // 'S'
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100], // 'T'
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // 'U'
+    [0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // 'V'
+    [0b10001, 0b10001, 0b10101, 0b11011, 0b10001], // 'W'
+    [0b10001, 0b01010, 0b00100, 0b01010, 0b10001], // 'X'
+    [0b10001, 0b01010, 0b00100, 0b00100, 0b00100], // 'Y'
+    [0b11111, 0b00010, 0b00100, 0b01000, 0b11111], // 'Z'
+    [0b01110, 0b01000, 0b01000, 0b01000, 0b01110], // '['
+    [0b10000, 0b01000, 0b00100, 0b00010, 0b00001], // '\\'
+    [0b01110, 0b00010, 0b00010, 0b00010, 0b01110], // ']'
+    [0b00100, 0b01010, 0b00000, 0b00000, 0b00000], // '^'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b11111], // '_'
+    [0b01000, 0b00100, 0b00000, 0b00000, 0b00000], // '`'
+    [0b00000, 0b01110, 0b10010, 0b10010, 0b01111], // 'a'
+    [0b10000, 0b10000, 0b11100, 0b10010, 0b11100], // 'b'
+    [0b00000, 0b01110, 0b10000, 0b10000, 0b01110], // 'c'
+    [0b00010, 0b00010, 0b01110, 0b10010, 0b01110], // 'd'
+    [0b01100, 0b10010, 0b11100, 0b10000, 0b01110], // 'e'
+    [0b00110, 0b01000, 0b11100, 0b01000, 0b01000], // 'f'
+    [0b01110, 0b10010, 0b01110, 0b00010, 0b01100], // 'g'
+    [0b10000, 0b10000, 0b11100, 0b10010, 0b10010], // 'h'
+    [0b01000, 0b00000, 0b01000, 0b01000, 0b01000], // 'i'
+    [0b00010, 0b00000, 0b00010, 0b10010, 0b01100], // 'j'
+    [0b10000, 0b10100, 0b11000, 0b10100, 0b10010], // 'k'
+    [0b01000, 0b01000, 0b01000, 0b01000, 0b00110], // 'l'
+    [0b00000, 0b11010, 0b10101, 0b10101, 0b10101], // 'm'
+    [0b00000, 0b11100, 0b10010, 0b10010, 0b10010], // 'n'
+    [0b00000, 0b01100, 0b10010, 0b10010, 0b01100], // 'o'
+    [0b00000, 0b11100, 0b10010, 0b11100, 0b10000], // 'p'
+    [0b00000, 0b01110, 0b10010, 0b01110, 0b00010], // 'q'
+    [0b00000, 0b01110, 0b10000, 0b10000, 0b10000], // 'r'
+    [0b00000, 0b00110, 0b01000, 0b00100, 0b11000], // 's'
+    [0b01000, 0b11100, 0b01000, 0b01000, 0b00110], // 't'
+    [0b00000, 0b10010, 0b10010, 0b10010, 0b01110], // 'u'
+    [0b00000, 0b10001, 0b10001, 0b01010, 0b00100], // 'v'
+    [0b00000, 0b10001, 0b10101, 0b10101, 0b01010], // 'w'
+    [0b00000, 0b10010, 0b01100, 0b01100, 0b10010], // 'x'
+    [0b00000, 0b10010, 0b01110, 0b00010, 0b01100], // 'y'
+    [0b00000, 0b11110, 0b00100, 0b01000, 0b11110], // 'z'
+    [0b00110, 0b00100, 0b01100, 0b00100, 0b00110], // '{'
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // '|'
+    [0b01100, 0b00100, 0b00110, 0b00100, 0b01100], // '}'
+    [0b00000, 0b00000, 0b01101, 0b10110, 0b00000], // '~'
+];
+
+/// The glyph for `c`, or `?` for characters outside printable ASCII.
+pub fn glyph(c: char) -> Frame {
+    let index = (c as usize).wrapping_sub(FIRST as usize);
+    Frame::new(
+        *GLYPHS
+            .get(index)
+            .unwrap_or(&GLYPHS['?' as usize - FIRST as usize]),
+    )
+}
+
+/// The frames of `text` scrolling across the display from right to left, one
+/// column per frame with a blank column between characters, until it has
+/// completely left the display.
+#[derive(Debug, Clone)]
+pub struct Scroll<'a> {
+    chars: Chars<'a>,
+    glyph: Option<(Frame, usize)>,
+    trailing: usize,
+    frame: Frame,
+}
+
+impl<'a> Scroll<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Scroll {
+            chars: text.chars(),
+            glyph: None,
+            trailing: WIDTH - 1,
+            frame: Frame::default(),
+        }
+    }
+
+    fn next_column(&mut self) -> Option<u8> {
+        loop {
+            if let Some((glyph, x)) = &mut self.glyph {
+                if *x < WIDTH {
+                    *x += 1;
+                    return Some(glyph.column(*x - 1));
+                }
+                self.glyph = None;
+                return Some(0);
+            }
+            match self.chars.next() {
+                Some(c) => self.glyph = Some((glyph(c), 0)),
+                None if self.trailing > 0 => {
+                    self.trailing -= 1;
+                    return Some(0);
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Scroll<'a> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        let column = self.next_column()?;
+        self.frame.shift_left(column);
+        Some(self.frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyphs() {
+        assert_eq!(glyph(' '), Frame::default());
+        assert_eq!(
+            glyph('1').rows(),
+            [0b00100, 0b01100, 0b00100, 0b00100, 0b01110]
+        );
+        assert_eq!(glyph('~'), Frame::new(GLYPHS[94]));
+        assert_eq!(glyph('\u{7F}'), glyph('?'));
+        assert_eq!(glyph('é'), glyph('?'));
+        assert_eq!(glyph('\n'), glyph('?'));
+    }
+
+    #[test]
+    fn scroll() {
+        let rightmost = Frame::new([0b00001; 5]);
+        assert_eq!(Scroll::new("Hi").next(), Some(rightmost));
+        assert_eq!(Scroll::new("Hi").nth(WIDTH - 1), Some(glyph('H')));
+        assert_eq!(Scroll::new("Hi").nth(2 * WIDTH), Some(glyph('i')));
+        assert_eq!(Scroll::new("Hi").last(), Some(Frame::default()));
+        assert_eq!(Scroll::new("Hi").count(), 2 * (WIDTH + 1) + WIDTH - 1);
+        assert_eq!(Scroll::new("").count(), WIDTH - 1);
+    }
+}
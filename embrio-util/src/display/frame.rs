@@ -0,0 +1,77 @@
+pub const WIDTH: usize = 5;
+pub const HEIGHT: usize = 5;
+
+/// An image for a 5x5 LED matrix.
+///
+/// Each row is stored with the leftmost LED as the highest of its 5 bits, so
+/// binary literals read the way they are displayed.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Frame {
+    rows: [u8; HEIGHT],
+}
+
+impl Frame {
+    pub const fn new(rows: [u8; HEIGHT]) -> Self {
+        Frame { rows }
+    }
+
+    pub fn rows(&self) -> [u8; HEIGHT] {
+        self.rows
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & bit(x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, on: bool) {
+        if on {
+            self.rows[y] |= bit(x);
+        } else {
+            self.rows[y] &= !bit(x);
+        }
+    }
+
+    /// Column `x` with bit `y` set for each lit LED.
+    pub(crate) fn column(&self, x: usize) -> u8 {
+        (0..HEIGHT)
+            .filter(|&y| self.get(x, y))
+            .fold(0, |column, y| column | (1 << y))
+    }
+
+    /// Moves everything one LED left, filling the rightmost column from
+    /// `column` as returned by [`column`](Frame::column).
+    pub(crate) fn shift_left(&mut self, column: u8) {
+        for (y, row) in self.rows.iter_mut().enumerate() {
+            *row = ((*row << 1) & 0b1_1111) | ((column >> y) & 1);
+        }
+    }
+}
+
+fn bit(x: usize) -> u8 {
+    0b1_0000 >> x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_set() {
+        let mut frame = Frame::default();
+        frame.set(0, 0, true);
+        frame.set(3, 4, true);
+        assert_eq!(frame.rows(), [0b10000, 0, 0, 0, 0b00010]);
+        assert!(frame.get(3, 4));
+        frame.set(0, 0, false);
+        assert!(!frame.get(0, 0));
+    }
+
+    #[test]
+    fn shift_left() {
+        let mut frame = Frame::new([0b10001, 0, 0, 0, 0b00001]);
+        frame.shift_left(0b00010);
+        assert_eq!(frame.rows(), [0b00010, 0b00001, 0, 0, 0b00010]);
+        assert_eq!(frame.column(4), 0b00010);
+        assert_eq!(frame.column(3), 0b10001);
+    }
+}
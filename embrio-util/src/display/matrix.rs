@@ -0,0 +1,55 @@
+use super::{Frame, HEIGHT, WIDTH};
+
+/// The electrical (row, column) driving the LED at each (y, x) position of a
+/// display wired as a matrix.
+pub type Layout = [[(usize, usize); WIDTH]; HEIGHT];
+
+/// The BBC micro:bit's display, wired as 3 rows and 9 columns.
+pub const MICROBIT: Layout = [
+    [(0, 0), (1, 3), (0, 1), (1, 4), (0, 2)],
+    [(2, 3), (2, 4), (2, 5), (2, 6), (2, 7)],
+    [(1, 1), (0, 8), (1, 2), (2, 8), (1, 0)],
+    [(0, 7), (0, 6), (0, 5), (0, 4), (0, 3)],
+    [(2, 2), (1, 6), (2, 0), (1, 5), (2, 1)],
+];
+
+impl Frame {
+    /// The columns to light for each electrical row of a display wired as
+    /// `layout`, bit `n` of `matrix[row]` is column `n`.
+    pub fn matrix(&self, layout: &Layout, matrix: &mut [u16]) {
+        for cols in matrix.iter_mut() {
+            *cols = 0;
+        }
+        for (y, layout) in layout.iter().enumerate() {
+            for (x, &(row, col)) in layout.iter().enumerate() {
+                if self.get(x, y) {
+                    matrix[row] |= 1 << col;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix_of(frame: &Frame) -> [u16; 3] {
+        let mut matrix = [0; 3];
+        frame.matrix(&MICROBIT, &mut matrix);
+        matrix
+    }
+
+    #[test]
+    fn microbit() {
+        let mut frame = Frame::default();
+        frame.set(0, 0, true);
+        assert_eq!(matrix_of(&frame), [0b1, 0, 0]);
+        frame.set(4, 4, true);
+        assert_eq!(matrix_of(&frame), [0b1, 0, 0b10]);
+
+        // Every LED has its own row and column
+        let full = matrix_of(&Frame::new([0b11111; HEIGHT]));
+        assert_eq!(full, [0x1FF, 0x7F, 0x1FF]);
+    }
+}
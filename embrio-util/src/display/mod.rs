@@ -0,0 +1,12 @@
+//! Images and text for 5x5 LED matrices, and the rows and columns they light
+//! on displays wired up as an electrical matrix.
+
+mod font;
+mod frame;
+mod matrix;
+
+pub use self::{
+    font::{glyph, Scroll},
+    frame::{Frame, HEIGHT, WIDTH},
+    matrix::{Layout, MICROBIT},
+};
@@ -0,0 +1,206 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::{gpio::Input, timer::Timer};
+use futures_core::stream::Stream;
+use futures_util::ready;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ButtonEvent {
+    Press,
+    Release,
+    LongPress,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Config {
+    /// The level the pin reads while the button is held down
+    pub active_level: bool,
+    /// How long the pin must stay at a new level before it is believed
+    pub debounce: Duration,
+    /// How long the button must be held before a `LongPress` is reported
+    pub long_press: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            active_level: false,
+            debounce: Duration::from_millis(10),
+            long_press: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A debounced [`Stream`] of [`ButtonEvent`]s from an input pin.
+///
+/// While idle this only waits on the pin, the timer is used to re-sample the
+/// pin after each change and to time long presses. A long press is timed from
+/// when the pin first changed, a glitch while held restarts it.
+pub struct Button<P: Input, T: Timer> {
+    pin: P,
+    timer: Option<T>,
+    timeout: Option<T::Timeout>,
+    config: Config,
+    pressed: bool,
+    settling: bool,
+    /// Whether `timeout` is timing a long press rather than a debounce.
+    holding: bool,
+    long_pressed: bool,
+}
+
+impl<P: Input, T: Timer> Button<P, T> {
+    pub fn new(pin: P, timer: T, config: Config) -> Self {
+        Button {
+            pressed: pin.state() == config.active_level,
+            pin,
+            timer: Some(timer),
+            timeout: None,
+            config,
+            settling: false,
+            holding: false,
+            long_pressed: false,
+        }
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+}
+
+impl<P: Input, T: Timer> Stream for Button<P, T> {
+    type Item = Result<ButtonEvent, T::Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // Safety: `pin` and `timeout` are only accessed through new pinned
+        // references, and `timeout` is only replaced by dropping it in place
+        let Button {
+            ref mut pin,
+            ref mut timer,
+            ref mut timeout,
+            ref config,
+            ref mut pressed,
+            ref mut settling,
+            ref mut holding,
+            ref mut long_pressed,
+        } = unsafe { Pin::get_unchecked_mut(self) };
+        let mut pin = unsafe { Pin::new_unchecked(pin) };
+
+        loop {
+            if let Some(pending) = timeout.as_mut() {
+                let mut pending = unsafe { Pin::new_unchecked(pending) };
+
+                if *holding {
+                    // Race the long press against the release
+                    let result = match pending.as_mut().poll(cx) {
+                        Poll::Ready(result) => result,
+                        Poll::Pending => {
+                            let released = !config.active_level;
+                            ready!(pin.as_mut().poll_level(cx, released));
+                            match T::cancel(pending.as_mut()) {
+                                Some(returned) => {
+                                    *holding = false;
+                                    *settling = true;
+                                    let debounce = config.debounce;
+                                    *timeout = Some(returned.timeout(debounce));
+                                    continue;
+                                }
+                                // Completed since it was polled, so the long
+                                // press still wins and has the timer
+                                None => ready!(pending.poll(cx)),
+                            }
+                        }
+                    };
+                    *timeout = None;
+                    *holding = false;
+                    *timer = Some(match result {
+                        Ok(returned) => returned,
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    });
+                    *long_pressed = true;
+                    return Poll::Ready(Some(Ok(ButtonEvent::LongPress)));
+                }
+
+                let result = ready!(pending.poll(cx));
+                *timeout = None;
+                let sampled = match result {
+                    Ok(returned) => {
+                        let sampled = pin.state() == config.active_level;
+                        *timer = Some(returned);
+                        sampled
+                    }
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                };
+
+                if sampled != *pressed {
+                    if *settling {
+                        *settling = false;
+                        *pressed = sampled;
+                        if sampled {
+                            *long_pressed = false;
+                            arm_long_press(timer, timeout, holding, config);
+                            return Poll::Ready(Some(Ok(ButtonEvent::Press)));
+                        } else {
+                            return Poll::Ready(Some(Ok(ButtonEvent::Release)));
+                        }
+                    }
+
+                    // Changed between samples while held, give it time to
+                    // settle before believing it
+                    *settling = true;
+                    let timer = timer.take().unwrap();
+                    *timeout = Some(timer.timeout(config.debounce));
+                    continue;
+                }
+
+                *settling = false;
+                if sampled && !*long_pressed {
+                    arm_long_press(timer, timeout, holding, config);
+                }
+
+                continue;
+            }
+
+            // A previous timeout failed and took the timer with it
+            if timer.is_none() {
+                return Poll::Ready(None);
+            }
+
+            let target = config.active_level != *pressed;
+            ready!(pin.as_mut().poll_level(cx, target));
+            *settling = true;
+            let timer = timer.take().unwrap();
+            *timeout = Some(timer.timeout(config.debounce));
+        }
+    }
+}
+
+/// The press was seen a debounce before it was reported, so that is taken off
+/// the long press.
+fn arm_long_press<T: Timer>(
+    timer: &mut Option<T>,
+    timeout: &mut Option<T::Timeout>,
+    holding: &mut bool,
+    config: &Config,
+) {
+    let remaining = config
+        .long_press
+        .checked_sub(config.debounce)
+        .unwrap_or_default();
+    *timeout = Some(timer.take().unwrap().timeout(remaining));
+    *holding = true;
+}
+
+impl<P: Input, T: Timer> Drop for Button<P, T> {
+    fn drop(&mut self) {
+        // Safety: the pin is dropped in place straight after
+        unsafe { Pin::new_unchecked(&mut self.pin) }.cancel_wait();
+    }
+}
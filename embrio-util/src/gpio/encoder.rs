@@ -0,0 +1,114 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use embrio_core::gpio::Input;
+use futures_core::stream::Stream;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Step {
+    Clockwise,
+    CounterClockwise,
+}
+
+// Indexed by `previous << 2 | current` where each state is `a << 1 | b`,
+// clockwise is taken to be `a` leading `b`. Transitions where both pins change
+// at once are invalid and ignored.
+const TRANSITIONS: [i8; 16] = [
+    0, -1, 1, 0, //
+    1, 0, 0, -1, //
+    -1, 0, 0, 1, //
+    0, 1, -1, 0, //
+];
+
+/// A [`Stream`] of [`Step`]s decoded from a quadrature encoder's pin pair.
+///
+/// Contact bounce on a single pin produces a transition and its reverse, which
+/// cancel out, so the pins need no separate debouncing.
+pub struct Encoder<A: Input, B: Input> {
+    a: A,
+    b: B,
+    state: u8,
+    count: i8,
+    transitions_per_step: i8,
+}
+
+impl<A: Input, B: Input> Encoder<A, B> {
+    /// Create an encoder that reports a step per full quadrature cycle (four
+    /// transitions), as is usual for detented encoders.
+    pub fn new(a: A, b: B) -> Self {
+        Self::with_transitions_per_step(a, b, 4)
+    }
+
+    pub fn with_transitions_per_step(
+        a: A,
+        b: B,
+        transitions_per_step: i8,
+    ) -> Self {
+        assert!(transitions_per_step > 0);
+        Encoder {
+            state: read(&a, &b),
+            a,
+            b,
+            count: 0,
+            transitions_per_step,
+        }
+    }
+}
+
+fn read(a: &impl Input, b: &impl Input) -> u8 {
+    (a.state() as u8) << 1 | b.state() as u8
+}
+
+impl<A: Input, B: Input> Stream for Encoder<A, B> {
+    type Item = Step;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // Safety: the pins are only accessed through new pinned references
+        let Encoder {
+            ref mut a,
+            ref mut b,
+            ref mut state,
+            ref mut count,
+            transitions_per_step,
+        } = *unsafe { Pin::get_unchecked_mut(self) };
+        let mut a = unsafe { Pin::new_unchecked(a) };
+        let mut b = unsafe { Pin::new_unchecked(b) };
+
+        loop {
+            // Poll both so that a change on either wakes us
+            let a_changed =
+                a.as_mut().poll_level(cx, *state & 0b10 == 0).is_ready();
+            let b_changed =
+                b.as_mut().poll_level(cx, *state & 0b01 == 0).is_ready();
+            if !a_changed && !b_changed {
+                return Poll::Pending;
+            }
+
+            let current = read(&*a, &*b);
+            *count += TRANSITIONS[(*state << 2 | current) as usize];
+            *state = current;
+
+            if *count >= transitions_per_step {
+                *count -= transitions_per_step;
+                return Poll::Ready(Some(Step::Clockwise));
+            }
+            if *count <= -transitions_per_step {
+                *count += transitions_per_step;
+                return Poll::Ready(Some(Step::CounterClockwise));
+            }
+        }
+    }
+}
+
+impl<A: Input, B: Input> Drop for Encoder<A, B> {
+    fn drop(&mut self) {
+        // Safety: the pins are dropped in place straight after
+        unsafe { Pin::new_unchecked(&mut self.a) }.cancel_wait();
+        unsafe { Pin::new_unchecked(&mut self.b) }.cancel_wait();
+    }
+}
@@ -0,0 +1,7 @@
+pub mod button;
+pub mod encoder;
+
+pub use self::{
+    button::{Button, ButtonEvent},
+    encoder::{Encoder, Step},
+};
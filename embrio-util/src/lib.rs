@@ -3,11 +3,19 @@
     arbitrary_self_types,
     const_fn,
     core_intrinsics,
+    generators,
     in_band_lifetimes,
     never_type,
     specialization
 )]
 
+pub mod adc;
+pub mod display;
 pub mod fmt;
+pub mod gpio;
 pub mod io;
+pub mod rgb;
+pub mod spi;
+pub mod storage;
 pub mod utils;
+pub mod watchdog;
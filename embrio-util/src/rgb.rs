@@ -0,0 +1,249 @@
+//! Colours for RGB LEDs, and sequences of them played one colour per tick of
+//! a timer. Sequences are plain iterators so can be combined, e.g. a fade in
+//! chained with a fade out and cycled to breathe.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::{pwm::Pwm, timer::Timer};
+use futures_core::stream::Stream;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+
+/// Each component is a brightness from `0` (off) to `255` (full).
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Something that can show a [`Color`].
+pub trait Light {
+    fn set(&mut self, color: Color);
+}
+
+/// An RGB LED on three channels of a [`Pwm`], lit while the output is
+/// inactive as on boards that wire their LEDs to the supply.
+#[derive(Debug)]
+pub struct RgbPwm<P: Pwm> {
+    pwm: P,
+    channels: [P::Channel; 3],
+}
+
+/// Fades from one colour to another over a number of steps, ending on the
+/// target colour.
+#[derive(Debug, Clone)]
+pub struct Fade {
+    from: Color,
+    to: Color,
+    step: u32,
+    steps: u32,
+}
+
+/// Alternates between a colour and off, a number of times.
+#[derive(Debug, Clone)]
+pub struct Blink {
+    color: Color,
+    remaining: u32,
+}
+
+pin_project! {
+    /// Shows each colour on a [`Light`] for one tick of an interval,
+    /// completing after the last colour has been shown for its tick.
+    pub struct Play<'a, L, T: Timer, I> {
+        light: &'a mut L,
+        #[pin]
+        interval: T::Interval,
+        colors: I,
+        started: bool,
+    }
+}
+
+impl Color {
+    pub const OFF: Color = Color::new(0, 0, 0);
+    pub const RED: Color = Color::new(255, 0, 0);
+    pub const GREEN: Color = Color::new(0, 255, 0);
+    pub const BLUE: Color = Color::new(0, 0, 255);
+    pub const WHITE: Color = Color::new(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    /// The colour `step` of `steps` along the way to `to`.
+    fn lerp(self, to: Color, step: u32, steps: u32) -> Color {
+        let component = |from: u8, to: u8| {
+            let (from, to) = (i64::from(from), i64::from(to));
+            (from + (to - from) * i64::from(step) / i64::from(steps)) as u8
+        };
+        Color::new(
+            component(self.r, to.r),
+            component(self.g, to.g),
+            component(self.b, to.b),
+        )
+    }
+}
+
+impl<P: Pwm> RgbPwm<P> {
+    /// `channels` are the red, green and blue channels, which are enabled
+    /// and turned off.
+    pub fn new(pwm: P, channels: [P::Channel; 3]) -> Self {
+        let mut rgb = RgbPwm { pwm, channels };
+        rgb.set(Color::OFF);
+        for &channel in &rgb.channels {
+            rgb.pwm.enable(channel);
+        }
+        rgb
+    }
+
+    pub fn free(self) -> P {
+        self.pwm
+    }
+}
+
+impl<P: Pwm> Light for RgbPwm<P> {
+    fn set(&mut self, color: Color) {
+        let max = self.pwm.max_duty();
+        for (&channel, &level) in
+            self.channels.iter().zip(&[color.r, color.g, color.b])
+        {
+            self.pwm.set_duty(channel, inverted_duty(level, max));
+        }
+    }
+}
+
+fn inverted_duty(level: u8, max: u32) -> u32 {
+    max - (u64::from(level) * u64::from(max) / 255) as u32
+}
+
+/// `steps` colours, the first is one step on from `from`.
+pub fn fade(from: Color, to: Color, steps: u32) -> Fade {
+    Fade {
+        from,
+        to,
+        step: 0,
+        steps,
+    }
+}
+
+/// `count` pairs of `color` then off.
+pub fn blink(color: Color, count: u32) -> Blink {
+    Blink {
+        color,
+        remaining: count * 2,
+    }
+}
+
+pub fn play<L: Light, T: Timer, I: Iterator<Item = Color>>(
+    light: &mut L,
+    timer: T,
+    colors: I,
+    step: Duration,
+) -> Play<'_, L, T, I> {
+    Play {
+        light,
+        interval: timer.interval(step),
+        colors,
+        started: false,
+    }
+}
+
+impl Iterator for Fade {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        if self.step == self.steps {
+            return None;
+        }
+        self.step += 1;
+        Some(self.from.lerp(self.to, self.step, self.steps))
+    }
+}
+
+impl Iterator for Blink {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        if self.remaining % 2 == 1 {
+            Some(self.color)
+        } else {
+            Some(Color::OFF)
+        }
+    }
+}
+
+impl<'a, L: Light, T: Timer, I: Iterator<Item = Color>> Future
+    for Play<'a, L, T, I>
+{
+    type Output = Result<(), T::Error>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if !*this.started {
+            *this.started = true;
+            match this.colors.next() {
+                Some(color) => this.light.set(color),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        loop {
+            match ready!(this.interval.as_mut().poll_next(cx)) {
+                Some(Ok(())) => match this.colors.next() {
+                    Some(color) => this.light.set(color),
+                    None => return Poll::Ready(Ok(())),
+                },
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades() {
+        let mut fade = fade(Color::OFF, Color::new(255, 100, 0), 4);
+        assert_eq!(fade.next(), Some(Color::new(63, 25, 0)));
+        assert_eq!(fade.next(), Some(Color::new(127, 50, 0)));
+        assert_eq!(fade.nth(1), Some(Color::new(255, 100, 0)));
+        assert_eq!(fade.next(), None);
+
+        let mut fade = super::fade(Color::WHITE, Color::RED, 2);
+        assert_eq!(fade.next(), Some(Color::new(255, 128, 128)));
+        assert_eq!(fade.next(), Some(Color::RED));
+    }
+
+    #[test]
+    fn blinks() {
+        let mut blink = blink(Color::GREEN, 2);
+        assert_eq!(blink.next(), Some(Color::GREEN));
+        assert_eq!(blink.next(), Some(Color::OFF));
+        assert_eq!(blink.next(), Some(Color::GREEN));
+        assert_eq!(blink.next(), Some(Color::OFF));
+        assert_eq!(blink.next(), None);
+    }
+
+    #[test]
+    fn duty() {
+        assert_eq!(inverted_duty(0, 1000), 1000);
+        assert_eq!(inverted_duty(255, 1000), 0);
+        assert_eq!(inverted_duty(51, 1000), 800);
+    }
+}
@@ -0,0 +1,48 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use embrio_core::{
+    gpio::Output,
+    spi::{Bus, Device},
+};
+
+/// A [`Device`] that is the only user of its bus, selected by driving `cs`
+/// low.
+pub struct ExclusiveDevice<B, CS> {
+    bus: B,
+    cs: CS,
+}
+
+impl<B: Bus, CS: Output> ExclusiveDevice<B, CS> {
+    pub fn new(bus: B, cs: CS) -> Self {
+        cs.set_high();
+        ExclusiveDevice { bus, cs }
+    }
+
+    pub fn into_inner(self) -> (B, CS) {
+        (self.bus, self.cs)
+    }
+}
+
+impl<B: Bus, CS: Output> Device for ExclusiveDevice<B, CS> {
+    type Bus = B;
+
+    fn poll_select(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), B::Error>> {
+        self.cs.set_low();
+        Poll::Ready(Ok(()))
+    }
+
+    fn bus(self: Pin<&mut Self>) -> Pin<&mut B> {
+        // Safety: `bus` is structurally pinned and never moved out of
+        unsafe { self.map_unchecked_mut(|this| &mut this.bus) }
+    }
+
+    fn deselect(self: Pin<&mut Self>) {
+        self.cs.set_high();
+    }
+}
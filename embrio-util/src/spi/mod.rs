@@ -0,0 +1,7 @@
+mod exclusive;
+mod shared;
+
+pub use self::{
+    exclusive::ExclusiveDevice,
+    shared::{SharedBus, SharedDevice, MAX_DEVICES},
+};
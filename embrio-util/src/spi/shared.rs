@@ -0,0 +1,125 @@
+use core::{
+    cell::{Cell, UnsafeCell},
+    marker::Unpin,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+use embrio_core::{
+    gpio::Output,
+    spi::{Bus, Device},
+};
+
+/// Maximum number of [`SharedDevice`]s on a [`SharedBus`] at once.
+pub const MAX_DEVICES: usize = 32;
+
+/// Lets multiple [`SharedDevice`]s take turns using a single bus, a device
+/// holds the bus from being selected until it is deselected.
+pub struct SharedBus<B> {
+    bus: UnsafeCell<B>,
+    locked: Cell<bool>,
+    /// A bit per slot in `waiters` in use by a device.
+    devices: Cell<u32>,
+    /// The devices waiting for the bus, all woken when it is unlocked.
+    waiters: [Cell<Option<Waker>>; MAX_DEVICES],
+}
+
+/// Holds one of the [`MAX_DEVICES`] slots on its bus until dropped.
+pub struct SharedDevice<'a, B, CS: Output> {
+    shared: &'a SharedBus<B>,
+    cs: CS,
+    slot: usize,
+    selected: bool,
+}
+
+impl<B: Bus + Unpin> SharedBus<B> {
+    pub fn new(bus: B) -> Self {
+        SharedBus {
+            bus: UnsafeCell::new(bus),
+            locked: Cell::new(false),
+            devices: Cell::new(0),
+            waiters: Default::default(),
+        }
+    }
+
+    /// Panics if [`MAX_DEVICES`] are already on this bus.
+    pub fn device<CS: Output>(&self, cs: CS) -> SharedDevice<'_, B, CS> {
+        let free = !self.devices.get();
+        assert!(free != 0, "too many devices on the bus");
+        let slot = free.trailing_zeros() as usize;
+        self.devices.set(self.devices.get() | 1 << slot);
+        cs.set_high();
+        SharedDevice {
+            shared: self,
+            cs,
+            slot,
+            selected: false,
+        }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.bus.into_inner()
+    }
+}
+
+impl<B> SharedBus<B> {
+    fn unlock(&self) {
+        self.locked.set(false);
+        for waiter in &self.waiters {
+            if let Some(waker) = waiter.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<'a, B: Bus + Unpin, CS: Output> Device for SharedDevice<'a, B, CS> {
+    type Bus = B;
+
+    fn poll_select(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), B::Error>> {
+        // Safety: nothing is structurally pinned
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+        if !this.selected {
+            if this.shared.locked.get() {
+                this.shared.waiters[this.slot].set(Some(cx.waker().clone()));
+                return Poll::Pending;
+            }
+            this.shared.locked.set(true);
+            this.selected = true;
+            this.cs.set_low();
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn bus(self: Pin<&mut Self>) -> Pin<&mut B> {
+        assert!(self.selected);
+        // Safety: the bus is only accessed by the device that has it locked,
+        // and `SharedBus` is `!Sync` so that is always from this thread
+        Pin::new(unsafe { &mut *self.shared.bus.get() })
+    }
+
+    fn deselect(self: Pin<&mut Self>) {
+        // Safety: nothing is structurally pinned
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+        if this.selected {
+            this.cs.set_high();
+            this.selected = false;
+            this.shared.unlock();
+        }
+    }
+}
+
+impl<'a, B, CS: Output> Drop for SharedDevice<'a, B, CS> {
+    fn drop(&mut self) {
+        if self.selected {
+            self.cs.set_high();
+            self.shared.unlock();
+        }
+        self.shared.waiters[self.slot].set(None);
+        let devices = &self.shared.devices;
+        devices.set(devices.get() & !(1 << self.slot));
+    }
+}
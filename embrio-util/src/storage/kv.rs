@@ -0,0 +1,591 @@
+//! A log structured key-value store for small values that change rarely, such
+//! as configuration and calibration data.
+//!
+//! Records are appended to the active page, when it fills the next free page
+//! becomes active and if that was the last free page the oldest page is
+//! garbage collected by copying its live records forward then erasing it.
+//! Writes are spread over every page in turn, and each record is committed by
+//! a final write after its data so an interrupted [`set`](Store::set) or
+//! [`remove`](Store::remove) leaves either the old or the new value.
+//!
+//! Interrupted writes and erases are assumed to have taken effect in address
+//! order, with at most one partially changed byte.
+
+use core::{cmp, pin::Pin};
+
+use embrio_async::embrio_async;
+use embrio_core::storage::{self, Storage};
+
+pub const MAX_KEY_LEN: usize = 255;
+
+pub const MAX_VALUE_LEN: usize = 0xFFFF;
+
+const PAGE_MAGIC: [u8; 4] = *b"KVS1";
+
+// Neither has all the set bits of the other, so a partially written kind is
+// never mistaken for the other kind
+const VALUE: u8 = 0x0F;
+const TOMBSTONE: u8 = 0xF0;
+
+const ERASED: u8 = 0xFF;
+
+/// Data is moved through stack buffers of this size, the storage write size
+/// must divide it.
+const CHUNK: usize = 32;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error<E> {
+    Storage(storage::Error<E>),
+    /// The key or value can never fit in a page, or the value doesn't fit in
+    /// the buffer given to [`get`](Store::get).
+    TooLong,
+    /// There is no space for the record even after garbage collection.
+    Full,
+}
+
+impl<E> From<storage::Error<E>> for Error<E> {
+    fn from(err: storage::Error<E>) -> Self {
+        Error::Storage(err)
+    }
+}
+
+#[derive(Debug)]
+pub struct Store<S> {
+    storage: S,
+    pages: usize,
+    page_size: usize,
+    write_size: usize,
+    mounted: bool,
+    /// The page records are appended to and the offset of the next record.
+    active: Option<(usize, usize)>,
+    next_seq: u32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Record {
+    offset: usize,
+    key_len: usize,
+    value_len: usize,
+    kind: u8,
+    committed: bool,
+}
+
+enum Entry {
+    /// Nothing more has been written to the page.
+    End,
+    /// A header that was interrupted while being written.
+    Torn,
+    /// A header that can't have been written by this store, nothing after it
+    /// can be trusted.
+    Corrupt,
+    Record(Record),
+}
+
+#[derive(Copy, Clone)]
+enum Data<'a> {
+    Slices(&'a [u8], &'a [u8]),
+    /// Already in storage at this address.
+    Stored(usize),
+}
+
+impl<S: Storage + Unpin> Store<S> {
+    /// A store covering all of `storage`, which must be at least two erase
+    /// units, byte readable, and have a write size dividing 32.
+    ///
+    /// Erased storage is an empty store, storage with other contents is
+    /// treated as garbage and erased as it is needed. Nothing is read until
+    /// the first operation.
+    pub fn new(storage: S) -> Self {
+        assert_eq!(storage.read_size(), 1);
+        let write_size = storage.write_size();
+        assert!(write_size <= CHUNK && CHUNK % write_size == 0);
+        let page_size = storage.erase_size();
+        let pages = storage.capacity() / page_size;
+        assert!(pages >= 2);
+
+        let store = Store {
+            storage,
+            pages,
+            page_size,
+            write_size,
+            mounted: false,
+            active: None,
+            next_seq: 0,
+        };
+        assert!(store.page_header_len() + store.record_len(0, 0) <= page_size);
+        store
+    }
+
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    /// Read the value of `key` into `buf`, returning its length.
+    #[embrio_async]
+    pub async fn get(
+        &mut self,
+        key: &[u8],
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Error<S::Error>> {
+        self.mount().await?;
+        let (page, record) = match self.find(key).await? {
+            Some((page, record)) if record.kind == VALUE => (page, record),
+            _ => return Ok(None),
+        };
+        let buf = buf.get_mut(..record.value_len).ok_or(Error::TooLong)?;
+        let offset = record.offset + self.header_len() + record.key_len;
+        let address = self.address(page, offset);
+        self.storage().read(address, buf).await?;
+        Ok(Some(record.value_len))
+    }
+
+    #[embrio_async]
+    pub async fn set(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error<S::Error>> {
+        self.mount().await?;
+        self.append(VALUE, key, value).await
+    }
+
+    #[embrio_async]
+    pub async fn remove(&mut self, key: &[u8]) -> Result<(), Error<S::Error>> {
+        self.mount().await?;
+        match self.find(key).await? {
+            Some((_, record)) if record.kind == VALUE => {
+                self.append(TOMBSTONE, key, &[]).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn storage(&mut self) -> Pin<&mut S> {
+        Pin::new(&mut self.storage)
+    }
+
+    fn align(&self, len: usize) -> usize {
+        (len + self.write_size - 1) / self.write_size * self.write_size
+    }
+
+    /// The sequence number between two copies of the magic, an interrupted
+    /// write loses the last and an interrupted erase the first.
+    fn page_header_len(&self) -> usize {
+        self.align(12)
+    }
+
+    /// The key length, the value length then the kind.
+    fn header_len(&self) -> usize {
+        self.align(4)
+    }
+
+    /// The header, the key and value, then the commit marker.
+    fn record_len(&self, key_len: usize, value_len: usize) -> usize {
+        self.header_len() + self.align(key_len + value_len) + self.write_size
+    }
+
+    fn address(&self, page: usize, offset: usize) -> usize {
+        page * self.page_size + offset
+    }
+
+    /// Find the active page, finishing an interrupted garbage collection.
+    #[embrio_async]
+    async fn mount(&mut self) -> Result<(), Error<S::Error>> {
+        if self.mounted {
+            return Ok(());
+        }
+
+        let mut newest: Option<(usize, u32)> = None;
+        let mut free = 0;
+        for page in 0..self.pages {
+            match self.page_seq(page).await? {
+                Some(seq) if newest.map_or(true, |(_, max)| seq > max) => {
+                    newest = Some((page, seq))
+                }
+                Some(_) => {}
+                None => free += 1,
+            }
+        }
+
+        if let Some((page, seq)) = newest {
+            self.next_seq = seq.wrapping_add(1);
+            if free == 0 {
+                // Interrupted while collecting the oldest page into the
+                // newest, which holds nothing else so can be started again
+                self.erase_page(page).await?;
+                self.switch().await?;
+            } else {
+                let end = self.end_of(page).await?;
+                self.active = Some((page, end));
+            }
+        }
+
+        self.mounted = true;
+        Ok(())
+    }
+
+    #[embrio_async]
+    async fn page_seq(
+        &mut self,
+        page: usize,
+    ) -> Result<Option<u32>, Error<S::Error>> {
+        let mut header = [0; 12];
+        let address = self.address(page, 0);
+        self.storage().read(address, &mut header).await?;
+        if header[..4] != PAGE_MAGIC || header[8..] != PAGE_MAGIC {
+            return Ok(None);
+        }
+        Ok(Some(u32::from_le_bytes([
+            header[4], header[5], header[6], header[7],
+        ])))
+    }
+
+    /// The page with the highest sequence number below `below`.
+    #[embrio_async]
+    async fn older_page(
+        &mut self,
+        below: Option<u32>,
+    ) -> Result<Option<(usize, u32)>, Error<S::Error>> {
+        let mut found: Option<(usize, u32)> = None;
+        for page in 0..self.pages {
+            if let Some(seq) = self.page_seq(page).await? {
+                if below.map_or(true, |below| seq < below)
+                    && found.map_or(true, |(_, max)| seq > max)
+                {
+                    found = Some((page, seq));
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    #[embrio_async]
+    async fn oldest_page(&mut self) -> Result<Option<usize>, Error<S::Error>> {
+        let mut found: Option<(usize, u32)> = None;
+        for page in 0..self.pages {
+            if let Some(seq) = self.page_seq(page).await? {
+                if found.map_or(true, |(_, min)| seq < min) {
+                    found = Some((page, seq));
+                }
+            }
+        }
+        Ok(found.map(|(page, _)| page))
+    }
+
+    #[embrio_async]
+    async fn free_page(&mut self) -> Result<Option<usize>, Error<S::Error>> {
+        for page in 0..self.pages {
+            if self.page_seq(page).await?.is_none() {
+                return Ok(Some(page));
+            }
+        }
+        Ok(None)
+    }
+
+    #[embrio_async]
+    async fn is_erased(
+        &mut self,
+        page: usize,
+    ) -> Result<bool, Error<S::Error>> {
+        let mut chunk = [0; CHUNK];
+        let mut offset = 0;
+        while offset < self.page_size {
+            let len = cmp::min(CHUNK, self.page_size - offset);
+            let address = self.address(page, offset);
+            self.storage().read(address, &mut chunk[..len]).await?;
+            if chunk[..len].iter().any(|&byte| byte != ERASED) {
+                return Ok(false);
+            }
+            offset += len;
+        }
+        Ok(true)
+    }
+
+    #[embrio_async]
+    async fn erase_page(&mut self, page: usize) -> Result<(), Error<S::Error>> {
+        let address = self.address(page, 0);
+        let len = self.page_size;
+        self.storage().erase(address, len).await?;
+        Ok(())
+    }
+
+    #[embrio_async]
+    async fn entry(
+        &mut self,
+        page: usize,
+        offset: usize,
+    ) -> Result<Entry, Error<S::Error>> {
+        if offset + self.header_len() > self.page_size {
+            return Ok(Entry::End);
+        }
+        let mut header = [0; 4];
+        let address = self.address(page, offset);
+        self.storage().read(address, &mut header).await?;
+        if header.iter().all(|&byte| byte == ERASED) {
+            return Ok(Entry::End);
+        }
+        let kind = header[3];
+        if kind != VALUE && kind != TOMBSTONE {
+            return Ok(Entry::Torn);
+        }
+
+        let key_len = usize::from(header[0]);
+        let value_len = usize::from(u16::from_le_bytes([header[1], header[2]]));
+        let len = self.record_len(key_len, value_len);
+        if offset + len > self.page_size {
+            return Ok(Entry::Corrupt);
+        }
+
+        let mut commit = [0; CHUNK];
+        let commit = &mut commit[..self.write_size];
+        let address = self.address(page, offset + len - self.write_size);
+        self.storage().read(address, commit).await?;
+        Ok(Entry::Record(Record {
+            offset,
+            key_len,
+            value_len,
+            kind,
+            committed: commit.iter().all(|&byte| byte == 0),
+        }))
+    }
+
+    /// Where the next record can be written.
+    #[embrio_async]
+    async fn end_of(&mut self, page: usize) -> Result<usize, Error<S::Error>> {
+        let mut offset = self.page_header_len();
+        loop {
+            match self.entry(page, offset).await? {
+                Entry::End => return Ok(offset),
+                Entry::Torn => offset += self.header_len(),
+                Entry::Corrupt => return Ok(self.page_size),
+                Entry::Record(record) => {
+                    offset += self.record_len(record.key_len, record.value_len)
+                }
+            }
+        }
+    }
+
+    #[embrio_async]
+    async fn key_matches(
+        &mut self,
+        page: usize,
+        record: Record,
+        key: &[u8],
+    ) -> Result<bool, Error<S::Error>> {
+        if record.key_len != key.len() {
+            return Ok(false);
+        }
+        let mut chunk = [0; CHUNK];
+        let start = self.address(page, record.offset + self.header_len());
+        for (i, expected) in key.chunks(CHUNK).enumerate() {
+            let chunk = &mut chunk[..expected.len()];
+            self.storage().read(start + i * CHUNK, chunk).await?;
+            if chunk != expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The latest committed record for `key`.
+    #[embrio_async]
+    async fn find(
+        &mut self,
+        key: &[u8],
+    ) -> Result<Option<(usize, Record)>, Error<S::Error>> {
+        let mut below = None;
+        while let Some((page, seq)) = self.older_page(below).await? {
+            below = Some(seq);
+            let mut found = None;
+            let mut offset = self.page_header_len();
+            loop {
+                let record = match self.entry(page, offset).await? {
+                    Entry::End | Entry::Corrupt => break,
+                    Entry::Torn => {
+                        offset += self.header_len();
+                        continue;
+                    }
+                    Entry::Record(record) => record,
+                };
+                offset += self.record_len(record.key_len, record.value_len);
+                if record.committed
+                    && self.key_matches(page, record, key).await?
+                {
+                    found = Some(record);
+                }
+            }
+            if let Some(record) = found {
+                return Ok(Some((page, record)));
+            }
+        }
+        Ok(None)
+    }
+
+    #[embrio_async]
+    async fn write_record(
+        &mut self,
+        kind: u8,
+        key_len: usize,
+        value_len: usize,
+        data: Data<'_>,
+    ) -> Result<(), Error<S::Error>> {
+        let len = self.record_len(key_len, value_len);
+        let (page, offset) = self.active.unwrap();
+        assert!(offset + len <= self.page_size);
+        // Move on first so a failed write is never written over
+        self.active = Some((page, offset + len));
+
+        let address = self.address(page, offset);
+        let header_len = self.header_len();
+        let mut header = [ERASED; CHUNK];
+        header[0] = key_len as u8;
+        header[1..3].copy_from_slice(&(value_len as u16).to_le_bytes());
+        header[3] = kind;
+        self.storage().write(address, &header[..header_len]).await?;
+
+        let total = key_len + value_len;
+        let mut done = 0;
+        while done < total {
+            let len = cmp::min(CHUNK, total - done);
+            let mut chunk = [ERASED; CHUNK];
+            match data {
+                Data::Slices(key, value) => {
+                    for (i, byte) in chunk[..len].iter_mut().enumerate() {
+                        let j = done + i;
+                        *byte = if j < key.len() {
+                            key[j]
+                        } else {
+                            value[j - key.len()]
+                        };
+                    }
+                }
+                Data::Stored(from) => {
+                    self.storage().read(from + done, &mut chunk[..len]).await?;
+                }
+            }
+            let chunk = &chunk[..self.align(len)];
+            let at = address + header_len + done;
+            self.storage().write(at, chunk).await?;
+            done += len;
+        }
+
+        let commit = [0; CHUNK];
+        let commit = &commit[..self.write_size];
+        let at = address + header_len + self.align(total);
+        self.storage().write(at, commit).await?;
+        Ok(())
+    }
+
+    /// On failure the store is mounted again before the next operation, so
+    /// nothing is appended after a torn record and an interrupted collection
+    /// is restarted.
+    #[embrio_async]
+    async fn append(
+        &mut self,
+        kind: u8,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error<S::Error>> {
+        let result = self.try_append(kind, key, value).await;
+        if let Err(Error::Storage(_)) = result {
+            self.mounted = false;
+            self.active = None;
+        }
+        result
+    }
+
+    #[embrio_async]
+    async fn try_append(
+        &mut self,
+        kind: u8,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error<S::Error>> {
+        let len = self.record_len(key.len(), value.len());
+        if key.len() > MAX_KEY_LEN
+            || value.len() > MAX_VALUE_LEN
+            || self.page_header_len() + len > self.page_size
+        {
+            return Err(Error::TooLong);
+        }
+
+        // Collecting every page once without making space means it's full
+        for _ in 0..self.pages {
+            if let Some((_, offset)) = self.active {
+                if offset + len <= self.page_size {
+                    let data = Data::Slices(key, value);
+                    return self
+                        .write_record(kind, key.len(), value.len(), data)
+                        .await;
+                }
+            }
+            self.switch().await?;
+        }
+        Err(Error::Full)
+    }
+
+    /// Start a new active page, collecting the oldest page if there would be
+    /// no free pages left.
+    #[embrio_async]
+    async fn switch(&mut self) -> Result<(), Error<S::Error>> {
+        let page = match self.free_page().await? {
+            Some(page) => page,
+            None => return Err(Error::Full),
+        };
+        self.active = None;
+        if !self.is_erased(page).await? {
+            self.erase_page(page).await?;
+        }
+
+        let seq = self.next_seq;
+        self.next_seq = seq.wrapping_add(1);
+        let mut header = [ERASED; CHUNK];
+        header[..4].copy_from_slice(&PAGE_MAGIC);
+        header[4..8].copy_from_slice(&seq.to_le_bytes());
+        header[8..12].copy_from_slice(&PAGE_MAGIC);
+        let header_len = self.page_header_len();
+        let address = self.address(page, 0);
+        self.storage().write(address, &header[..header_len]).await?;
+        self.active = Some((page, header_len));
+
+        if self.free_page().await?.is_none() {
+            self.collect().await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the live records of the oldest page to the active page, then
+    /// erase it.
+    #[embrio_async]
+    async fn collect(&mut self) -> Result<(), Error<S::Error>> {
+        let victim = self.oldest_page().await?.unwrap();
+        let header_len = self.header_len();
+        let mut offset = self.page_header_len();
+        loop {
+            let record = match self.entry(victim, offset).await? {
+                Entry::End | Entry::Corrupt => break,
+                Entry::Torn => {
+                    offset += header_len;
+                    continue;
+                }
+                Entry::Record(record) => record,
+            };
+            offset += self.record_len(record.key_len, record.value_len);
+            if !record.committed || record.kind != VALUE {
+                continue;
+            }
+
+            let mut key = [0; MAX_KEY_LEN];
+            let key = &mut key[..record.key_len];
+            let data = self.address(victim, record.offset + header_len);
+            self.storage().read(data, key).await?;
+            if self.find(key).await? != Some((victim, record)) {
+                continue;
+            }
+            let data = Data::Stored(data);
+            self.write_record(VALUE, record.key_len, record.value_len, data)
+                .await?;
+        }
+        self.erase_page(victim).await
+    }
+}
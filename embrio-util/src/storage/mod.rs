@@ -0,0 +1,4 @@
+pub mod kv;
+mod ram;
+
+pub use self::ram::{NotErased, RamStorage};
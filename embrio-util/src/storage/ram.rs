@@ -0,0 +1,105 @@
+use core::{
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use embrio_core::storage::{check, Error, Storage};
+
+/// A write to bytes that have been written since they were last erased.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NotErased;
+
+/// [`Storage`] in a RAM buffer, for testing code that uses flash on the host.
+///
+/// Writes are checked against the flash rules as far as possible, a write
+/// fails if any of its bytes are not `0xFF`.
+#[derive(Debug)]
+pub struct RamStorage<'a> {
+    memory: &'a mut [u8],
+    write_size: usize,
+    erase_size: usize,
+}
+
+impl<'a> RamStorage<'a> {
+    /// The length of `memory` must be a multiple of `erase_size`, which must
+    /// be a multiple of `write_size`. Its initial contents are kept.
+    pub fn new(
+        memory: &'a mut [u8],
+        write_size: usize,
+        erase_size: usize,
+    ) -> Self {
+        assert!(write_size > 0 && erase_size % write_size == 0);
+        assert!(memory.len() % erase_size == 0);
+        RamStorage {
+            memory,
+            write_size,
+            erase_size,
+        }
+    }
+
+    pub fn memory(&self) -> &[u8] {
+        self.memory
+    }
+}
+
+impl<'a> Storage for RamStorage<'a> {
+    type Error = NotErased;
+
+    fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    fn read_size(&self) -> usize {
+        1
+    }
+
+    fn write_size(&self) -> usize {
+        self.write_size
+    }
+
+    fn erase_size(&self) -> usize {
+        self.erase_size
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Poll<Result<(), Error<NotErased>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), 1, offset, buf.len())?;
+        buf.copy_from_slice(&this.memory[offset..offset + buf.len()]);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        data: &[u8],
+    ) -> Poll<Result<(), Error<NotErased>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), this.write_size, offset, data.len())?;
+        let target = &mut this.memory[offset..offset + data.len()];
+        if target.iter().any(|&byte| byte != 0xFF) {
+            return Poll::Ready(Err(Error::Other(NotErased)));
+        }
+        target.copy_from_slice(data);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_erase(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        len: usize,
+    ) -> Poll<Result<(), Error<NotErased>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), this.erase_size, offset, len)?;
+        for byte in &mut this.memory[offset..offset + len] {
+            *byte = 0xFF;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,133 @@
+use core::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use embrio_core::{timer::Timer, watchdog::Watchdog};
+use futures_core::stream::Stream;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+
+/// Maximum number of signals registered with a [`Health`] at once.
+pub const MAX_SIGNALS: usize = 32;
+
+/// A set of signals that must all check in between feeds of a watchdog by a
+/// [`Feeder`].
+#[derive(Debug, Default)]
+pub struct Health {
+    registered: Cell<u32>,
+    checked_in: Cell<u32>,
+}
+
+/// Registered with a [`Health`] until dropped, whatever owns this should
+/// [`check_in`](Signal::check_in) each time it makes progress.
+#[derive(Debug)]
+pub struct Signal<'a> {
+    health: &'a Health,
+    mask: u32,
+}
+
+pin_project! {
+    /// Feeds a watchdog on each tick of an interval, but only if every
+    /// signal registered with its [`Health`] has checked in since it last fed
+    /// it.
+    ///
+    /// The period should be shorter than the watchdog timeout, and each
+    /// signal should check in more often than the period. A signal that
+    /// stops checking in, such as from a task stuck waiting on a future that
+    /// is never woken, lets the watchdog reset the device.
+    pub struct Feeder<'a, W: Watchdog, T: Timer> {
+        watchdog: W,
+        health: &'a Health,
+        #[pin]
+        interval: T::Interval,
+    }
+}
+
+impl Health {
+    pub const fn new() -> Self {
+        Health {
+            registered: Cell::new(0),
+            checked_in: Cell::new(0),
+        }
+    }
+
+    /// Returns `None` if [`MAX_SIGNALS`] are already registered.
+    pub fn register(&self) -> Option<Signal<'_>> {
+        let free = !self.registered.get();
+        if free == 0 {
+            return None;
+        }
+        let mask = 1 << free.trailing_zeros();
+        self.registered.set(self.registered.get() | mask);
+        self.checked_in.set(self.checked_in.get() & !mask);
+        Some(Signal { health: self, mask })
+    }
+
+    /// Whether every registered signal has checked in, clearing the check
+    /// ins if so.
+    fn take_healthy(&self) -> bool {
+        let registered = self.registered.get();
+        if self.checked_in.get() & registered != registered {
+            return false;
+        }
+        self.checked_in.set(0);
+        true
+    }
+}
+
+impl<'a> Signal<'a> {
+    pub fn check_in(&self) {
+        let checked_in = &self.health.checked_in;
+        checked_in.set(checked_in.get() | self.mask);
+    }
+}
+
+impl<'a> Drop for Signal<'a> {
+    fn drop(&mut self) {
+        let registered = &self.health.registered;
+        registered.set(registered.get() & !self.mask);
+    }
+}
+
+impl<'a, W: Watchdog, T: Timer> Feeder<'a, W, T> {
+    pub fn new(
+        watchdog: W,
+        health: &'a Health,
+        timer: T,
+        period: Duration,
+    ) -> Self {
+        Feeder {
+            watchdog,
+            health,
+            interval: timer.interval(period),
+        }
+    }
+}
+
+/// Runs until the interval ends.
+impl<'a, W: Watchdog, T: Timer> Future for Feeder<'a, W, T> {
+    type Output = Result<(), T::Error>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            match ready!(this.interval.as_mut().poll_next(cx)) {
+                Some(Ok(())) => {
+                    if this.health.take_healthy() {
+                        this.watchdog.feed();
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use embrio_core::adc::Adc;
+use embrio_native::timer::StreamTimer;
+use embrio_util::adc::{millivolts, Error, Samples};
+use futures::{
+    executor::block_on,
+    stream::{self, Iter, StreamExt},
+    task::noop_waker_ref,
+};
+
+type Ticks = Iter<std::vec::IntoIter<Result<(), &'static str>>>;
+
+/// Takes a pending poll to convert, each reading is one more than the last
+/// plus the channel number.
+struct MockAdc {
+    next: u16,
+    converting: bool,
+}
+
+impl Adc for MockAdc {
+    type Channel = u16;
+
+    type Error = ();
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        channel: u16,
+    ) -> Poll<Result<u16, ()>> {
+        let this = Pin::get_mut(self);
+        if this.converting {
+            this.converting = false;
+            this.next += 1;
+            Poll::Ready(Ok(this.next + channel))
+        } else {
+            this.converting = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        Pin::get_mut(self).converting = false;
+    }
+}
+
+/// Every interval ticks immediately, failing after the given number of ticks.
+fn timer(ticks: usize) -> StreamTimer<Ticks> {
+    let mut ticks = vec![Ok(()); ticks];
+    ticks.push(Err("stopped"));
+    StreamTimer::new(stream::iter(ticks))
+}
+
+#[test]
+fn single_read() {
+    let adc = MockAdc {
+        next: 0,
+        converting: false,
+    };
+    futures::pin_mut!(adc);
+    assert_eq!(block_on(adc.as_mut().read(10)), Ok(11));
+    assert_eq!(block_on(adc.as_mut().read(20)), Ok(22));
+}
+
+#[test]
+fn cancelled_read() {
+    let adc = MockAdc {
+        next: 0,
+        converting: false,
+    };
+    futures::pin_mut!(adc);
+    {
+        let read = adc.as_mut().read(10);
+        futures::pin_mut!(read);
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert_eq!(read.poll(&mut cx), Poll::Pending);
+    }
+
+    // The next read starts its own conversion instead of taking the result
+    // of the cancelled one
+    assert!(!adc.converting);
+    assert_eq!(block_on(adc.as_mut().read(20)), Ok(21));
+}
+
+#[test]
+fn samples_per_tick() {
+    let adc = MockAdc {
+        next: 0,
+        converting: false,
+    };
+    let samples = Samples::new(adc, 5, timer(3), Duration::from_millis(1));
+
+    assert_eq!(
+        block_on(samples.collect::<Vec<_>>()),
+        vec![Ok(6), Ok(7), Ok(8), Err(Error::Timer("stopped"))]
+    );
+}
+
+#[test]
+fn band_gap() {
+    assert_eq!(millivolts(0, 10, 1200, 1, 1), 0);
+    assert_eq!(millivolts(1023, 10, 1200, 1, 1), 1200);
+    assert_eq!(millivolts(1023, 10, 1200, 1, 3), 3600);
+    assert_eq!(millivolts(512, 10, 1200, 1, 3), 1801);
+    assert_eq!(millivolts(511, 9, 1200, 2, 3), 1800);
+}
+
+#[test]
+fn supply() {
+    assert_eq!(millivolts(255, 8, 3000 / 2, 1, 1), 1500);
+    assert_eq!(millivolts(255, 8, 3300 / 3, 1, 3), 3300);
+    assert_eq!(millivolts(341, 10, 3000 / 2, 2, 3), 750);
+}
@@ -0,0 +1,377 @@
+use std::{
+    cell::RefCell,
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use embrio_core::{
+    gpio::{Edge, Input},
+    timer::Timer,
+};
+use embrio_native::timer::{
+    Clock, VirtualInterval, VirtualTimeout, VirtualTimer,
+};
+use embrio_util::gpio::{
+    button::{Button, ButtonEvent, Config},
+    encoder::{Encoder, Step},
+};
+use futures::{
+    stream::{Stream, StreamExt},
+    task::noop_waker_ref,
+};
+
+/// Virtual time shared between scripted pins and timers, it only moves forward
+/// when everything is waiting.
+#[derive(Clone, Default)]
+struct World {
+    clock: Clock,
+    changes: Rc<RefCell<Vec<(Duration, usize, bool)>>>,
+}
+
+struct ScriptedPin {
+    world: World,
+    id: usize,
+    initial: bool,
+    /// When the current edge wait started.
+    edge_wait: Option<Duration>,
+}
+
+/// A timer whose timeouts can no longer be cancelled from their deadline, but
+/// only report completion a millisecond later, like a hardware timer whose
+/// interrupt has not run yet.
+struct LateTimer {
+    clock: Clock,
+}
+
+struct LateTimeout {
+    clock: Clock,
+    deadline: Duration,
+    timeout: VirtualTimeout,
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+impl World {
+    fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Add a pin that starts at `initial` and changes to each level at the
+    /// given millisecond.
+    fn pin(&self, initial: bool, script: &[(u64, bool)]) -> ScriptedPin {
+        let mut changes = self.changes.borrow_mut();
+        let id = changes.len();
+        changes.extend(script.iter().map(|&(at, level)| (ms(at), id, level)));
+        ScriptedPin {
+            world: self.clone(),
+            id,
+            initial,
+            edge_wait: None,
+        }
+    }
+
+    fn timer(&self) -> VirtualTimer {
+        self.clock.timer()
+    }
+
+    /// Jump to the next pin change or timer deadline
+    fn advance(&self) -> bool {
+        let now = self.now();
+        let next = self
+            .changes
+            .borrow()
+            .iter()
+            .map(|&(at, _, _)| at)
+            .chain(self.clock.next_deadline())
+            .filter(|&at| at > now)
+            .min();
+        match next {
+            Some(next) => {
+                self.clock.advance_to(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Run the stream until it ends or nothing is left to happen, recording
+    /// when each item was produced.
+    fn run<S: Stream + Unpin>(&self, mut stream: S) -> Vec<(u64, S::Item)> {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let mut items = Vec::new();
+        loop {
+            match stream.poll_next_unpin(&mut cx) {
+                Poll::Ready(Some(item)) => {
+                    items.push((self.now().as_millis() as u64, item))
+                }
+                Poll::Ready(None) => break,
+                Poll::Pending => {
+                    if !self.advance() {
+                        break;
+                    }
+                }
+            }
+        }
+        items
+    }
+}
+
+impl Input for ScriptedPin {
+    fn state(&self) -> bool {
+        let now = self.world.now();
+        self.world
+            .changes
+            .borrow()
+            .iter()
+            .rev()
+            .find(|&&(at, id, _)| id == self.id && at <= now)
+            .map_or(self.initial, |&(_, _, level)| level)
+    }
+
+    fn poll_level(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        level: bool,
+    ) -> Poll<()> {
+        if self.state() == level {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn poll_edge(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        edge: Edge,
+    ) -> Poll<()> {
+        let this = Pin::get_mut(self);
+        let now = this.world.now();
+        let since = *this.edge_wait.get_or_insert(now);
+        let mut level = this.initial;
+        for &(at, id, next) in this.world.changes.borrow().iter() {
+            if id != this.id || at > now {
+                continue;
+            }
+            let seen = match edge {
+                Edge::Rising => !level && next,
+                Edge::Falling => level && !next,
+                Edge::Any => level != next,
+            };
+            if seen && at > since {
+                this.edge_wait = None;
+                return Poll::Ready(());
+            }
+            level = next;
+        }
+        Poll::Pending
+    }
+
+    fn cancel_wait(self: Pin<&mut Self>) {
+        Pin::get_mut(self).edge_wait = None;
+    }
+}
+
+impl Timer for LateTimer {
+    type Error = Infallible;
+
+    type Timeout = LateTimeout;
+
+    type Interval = VirtualInterval;
+
+    fn timeout(self, duration: Duration) -> LateTimeout {
+        LateTimeout {
+            deadline: self.clock.now() + duration,
+            timeout: self.clock.timer().timeout(duration + ms(1)),
+            clock: self.clock,
+        }
+    }
+
+    fn interval(self, period: Duration) -> VirtualInterval {
+        self.clock.timer().interval(period)
+    }
+
+    fn cancel(timeout: Pin<&mut LateTimeout>) -> Option<Self> {
+        let this = Pin::get_mut(timeout);
+        if this.clock.now() >= this.deadline {
+            return None;
+        }
+        VirtualTimer::cancel(Pin::new(&mut this.timeout)).map(|_| LateTimer {
+            clock: this.clock.clone(),
+        })
+    }
+}
+
+impl Future for LateTimeout {
+    type Output = Result<LateTimer, Infallible>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let clock = self.clock.clone();
+        Pin::new(&mut self.timeout)
+            .poll(cx)
+            .map(|result| result.map(|_| LateTimer { clock }))
+    }
+}
+
+fn config() -> Config {
+    Config {
+        active_level: false,
+        debounce: ms(10),
+        long_press: ms(1000),
+    }
+}
+
+fn button(
+    world: &World,
+    script: &[(u64, bool)],
+) -> Button<ScriptedPin, VirtualTimer> {
+    Button::new(world.pin(true, script), world.timer(), config())
+}
+
+fn events<E>(items: Vec<(u64, Result<E, Infallible>)>) -> Vec<(u64, E)> {
+    items
+        .into_iter()
+        .map(|(at, item)| (at, item.unwrap()))
+        .collect()
+}
+
+#[test]
+fn button_press_release() {
+    let world = World::default();
+    let button = button(&world, &[(100, false), (300, true)]);
+    assert_eq!(
+        events(world.run(button)),
+        vec![(110, ButtonEvent::Press), (310, ButtonEvent::Release)]
+    );
+}
+
+#[test]
+fn button_bounce() {
+    let world = World::default();
+    let button = button(
+        &world,
+        &[
+            (100, false),
+            (102, true),
+            (104, false),
+            (300, true),
+            (303, false),
+            (305, true),
+        ],
+    );
+    assert_eq!(
+        events(world.run(button)),
+        vec![(110, ButtonEvent::Press), (310, ButtonEvent::Release)]
+    );
+}
+
+#[test]
+fn button_glitch() {
+    let world = World::default();
+    let button = button(&world, &[(100, false), (105, true)]);
+    assert_eq!(events(world.run(button)), vec![]);
+}
+
+#[test]
+fn button_long_press() {
+    let world = World::default();
+    let button = button(&world, &[(100, false), (2000, true)]);
+    assert_eq!(
+        events(world.run(button)),
+        vec![
+            (110, ButtonEvent::Press),
+            (1100, ButtonEvent::LongPress),
+            (2010, ButtonEvent::Release),
+        ]
+    );
+}
+
+#[test]
+fn button_release_as_long_press_completes() {
+    let world = World::default();
+    let timer = LateTimer {
+        clock: world.clock.clone(),
+    };
+    let pin = world.pin(true, &[(100, false), (1101, true)]);
+    let button = Button::new(pin, timer, config());
+    assert_eq!(
+        events(world.run(button)),
+        vec![
+            (111, ButtonEvent::Press),
+            (1102, ButtonEvent::LongPress),
+            (1113, ButtonEvent::Release),
+        ]
+    );
+}
+
+#[test]
+fn button_glitch_while_held() {
+    let world = World::default();
+    let button = button(
+        &world,
+        &[(100, false), (500, true), (505, false), (2000, true)],
+    );
+    assert_eq!(
+        events(world.run(button)),
+        vec![
+            (110, ButtonEvent::Press),
+            (1500, ButtonEvent::LongPress),
+            (2010, ButtonEvent::Release),
+        ]
+    );
+}
+
+#[test]
+fn encoder_steps() {
+    let world = World::default();
+    // Two clockwise detents (a leading b) then one counter-clockwise
+    let a = world.pin(
+        false,
+        &[
+            (10, true),
+            (30, false),
+            (50, true),
+            (70, false),
+            (110, true),
+            (130, false),
+        ],
+    );
+    let b = world.pin(
+        false,
+        &[
+            (20, true),
+            (40, false),
+            (60, true),
+            (80, false),
+            (100, true),
+            (120, false),
+        ],
+    );
+    assert_eq!(
+        world.run(Encoder::new(a, b)),
+        vec![
+            (40, Step::Clockwise),
+            (80, Step::Clockwise),
+            (130, Step::CounterClockwise)
+        ]
+    );
+}
+
+#[test]
+fn encoder_bounce() {
+    let world = World::default();
+    // `a` bounces on its first rising edge
+    let a =
+        world.pin(false, &[(10, true), (11, false), (12, true), (30, false)]);
+    let b = world.pin(false, &[(20, true), (40, false)]);
+    assert_eq!(world.run(Encoder::new(a, b)), vec![(40, Step::Clockwise)]);
+}
@@ -0,0 +1,343 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    pin::Pin,
+    rc::Rc,
+    task::{self, Poll},
+};
+
+use embrio_core::storage::{self, check, Storage};
+use embrio_util::storage::{
+    kv::{Error, Store},
+    RamStorage,
+};
+use futures::executor::block_on;
+
+#[test]
+fn set_get_remove() {
+    let mut memory = [0xFF; 512];
+    let mut store = Store::new(RamStorage::new(&mut memory, 4, 128));
+    let mut buf = [0; 16];
+
+    assert_eq!(block_on(store.get(b"missing", &mut buf)), Ok(None));
+    block_on(store.set(b"name", b"embrio")).unwrap();
+    block_on(store.set(b"empty", b"")).unwrap();
+    assert_eq!(block_on(store.get(b"name", &mut buf)), Ok(Some(6)));
+    assert_eq!(&buf[..6], b"embrio");
+    assert_eq!(block_on(store.get(b"empty", &mut buf)), Ok(Some(0)));
+
+    block_on(store.set(b"name", b"rs")).unwrap();
+    assert_eq!(block_on(store.get(b"name", &mut buf)), Ok(Some(2)));
+    assert_eq!(&buf[..2], b"rs");
+    assert_eq!(
+        block_on(store.get(b"name", &mut buf[..1])),
+        Err(Error::TooLong)
+    );
+
+    block_on(store.remove(b"name")).unwrap();
+    block_on(store.remove(b"missing")).unwrap();
+    assert_eq!(block_on(store.get(b"name", &mut buf)), Ok(None));
+    assert_eq!(block_on(store.get(b"empty", &mut buf)), Ok(Some(0)));
+
+    let mut store = Store::new(store.into_inner());
+    assert_eq!(block_on(store.get(b"name", &mut buf)), Ok(None));
+    assert_eq!(block_on(store.get(b"empty", &mut buf)), Ok(Some(0)));
+}
+
+#[test]
+fn collects_garbage() {
+    let mut memory = [0xFF; 256];
+    let mut store = Store::new(RamStorage::new(&mut memory, 4, 128));
+    block_on(store.set(b"constant", b"value")).unwrap();
+    for i in 0..1000u32 {
+        block_on(store.set(b"counter", &i.to_le_bytes())).unwrap();
+    }
+
+    let mut store = Store::new(store.into_inner());
+    let mut buf = [0; 8];
+    assert_eq!(block_on(store.get(b"counter", &mut buf)), Ok(Some(4)));
+    assert_eq!(&buf[..4], &999u32.to_le_bytes());
+    assert_eq!(block_on(store.get(b"constant", &mut buf)), Ok(Some(5)));
+    assert_eq!(&buf[..5], b"value");
+}
+
+#[test]
+fn full() {
+    let mut memory = [0xFF; 256];
+    let mut store = Store::new(RamStorage::new(&mut memory, 4, 128));
+    let mut set = 0u8;
+    loop {
+        match block_on(store.set(&[set], &[set; 20])) {
+            Ok(()) => set += 1,
+            Err(err) => {
+                assert_eq!(err, Error::Full);
+                break;
+            }
+        }
+    }
+    assert!(set > 0);
+
+    let mut buf = [0; 20];
+    for key in 0..set {
+        assert_eq!(block_on(store.get(&[key], &mut buf)), Ok(Some(20)));
+        assert_eq!(buf, [key; 20]);
+    }
+    assert_eq!(block_on(store.get(&[set], &mut buf)), Ok(None));
+
+    block_on(store.remove(&[0])).unwrap();
+    block_on(store.set(&[set], &[set; 4])).unwrap();
+}
+
+#[test]
+fn too_long() {
+    let mut memory = [0xFF; 256];
+    let mut store = Store::new(RamStorage::new(&mut memory, 4, 128));
+    assert_eq!(block_on(store.set(&[0; 256], b"")), Err(Error::TooLong));
+    assert_eq!(block_on(store.set(b"key", &[0; 128])), Err(Error::TooLong));
+}
+
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 32) as usize % n
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct PowerLoss;
+
+/// How many more bytes can be changed before power is lost, shared so it can
+/// be restored while a store still holds the storage.
+#[derive(Debug, Default)]
+struct Power {
+    budget: Option<usize>,
+    lost: bool,
+}
+
+/// RAM storage that loses power after a number of bytes have been changed,
+/// applying a prefix of the interrupted operation with its last byte partly
+/// written, and failing everything until it is restored.
+#[derive(Debug)]
+struct TornStorage {
+    memory: Vec<u8>,
+    write_size: usize,
+    erase_size: usize,
+    power: Rc<RefCell<Power>>,
+    rng: Rng,
+}
+
+impl Power {
+    fn spend(&mut self, len: usize) -> usize {
+        match self.budget {
+            Some(budget) if budget < len => {
+                self.budget = Some(0);
+                self.lost = true;
+                budget
+            }
+            Some(budget) => {
+                self.budget = Some(budget - len);
+                len
+            }
+            None => len,
+        }
+    }
+
+    fn restore(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        self.lost = false;
+    }
+}
+
+impl Storage for TornStorage {
+    type Error = PowerLoss;
+
+    fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    fn read_size(&self) -> usize {
+        1
+    }
+
+    fn write_size(&self) -> usize {
+        self.write_size
+    }
+
+    fn erase_size(&self) -> usize {
+        self.erase_size
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Poll<Result<(), storage::Error<PowerLoss>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), 1, offset, buf.len())?;
+        if this.power.borrow().lost {
+            return Poll::Ready(Err(storage::Error::Other(PowerLoss)));
+        }
+        buf.copy_from_slice(&this.memory[offset..offset + buf.len()]);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        data: &[u8],
+    ) -> Poll<Result<(), storage::Error<PowerLoss>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), this.write_size, offset, data.len())?;
+        if this.power.borrow().lost {
+            return Poll::Ready(Err(storage::Error::Other(PowerLoss)));
+        }
+        let target = &this.memory[offset..offset + data.len()];
+        assert!(
+            target.iter().all(|&byte| byte == 0xFF),
+            "write to {} which has not been erased",
+            offset
+        );
+
+        let done = this.power.borrow_mut().spend(data.len());
+        this.memory[offset..offset + done].copy_from_slice(&data[..done]);
+        if this.power.borrow().lost {
+            if done < data.len() {
+                let unchanged = this.rng.below(256) as u8;
+                this.memory[offset + done] = data[done] | unchanged;
+            }
+            return Poll::Ready(Err(storage::Error::Other(PowerLoss)));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_erase(
+        self: Pin<&mut Self>,
+        _cx: &mut task::Context<'_>,
+        offset: usize,
+        len: usize,
+    ) -> Poll<Result<(), storage::Error<PowerLoss>>> {
+        let this = Pin::get_mut(self);
+        check(this.capacity(), this.erase_size, offset, len)?;
+        if this.power.borrow().lost {
+            return Poll::Ready(Err(storage::Error::Other(PowerLoss)));
+        }
+        let done = this.power.borrow_mut().spend(len);
+        for byte in &mut this.memory[offset..offset + done] {
+            *byte = 0xFF;
+        }
+        if this.power.borrow().lost {
+            return Poll::Ready(Err(storage::Error::Other(PowerLoss)));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// What each key must read as, and what it may read as instead after an
+/// operation on it was interrupted.
+#[derive(Debug, Default)]
+struct Model {
+    values: HashMap<Vec<u8>, Vec<u8>>,
+    uncertain: HashMap<Vec<u8>, Vec<Option<Vec<u8>>>>,
+}
+
+impl Model {
+    fn check(&mut self, store: &mut Store<TornStorage>, keys: &[Vec<u8>]) {
+        for key in keys {
+            let mut buf = [0; 64];
+            let value = block_on(store.get(key, &mut buf))
+                .unwrap()
+                .map(|len| buf[..len].to_vec());
+            let expected = self.values.get(key).cloned();
+            let allowed = self.uncertain.remove(key).unwrap_or_default();
+            assert!(
+                value == expected || allowed.contains(&value),
+                "{:?} is {:?}, expected {:?} or one of {:?}",
+                key,
+                value,
+                expected,
+                allowed,
+            );
+            match value {
+                Some(value) => self.values.insert(key.clone(), value),
+                None => self.values.remove(key),
+            };
+        }
+    }
+}
+
+fn fuzz(seed: u64, write_size: usize) {
+    let mut rng = Rng(seed);
+    let keys: Vec<Vec<u8>> = (1..=8u8).map(|i| vec![i; i as usize]).collect();
+    let mut model = Model::default();
+    let power = Rc::new(RefCell::new(Power::default()));
+    let mut store = Store::new(TornStorage {
+        memory: vec![0xFF; 4 * 256],
+        write_size,
+        erase_size: 256,
+        power: power.clone(),
+        rng: Rng(!seed),
+    });
+
+    for _ in 0..200 {
+        power.borrow_mut().restore(Some(rng.below(2000)));
+        // Sometimes carry on with the same store, as after a transient error
+        if rng.below(2) == 0 {
+            store = Store::new(store.into_inner());
+        }
+
+        for _ in 0..50 {
+            let key = &keys[rng.below(keys.len())];
+            let value: Option<Vec<u8>> = if rng.below(5) == 0 {
+                None
+            } else {
+                let len = rng.below(41);
+                Some((0..len).map(|_| rng.below(256) as u8).collect())
+            };
+            let result = match &value {
+                Some(value) => block_on(store.set(key, value)),
+                None => block_on(store.remove(key)),
+            };
+            match result {
+                Ok(()) => {
+                    model.uncertain.remove(key);
+                    match value {
+                        Some(value) => model.values.insert(key.clone(), value),
+                        None => model.values.remove(key),
+                    };
+                }
+                Err(Error::Storage(storage::Error::Other(PowerLoss))) => {
+                    model.uncertain.entry(key.clone()).or_default().push(value);
+                    break;
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+
+        // Sometimes carry on without checking, so recovery can be torn too
+        if rng.below(2) == 0 {
+            power.borrow_mut().restore(None);
+            store = Store::new(store.into_inner());
+            model.check(&mut store, &keys);
+        }
+    }
+
+    power.borrow_mut().restore(None);
+    let mut store = Store::new(store.into_inner());
+    model.check(&mut store, &keys);
+}
+
+#[test]
+fn torn_writes() {
+    for seed in 1..=20 {
+        fuzz(seed, 1);
+        fuzz(seed, 4);
+    }
+}
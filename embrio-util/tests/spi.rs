@@ -0,0 +1,298 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use embrio_core::{
+    gpio::Output,
+    spi::{Bus, Device, Operation},
+};
+use embrio_util::spi::{ExclusiveDevice, SharedBus};
+use futures::{executor::block_on, future::join, pin_mut};
+
+#[derive(Debug, Eq, PartialEq)]
+enum Event {
+    Select(&'static str),
+    Deselect(&'static str),
+    Write(u8),
+    Flush,
+    Abort,
+}
+
+type Log = Rc<RefCell<Vec<Event>>>;
+
+/// Echoes back each byte plus one, a byte at a time with a pending poll
+/// between each.
+struct MockBus {
+    log: Log,
+    ready: bool,
+}
+
+struct MockCs {
+    log: Log,
+    name: &'static str,
+    state: Cell<bool>,
+}
+
+impl MockBus {
+    fn poll_byte(
+        &mut self,
+        cx: &mut Context<'_>,
+        byte: u8,
+    ) -> Poll<Result<u8, ()>> {
+        if self.ready {
+            self.ready = false;
+            self.log.borrow_mut().push(Event::Write(byte));
+            Poll::Ready(Ok(byte + 1))
+        } else {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+impl Bus for MockBus {
+    type Error = ();
+
+    fn poll_transfer(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, ()>> {
+        buf[0] = futures::ready!(Pin::get_mut(self).poll_byte(cx, buf[0]))?;
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, ()>> {
+        futures::ready!(Pin::get_mut(self).poll_byte(cx, buf[0]))?;
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, ()>> {
+        buf[0] = futures::ready!(Pin::get_mut(self).poll_byte(cx, 0))?;
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), ()>> {
+        self.log.borrow_mut().push(Event::Flush);
+        Poll::Ready(Ok(()))
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let this = Pin::get_mut(self);
+        this.ready = false;
+        this.log.borrow_mut().push(Event::Abort);
+    }
+}
+
+impl Output for MockCs {
+    fn state(&self) -> bool {
+        self.state.get()
+    }
+
+    fn set_state(&self, state: bool) {
+        if state != self.state.get() {
+            self.state.set(state);
+            self.log.borrow_mut().push(if state {
+                Event::Deselect(self.name)
+            } else {
+                Event::Select(self.name)
+            });
+        }
+    }
+}
+
+fn setup() -> (Log, MockBus, impl Fn(&'static str) -> MockCs) {
+    let log = Log::default();
+    let bus = MockBus {
+        log: log.clone(),
+        ready: false,
+    };
+    let cs_log = log.clone();
+    let cs = move |name| MockCs {
+        log: cs_log.clone(),
+        name,
+        state: Cell::new(false),
+    };
+    (log, bus, cs)
+}
+
+#[test]
+fn exclusive_transaction() {
+    let (log, bus, cs) = setup();
+    let device = ExclusiveDevice::new(bus, cs("a"));
+    pin_mut!(device);
+
+    let mut read = [0; 2];
+    let mut transfer = [5, 6];
+    block_on(device.as_mut().transaction(&mut [
+        Operation::Write(&[1, 2]),
+        Operation::Read(&mut read),
+        Operation::Transfer(&mut transfer),
+    ]))
+    .unwrap();
+
+    assert_eq!(read, [1, 1]);
+    assert_eq!(transfer, [6, 7]);
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            Event::Deselect("a"),
+            Event::Select("a"),
+            Event::Write(1),
+            Event::Write(2),
+            Event::Write(0),
+            Event::Write(0),
+            Event::Write(5),
+            Event::Write(6),
+            Event::Flush,
+            Event::Deselect("a"),
+        ]
+    );
+}
+
+#[test]
+fn shared_transactions_do_not_interleave() {
+    let (log, bus, cs) = setup();
+    let shared = SharedBus::new(bus);
+    let a = shared.device(cs("a"));
+    let b = shared.device(cs("b"));
+    pin_mut!(a, b);
+    log.borrow_mut().clear();
+
+    let (ra, rb) = block_on(join(
+        a.as_mut().write(&[1, 2, 3]),
+        b.as_mut().write(&[4, 5]),
+    ));
+    ra.unwrap();
+    rb.unwrap();
+
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            Event::Select("a"),
+            Event::Write(1),
+            Event::Write(2),
+            Event::Write(3),
+            Event::Flush,
+            Event::Deselect("a"),
+            Event::Select("b"),
+            Event::Write(4),
+            Event::Write(5),
+            Event::Flush,
+            Event::Deselect("b"),
+        ]
+    );
+}
+
+#[test]
+fn cancelled_transaction_releases_bus() {
+    let (log, bus, cs) = setup();
+    let shared = SharedBus::new(bus);
+    let a = shared.device(cs("a"));
+    let b = shared.device(cs("b"));
+    pin_mut!(a, b);
+    log.borrow_mut().clear();
+
+    {
+        let write = a.as_mut().write(&[1, 2]);
+        pin_mut!(write);
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert!(write.poll(&mut cx).is_pending());
+    }
+
+    block_on(b.as_mut().write(&[3])).unwrap();
+
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            Event::Select("a"),
+            Event::Abort,
+            Event::Deselect("a"),
+            Event::Select("b"),
+            Event::Write(3),
+            Event::Flush,
+            Event::Deselect("b"),
+        ]
+    );
+}
+
+#[test]
+fn dropped_device_releases_bus() {
+    let (log, bus, cs) = setup();
+    let shared = SharedBus::new(bus);
+    let b = shared.device(cs("b"));
+    pin_mut!(b);
+
+    {
+        let a = shared.device(cs("a"));
+        pin_mut!(a);
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert!(a.as_mut().poll_select(&mut cx).is_ready());
+    }
+    log.borrow_mut().clear();
+
+    block_on(b.as_mut().write(&[1])).unwrap();
+
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            Event::Select("b"),
+            Event::Write(1),
+            Event::Flush,
+            Event::Deselect("b"),
+        ]
+    );
+}
+
+#[test]
+fn waiting_devices_take_turns() {
+    let (log, bus, cs) = setup();
+    let shared = SharedBus::new(bus);
+    let a = shared.device(cs("a"));
+    let b = shared.device(cs("b"));
+    let c = shared.device(cs("c"));
+    pin_mut!(a, b, c);
+    log.borrow_mut().clear();
+
+    let (ra, (rb, rc)) = block_on(join(
+        a.as_mut().write(&[1]),
+        join(b.as_mut().write(&[2]), c.as_mut().write(&[3])),
+    ));
+    ra.unwrap();
+    rb.unwrap();
+    rc.unwrap();
+
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            Event::Select("a"),
+            Event::Write(1),
+            Event::Flush,
+            Event::Deselect("a"),
+            Event::Select("b"),
+            Event::Write(2),
+            Event::Flush,
+            Event::Deselect("b"),
+            Event::Select("c"),
+            Event::Write(3),
+            Event::Flush,
+            Event::Deselect("c"),
+        ]
+    );
+}
@@ -0,0 +1,61 @@
+use embrio_core::storage::{Error, Storage};
+use embrio_util::storage::{NotErased, RamStorage};
+use futures::executor::block_on;
+
+#[test]
+fn write_read_erase() {
+    let mut memory = [0xFF; 64];
+    let mut storage = RamStorage::new(&mut memory, 4, 16);
+    assert_eq!(storage.capacity(), 64);
+    assert_eq!(storage.write_size(), 4);
+    assert_eq!(storage.erase_size(), 16);
+    futures::pin_mut!(storage);
+
+    block_on(storage.as_mut().write(12, &[1, 2, 3, 4, 5, 6, 7, 8])).unwrap();
+    let mut buf = [0; 10];
+    block_on(storage.as_mut().read(11, &mut buf)).unwrap();
+    assert_eq!(buf, [0xFF, 1, 2, 3, 4, 5, 6, 7, 8, 0xFF]);
+
+    block_on(storage.as_mut().erase(0, 16)).unwrap();
+    block_on(storage.as_mut().read(11, &mut buf)).unwrap();
+    assert_eq!(buf, [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 5, 6, 7, 8, 0xFF]);
+    assert_eq!(&storage.memory()[16..20], &[5, 6, 7, 8]);
+}
+
+#[test]
+fn errors() {
+    let mut memory = [0xFF; 32];
+    let storage = RamStorage::new(&mut memory, 4, 16);
+    futures::pin_mut!(storage);
+
+    assert_eq!(
+        block_on(storage.as_mut().write(2, &[0; 4])),
+        Err(Error::Unaligned)
+    );
+    assert_eq!(
+        block_on(storage.as_mut().write(0, &[0; 3])),
+        Err(Error::Unaligned)
+    );
+    assert_eq!(
+        block_on(storage.as_mut().write(32, &[0; 4])),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        block_on(storage.as_mut().read(30, &mut [0; 4])),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        block_on(storage.as_mut().erase(8, 16)),
+        Err(Error::Unaligned)
+    );
+    assert_eq!(
+        block_on(storage.as_mut().erase(16, 32)),
+        Err(Error::OutOfBounds)
+    );
+
+    block_on(storage.as_mut().write(0, &[0; 4])).unwrap();
+    assert_eq!(
+        block_on(storage.as_mut().write(0, &[0; 4])),
+        Err(Error::Other(NotErased))
+    );
+}
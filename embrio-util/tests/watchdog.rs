@@ -0,0 +1,128 @@
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use embrio_core::watchdog::Watchdog;
+use embrio_native::timer::StreamTimer;
+use embrio_util::watchdog::{Feeder, Health, MAX_SIGNALS};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    task::noop_waker_ref,
+};
+
+type Ticks = UnboundedSender<Result<(), &'static str>>;
+
+struct MockWatchdog {
+    feeds: Rc<Cell<usize>>,
+}
+
+/// The interval ticks when a tick is sent, and ends when the sender is
+/// dropped.
+type MockTimer = StreamTimer<UnboundedReceiver<Result<(), &'static str>>>;
+
+impl Watchdog for MockWatchdog {
+    fn feed(&mut self) {
+        self.feeds.set(self.feeds.get() + 1);
+    }
+}
+
+fn feeder(
+    health: &Health,
+) -> (Feeder<'_, MockWatchdog, MockTimer>, Ticks, Rc<Cell<usize>>) {
+    let (sender, ticks) = unbounded();
+    let feeds = Rc::new(Cell::new(0));
+    let watchdog = MockWatchdog {
+        feeds: feeds.clone(),
+    };
+    let feeder = Feeder::new(
+        watchdog,
+        health,
+        StreamTimer::new(ticks),
+        Duration::from_millis(100),
+    );
+    (feeder, sender, feeds)
+}
+
+fn poll<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    Pin::new(future).poll(&mut Context::from_waker(noop_waker_ref()))
+}
+
+#[test]
+fn feeds_while_healthy() {
+    let health = Health::new();
+    let (mut feeder, ticks, feeds) = feeder(&health);
+    let first = health.register().unwrap();
+    let second = health.register().unwrap();
+
+    first.check_in();
+    second.check_in();
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 1);
+
+    // The second signal is stuck
+    first.check_in();
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 1);
+
+    // It recovers before the watchdog times out
+    second.check_in();
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 2);
+
+    // Check ins are cleared by feeding
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 2);
+
+    // A dropped signal no longer needs to check in
+    drop(second);
+    first.check_in();
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 3);
+
+    ticks.unbounded_send(Err("stopped")).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Ready(Err("stopped")));
+}
+
+#[test]
+fn ends_with_interval() {
+    let health = Health::new();
+    let (mut feeder, ticks, feeds) = feeder(&health);
+    ticks.unbounded_send(Ok(())).unwrap();
+    drop(ticks);
+    assert_eq!(poll(&mut feeder), Poll::Ready(Ok(())));
+    assert_eq!(feeds.get(), 1);
+}
+
+#[test]
+fn register_limit() {
+    let health = Health::new();
+    let mut signals: Vec<_> = (0..MAX_SIGNALS)
+        .map(|_| health.register().unwrap())
+        .collect();
+    assert!(health.register().is_none());
+
+    signals.swap_remove(3);
+    let signal = health.register().unwrap();
+    // A reused signal must check in again
+    let (mut feeder, ticks, feeds) = feeder(&health);
+    for signal in &signals {
+        signal.check_in();
+    }
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 0);
+    signal.check_in();
+    ticks.unbounded_send(Ok(())).unwrap();
+    assert_eq!(poll(&mut feeder), Poll::Pending);
+    assert_eq!(feeds.get(), 1);
+}
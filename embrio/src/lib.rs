@@ -1,5 +1,10 @@
 #![no_std]
 
+// Both chip crates define the interrupt vectors and `#[interrupt]` handlers
+#[cfg(all(feature = "nrf51", feature = "nrf52"))]
+compile_error!("the `nrf51` and `nrf52` features are mutually exclusive");
+
+extern crate embrio_ble;
 extern crate embrio_core;
 extern crate embrio_util;
 
@@ -9,18 +14,78 @@ extern crate embrio_executor;
 #[cfg(feature = "nrf51")]
 extern crate embrio_nrf51;
 
+#[cfg(feature = "nrf52")]
+extern crate embrio_nrf52;
+
 pub mod fmt {
     pub use embrio_util::{await_write, await_writeln};
 }
 
+pub mod adc {
+    pub use embrio_core::adc::Adc;
+    pub use embrio_util::adc::{millivolts, Error, Samples};
+}
+
+pub mod ble {
+    pub use embrio_ble::{
+        ad,
+        advertiser::Advertiser,
+        link, pdu,
+        radio::{Radio, Recv, Send},
+        scanner::{Report, Scanner},
+        Error,
+    };
+}
+
+pub mod display {
+    pub use embrio_util::display::{
+        glyph, Frame, Layout, Scroll, HEIGHT, MICROBIT, WIDTH,
+    };
+}
+
 pub mod gpio {
-    pub use embrio_core::gpio::Output;
+    pub use embrio_core::gpio::{Edge, Input, InputPort, Output, Port};
+    pub use embrio_util::gpio::{Button, ButtonEvent, Encoder, Step};
+}
+
+pub mod i2c {
+    pub use embrio_core::i2c::{Error, I2c, Operation};
+}
+
+pub mod pwm {
+    pub use embrio_core::pwm::Pwm;
+}
+
+pub mod rgb {
+    pub use embrio_util::rgb::{
+        blink, fade, play, Blink, Color, Fade, Light, Play, RgbPwm,
+    };
+}
+
+pub mod spi {
+    pub use embrio_core::spi::{
+        Bus, Device, Mode, Operation, Phase, Polarity, MODE_0, MODE_1, MODE_2,
+        MODE_3,
+    };
+    pub use embrio_util::spi::{
+        ExclusiveDevice, SharedBus, SharedDevice, MAX_DEVICES,
+    };
+}
+
+pub mod storage {
+    pub use embrio_core::storage::{check, Error, Storage};
+    pub use embrio_util::storage::{kv, NotErased, RamStorage};
 }
 
 pub mod timer {
     pub use embrio_core::timer::Timer;
 }
 
+pub mod watchdog {
+    pub use embrio_core::watchdog::Watchdog;
+    pub use embrio_util::watchdog::{Feeder, Health, Signal, MAX_SIGNALS};
+}
+
 pub mod io {
     pub use embrio_core::io::{void, BufRead, Cursor, Read, Write};
     pub use embrio_util::io::{
@@ -34,11 +99,36 @@ pub use embrio_executor::Executor;
 #[cfg(feature = "nrf51")]
 pub mod nrf51 {
     pub mod timer {
-        pub use embrio_nrf51::timer::Timer;
+        pub use embrio_nrf51::timer::{
+            BitMode, Builder, Channel, Error, Interval, Timeout, Timer,
+            TimerInstance, CHANNELS,
+        };
+    }
+
+    pub mod adc {
+        pub use embrio_nrf51::adc::{Adc, Converter};
+
+        pub mod mode {
+            pub use embrio_nrf51::adc::mode::{
+                millivolts, BandGap, Bits10, Bits8, Bits9, Full, OneThird,
+                Reference, Resolution, Scaling, SupplyOneHalf, SupplyOneThird,
+                TwoThirds,
+            };
+        }
+    }
+
+    pub mod clock {
+        pub use embrio_nrf51::clock::{
+            release_unused_hfclk, Clock, Hfclk, LfclkSource,
+        };
+    }
+
+    pub mod flash {
+        pub use embrio_nrf51::flash::{Flash, Partition};
     }
 
     pub mod gpio {
-        pub use embrio_nrf51::gpio::{Pin, Pins};
+        pub use embrio_nrf51::gpio::{AnyPin, Pin, Pins, Port};
 
         pub mod mode {
             pub use embrio_nrf51::gpio::mode::{
@@ -48,7 +138,97 @@ pub mod nrf51 {
         }
     }
 
+    pub mod gpiote {
+        pub use embrio_nrf51::gpiote::{
+            Channel, Channels, EventChannel, Polarity, TaskChannel, CHANNELS,
+        };
+    }
+
+    pub mod power {
+        pub use embrio_nrf51::power::{Power, ResetReason};
+    }
+
+    pub mod ppi {
+        pub use embrio_nrf51::ppi::{Channel, Channels, Event, Task, CHANNELS};
+    }
+
+    pub mod pwm {
+        pub use embrio_nrf51::pwm::{Channels, Error, Output, CHANNELS};
+    }
+
+    pub mod radio {
+        pub use embrio_nrf51::radio::{
+            Address, Config, Crc, Error, Format, Mode, Packet, Radio, Recv,
+            Send, Transceiver,
+        };
+    }
+
+    pub mod rng {
+        pub use embrio_nrf51::rng::{FillBytes, Rng};
+    }
+
+    pub mod rtc {
+        pub use embrio_nrf51::rtc::{Interval, Rtc, Timeout, FREQUENCY};
+    }
+
+    pub mod spi {
+        pub use embrio_nrf51::spi::{Master, Spi, FREQUENCY_A};
+    }
+
+    pub mod temp {
+        pub use embrio_nrf51::temp::{Read, Temp, Temperature};
+    }
+
+    pub mod twi {
+        pub use embrio_nrf51::twi::{Error, Master, Twi, FREQUENCY_A};
+    }
+
     pub mod uart {
-        pub use embrio_nrf51::uart::{Uart, BAUDRATEW};
+        pub use embrio_nrf51::uart::{
+            Config, Error, Parity, Pins, Rx, Tx, Uart, BAUDRATEW,
+        };
+    }
+
+    pub mod wdt {
+        pub use embrio_nrf51::wdt::{
+            Config, Error, Reload, Reloads, Wdt, RELOAD_REGISTERS,
+        };
+    }
+}
+
+#[cfg(feature = "nrf52")]
+pub mod nrf52 {
+    pub mod timer {
+        pub use embrio_nrf52::timer::{
+            BitMode, Builder, Channel, Error, Interval, Timeout, Timer,
+            TimerInstance, CHANNELS,
+        };
+    }
+
+    pub mod clock {
+        pub use embrio_nrf52::clock::{
+            release_unused_hfclk, Clock, Hfclk, LfclkSource,
+        };
+    }
+
+    pub mod gpio {
+        pub use embrio_nrf52::gpio::{AnyPin, Pin, Pins, Port};
+
+        pub mod mode {
+            pub use embrio_nrf52::gpio::mode::{
+                Disabled, Floating, Input, InputMode, OpenDrain, Output,
+                OutputMode, PinMode, PullDown, PullUp, PushPull,
+            };
+        }
+    }
+
+    pub mod rtc {
+        pub use embrio_nrf52::rtc::{Interval, Rtc, Timeout, FREQUENCY};
+    }
+
+    pub mod uarte {
+        pub use embrio_nrf52::uarte::{
+            Config, Error, Parity, Pins, Rx, Tx, Uarte, BAUDRATE_A,
+        };
     }
 }
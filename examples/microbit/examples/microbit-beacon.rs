@@ -0,0 +1,104 @@
+#![no_std]
+#![no_main]
+#![feature(generators)]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+// Link only imports, for panic implementation and interrupt vectors
+use {nrf51 as _, panic_abort as _};
+
+use core::{fmt::Debug, time::Duration};
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use embrio::{
+    ble::{
+        ad::{self, AdStructure},
+        link::{Channel, ADVERTISING_ACCESS_ADDRESS, ADVERTISING_CRC_INIT},
+        pdu::{self, Address, Pdu, PduType},
+        Advertiser,
+    },
+    gpio::Output,
+    Executor,
+};
+use embrio_async::embrio_async;
+use embrio_nrf51::{clock, radio::Config, EmbrioNrf51};
+use futures_util::{pin_mut, stream::Stream, StreamExt};
+
+/// A random static address, the top two bits must be set.
+const ADDRESS: [u8; 6] = [0x56, 0x34, 0x12, 0xEE, 0xFF, 0xC0];
+
+/// Toggle the top left LED after each advertising event.
+#[embrio_async]
+async fn run<E: Debug>(
+    advertiser: impl Stream<Item = Result<(), E>>,
+    led: &impl Output,
+) {
+    pin_mut!(advertiser);
+    while let Some(result) = advertiser.next().await {
+        result.unwrap();
+        led.set_state(!led.state());
+    }
+}
+
+/// Advertise as a non-connectable beacon named "embrio" every 100ms.
+#[entry]
+fn main() -> ! {
+    static mut EXECUTOR: Executor =
+        Executor::with_sleep_hook(clock::release_unused_hfclk);
+
+    let nrf51 = EmbrioNrf51::take().unwrap();
+    let EmbrioNrf51 {
+        pins,
+        mut radio,
+        mut rtc0,
+        ..
+    } = nrf51;
+    let mut col1 = pins.p0_04.output().push_pull();
+    col1.set_state(false);
+    let row1 = pins.p0_13.output().push_pull();
+
+    let mut data = [0; pdu::MAX_DATA_LEN];
+    let len = ad::encode(
+        &[
+            AdStructure::Flags(ad::BR_EDR_NOT_SUPPORTED),
+            AdStructure::CompleteLocalName("embrio"),
+        ],
+        &mut data,
+    )
+    .unwrap();
+    let mut buf = [0; pdu::MAX_LEN];
+    let len = Pdu::advertising(
+        PduType::AdvNonconnInd,
+        &Address::random(ADDRESS),
+        &data[..len],
+        &mut buf,
+    )
+    .unwrap();
+
+    let channel = Channel::ADVERTISING[0];
+    let radio = radio.init(&Config::ble(
+        ADVERTISING_ACCESS_ADDRESS,
+        ADVERTISING_CRC_INIT,
+        channel.frequency(),
+        channel.whitening_iv(),
+    ));
+    let advertiser = Advertiser::new(
+        radio,
+        &mut rtc0,
+        Duration::from_millis(100),
+        &buf[..len],
+    )
+    .unwrap();
+    EXECUTOR.block_on(run(advertiser, &row1));
+    unreachable!()
+}
+
+#[exception]
+fn HardFault(ef: &ExceptionFrame) -> ! {
+    panic!("HardFault at {:#?}", ef);
+}
+
+#[exception]
+fn DefaultHandler(irqn: i16) {
+    panic!("Unhandled exception (IRQn = {})", irqn);
+}
@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+#![feature(generators)]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+// Link only imports, for panic implementation and interrupt vectors
+use {nrf51 as _, panic_abort as _};
+
+use core::time::Duration;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use embrio::{gpio::Input, Executor};
+use embrio_async::embrio_async;
+use embrio_microbit::{
+    display::{glyph, Frame},
+    Board,
+};
+use embrio_nrf51::{clock, rtc::Rtc, EmbrioNrf51};
+
+const HEART: Frame = Frame::new([0b01010, 0b11111, 0b11111, 0b01110, 0b00100]);
+
+/// Scroll a greeting, then show a heart if button A is held or the letter B
+/// if button B is.
+#[embrio_async]
+async fn run(mut board: Board<'_>, mut rtc: Rtc<'_, nrf51::RTC0>) {
+    loop {
+        let step = Duration::from_millis(120);
+        let _ = board.display.scroll(&mut rtc, "Hello embrio", step).await;
+
+        let second = Duration::from_secs(1);
+        if board.buttons.a.is_low() {
+            let _ = board.display.show(&mut rtc, HEART, second).await;
+        } else if board.buttons.b.is_low() {
+            let _ = board.display.show(&mut rtc, glyph('B'), second).await;
+        }
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    static mut EXECUTOR: Executor =
+        Executor::with_sleep_hook(clock::release_unused_hfclk);
+
+    let EmbrioNrf51 { pins, rtc0, .. } = EmbrioNrf51::take().unwrap();
+    EXECUTOR.block_on(run(Board::new(pins), rtc0));
+    unreachable!()
+}
+
+#[exception]
+fn HardFault(ef: &ExceptionFrame) -> ! {
+    panic!("HardFault at {:#?}", ef);
+}
+
+#[exception]
+fn DefaultHandler(irqn: i16) {
+    panic!("Unhandled exception (IRQn = {})", irqn);
+}
@@ -7,17 +7,30 @@
 use {nrf51 as _, panic_abort as _};
 
 use cortex_m_rt::{entry, exception, ExceptionFrame};
-use embrio_nrf51::{uart::BAUDRATE_A, EmbrioNrf51};
+use embrio_nrf51::{
+    uart::{Config, Pins},
+    EmbrioNrf51,
+};
 
 #[entry]
 fn main() -> ! {
+    // The interrupt keeps using these after `init` returns, so they must be
+    // 'static
+    static mut TX_BUFFER: [u8; 64] = [0; 64];
+    static mut RX_BUFFER: [u8; 64] = [0; 64];
+
     let mut nrf51 = EmbrioNrf51::take().unwrap();
-    let mut txpin = nrf51.pins.24.output().push_pull();
-    let mut rxpin = nrf51.pins.25.input().floating();
+    let mut txpin = nrf51.pins.p0_24.output().push_pull().degrade();
+    let mut rxpin = nrf51.pins.p0_25.input().floating().degrade();
+    let pins = Pins {
+        tx: &mut txpin,
+        rx: &mut rxpin,
+        flow_control: None,
+    };
     let (tx, rx) =
         nrf51
             .uart
-            .init(&mut txpin, &mut rxpin, BAUDRATE_A::BAUD115200);
+            .init(pins, Config::default(), TX_BUFFER, RX_BUFFER);
     unsafe { hello::main(rx, tx) }.unwrap();
     unreachable!()
 }
@@ -0,0 +1,107 @@
+#![no_std]
+#![no_main]
+#![feature(generators)]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+// Link only imports, for panic implementation and interrupt vectors
+use {nrf51 as _, panic_abort as _};
+
+use core::time::Duration;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use embrio::{
+    gpio::{Input, Output},
+    timer::Timer,
+    Executor,
+};
+use embrio_async::embrio_async;
+use embrio_nrf51::{
+    clock,
+    radio::{Address, Config, Format, Packet, Transceiver},
+    rtc::Rtc,
+    EmbrioNrf51,
+};
+
+const ADDRESS: Address = Address {
+    base: 0xE7E7_E7E7,
+    prefix: 0xE7,
+    base_len: 4,
+};
+
+/// Wait for the next counter value, ignoring anything else.
+#[embrio_async]
+async fn receive(radio: &mut Transceiver<'_, '_>, rx: &mut [u8]) -> u8 {
+    loop {
+        if let Ok(received) = radio.recv(rx).await {
+            if received.payload.len() == 1 {
+                return received.payload[0].wrapping_add(1);
+            }
+        }
+    }
+}
+
+/// Pass a counter back and forth between two boards, toggling the top left
+/// LED on each hop. Hold button A while resetting one board to serve first.
+#[embrio_async]
+async fn run(
+    mut radio: Transceiver<'_, '_>,
+    mut rtc: Rtc<'_, nrf51::RTC0>,
+    led: &impl Output,
+    serve: bool,
+) {
+    let mut tx = [0; 8];
+    let mut rx = [0; 256];
+    let mut count = 0u8;
+
+    if !serve {
+        count = receive(&mut radio, &mut rx).await;
+    }
+
+    loop {
+        led.set_state(!led.state());
+        let _ = (&mut rtc).timeout(Duration::from_millis(500)).await;
+
+        let packet = Packet {
+            s0: 0,
+            s1: 0,
+            payload: &[count],
+        };
+        let len = Format::NRF.encode(&packet, &mut tx).unwrap();
+        radio.send(&tx[..len]).await.unwrap();
+
+        count = receive(&mut radio, &mut rx).await;
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    static mut EXECUTOR: Executor =
+        Executor::with_sleep_hook(clock::release_unused_hfclk);
+
+    let nrf51 = EmbrioNrf51::take().unwrap();
+    let EmbrioNrf51 {
+        pins,
+        mut radio,
+        rtc0,
+        ..
+    } = nrf51;
+    let mut col1 = pins.p0_04.output().push_pull();
+    col1.set_state(false);
+    let row1 = pins.p0_13.output().push_pull();
+    let button_a = pins.p0_17.input().floating();
+
+    let radio = radio.init(&Config::nrf(ADDRESS));
+    EXECUTOR.block_on(run(radio, rtc0, &row1, !button_a.state()));
+    unreachable!()
+}
+
+#[exception]
+fn HardFault(ef: &ExceptionFrame) -> ! {
+    panic!("HardFault at {:#?}", ef);
+}
+
+#[exception]
+fn DefaultHandler(irqn: i16) {
+    panic!("Unhandled exception (IRQn = {})", irqn);
+}
@@ -7,17 +7,29 @@
 use {nrf51 as _, panic_abort as _};
 
 use cortex_m_rt::{entry, exception, ExceptionFrame};
-use embrio_nrf51::{uart::BAUDRATE_A, EmbrioNrf51};
+use embrio_nrf51::{
+    uart::{Config, Pins},
+    EmbrioNrf51,
+};
+use embrio_pca10031::Board;
 
 #[entry]
 fn main() -> ! {
-    let mut nrf51 = EmbrioNrf51::take().unwrap();
-    let mut txpin = nrf51.pins.9.output().push_pull();
-    let mut rxpin = nrf51.pins.11.input().floating();
-    let (tx, rx) =
-        nrf51
-            .uart
-            .init(&mut txpin, &mut rxpin, BAUDRATE_A::BAUD115200);
+    // The interrupt keeps using these after `init` returns, so they must be
+    // 'static
+    static mut TX_BUFFER: [u8; 64] = [0; 64];
+    static mut RX_BUFFER: [u8; 64] = [0; 64];
+
+    let EmbrioNrf51 { pins, mut uart, .. } = EmbrioNrf51::take().unwrap();
+    let board = Board::new(pins);
+    let mut txpin = board.uart.tx.degrade();
+    let mut rxpin = board.uart.rx.degrade();
+    let pins = Pins {
+        tx: &mut txpin,
+        rx: &mut rxpin,
+        flow_control: None,
+    };
+    let (tx, rx) = uart.init(pins, Config::default(), TX_BUFFER, RX_BUFFER);
     unsafe { hello::main(rx, tx) }.unwrap();
     unreachable!()
 }
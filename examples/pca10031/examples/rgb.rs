@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+#![feature(generators)]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+// Link only imports, for panic implementation and interrupt vectors
+use {nrf51 as _, panic_abort as _};
+
+use core::time::Duration;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use embrio::Executor;
+use embrio_async::embrio_async;
+use embrio_nrf51::{clock, rtc::Rtc, EmbrioNrf51};
+use embrio_pca10031::{
+    effects::{blink, play, Color},
+    Board, RgbLed,
+};
+
+/// Blink through red, green and blue forever.
+#[embrio_async]
+async fn run(mut led: RgbLed<'_>, mut rtc: Rtc<'_, nrf51::RTC0>) {
+    let step = Duration::from_millis(250);
+    loop {
+        for &color in &[Color::RED, Color::GREEN, Color::BLUE] {
+            let _ = play(&mut led, &mut rtc, blink(color, 3), step).await;
+        }
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    static mut EXECUTOR: Executor =
+        Executor::with_sleep_hook(clock::release_unused_hfclk);
+
+    let EmbrioNrf51 { pins, rtc0, .. } = EmbrioNrf51::take().unwrap();
+    EXECUTOR.block_on(run(Board::new(pins).led, rtc0));
+    unreachable!()
+}
+
+#[exception]
+fn HardFault(ef: &ExceptionFrame) -> ! {
+    panic!("HardFault at {:#?}", ef);
+}
+
+#[exception]
+fn DefaultHandler(irqn: i16) {
+    panic!("Unhandled exception (IRQn = {})", irqn);
+}
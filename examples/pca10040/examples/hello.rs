@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+// workaround https://github.com/rust-embedded/cortex-m-rt/issues/225
+#![allow(clippy::missing_safety_doc)]
+
+// Link only imports, for panic implementation and interrupt vectors
+use {nrf52832_pac as _, panic_abort as _};
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use embrio_nrf52::{
+    uarte::{Config, Pins},
+    EmbrioNrf52,
+};
+
+#[entry]
+fn main() -> ! {
+    // EasyDMA keeps using these after `init` returns, so they must be 'static
+    static mut TX_BUFFER: [u8; 64] = [0; 64];
+    static mut RX_BUFFER: [u8; 64] = [0; 64];
+
+    let mut nrf52 = EmbrioNrf52::take().unwrap();
+    // Connected to the interface MCU's virtual COM port on the DK
+    let mut txpin = nrf52.pins.p0_06.output().push_pull().degrade();
+    let mut rxpin = nrf52.pins.p0_08.input().floating().degrade();
+    let pins = Pins {
+        tx: &mut txpin,
+        rx: &mut rxpin,
+        flow_control: None,
+    };
+    let (tx, rx) =
+        nrf52
+            .uarte
+            .init(pins, Config::default(), TX_BUFFER, RX_BUFFER);
+    unsafe { hello::main(rx, tx) }.unwrap();
+    unreachable!()
+}
+
+#[exception]
+fn HardFault(ef: &ExceptionFrame) -> ! {
+    panic!("HardFault at {:#?}", ef);
+}
+
+#[exception]
+fn DefaultHandler(irqn: i16) {
+    panic!("Unhandled exception (IRQn = {})", irqn);
+}